@@ -0,0 +1,99 @@
+//! # Jump Point Search Benchmark
+//!
+//! Headless comparison of `astar_8dir` and `jps` on a generated 200×200 cave.
+//! Prints node expansions and wall-clock time for a batch of random queries,
+//! and checks that both algorithms agree on the path cost.
+//!
+//! Run with `cargo run --release --example jps_benchmark`.
+
+use std::time::{Duration, Instant};
+
+use jengine::pathfinding::prelude::{astar_8dir_with_stats, jps_with_stats};
+use jengine::rng::Rng;
+
+const MAP_W: i32 = 200;
+const MAP_H: i32 = 200;
+const QUERIES: usize = 200;
+const MAX_ITERATIONS: usize = 1_000_000;
+
+/// Classic cellular-automata cave: 45% random fill, then smooth 4 times.
+fn generate_cave(rng: &mut Rng) -> Vec<bool> {
+    let idx = |x: i32, y: i32| (y * MAP_W + x) as usize;
+    let mut walls: Vec<bool> = (0..MAP_W * MAP_H).map(|_| rng.chance(0.45)).collect();
+    for _ in 0..4 {
+        let mut next = walls.clone();
+        for y in 0..MAP_H {
+            for x in 0..MAP_W {
+                let mut n = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let (nx, ny) = (x + dx, y + dy);
+                        if nx < 0 || ny < 0 || nx >= MAP_W || ny >= MAP_H || walls[idx(nx, ny)] {
+                            n += 1;
+                        }
+                    }
+                }
+                next[idx(x, y)] = n >= 5;
+            }
+        }
+        walls = next;
+    }
+    walls
+}
+
+fn path_cost(path: &[(i32, i32)]) -> i32 {
+    path.windows(2)
+        .map(|w| if w[0].0 != w[1].0 && w[0].1 != w[1].1 { 14 } else { 10 })
+        .sum()
+}
+
+fn main() {
+    let mut rng = Rng::new(0xCAFE);
+    let walls = generate_cave(&mut rng);
+    let passable = |x: i32, y: i32| !walls[(y * MAP_W + x) as usize];
+
+    let floor: Vec<(i32, i32)> = (0..MAP_H)
+        .flat_map(|y| (0..MAP_W).map(move |x| (x, y)))
+        .filter(|&(x, y)| passable(x, y))
+        .collect();
+    println!("{MAP_W}x{MAP_H} cave, {} floor tiles, {QUERIES} queries\n", floor.len());
+
+    let (mut a_nodes, mut j_nodes) = (0usize, 0usize);
+    let (mut a_time, mut j_time) = (Duration::ZERO, Duration::ZERO);
+    let mut found = 0;
+
+    for _ in 0..QUERIES {
+        let start = floor[rng.range(0, floor.len() as i32) as usize];
+        let goal = floor[rng.range(0, floor.len() as i32) as usize];
+
+        let t = Instant::now();
+        let (a_path, a_stats) = astar_8dir_with_stats(start, goal, MAP_W, MAP_H, passable, MAX_ITERATIONS);
+        a_time += t.elapsed();
+
+        let t = Instant::now();
+        let (j_path, j_stats) = jps_with_stats(start, goal, MAP_W, MAP_H, passable, MAX_ITERATIONS);
+        j_time += t.elapsed();
+
+        a_nodes += a_stats.expanded;
+        j_nodes += j_stats.expanded;
+
+        match (a_path, j_path) {
+            (Some(a), Some(j)) => {
+                assert_eq!(path_cost(&a), path_cost(&j), "cost mismatch {start:?} -> {goal:?}");
+                found += 1;
+            }
+            (None, None) => {}
+            _ => panic!("reachability mismatch {start:?} -> {goal:?}"),
+        }
+    }
+
+    println!("{found} of {QUERIES} goals reachable, path costs identical\n");
+    println!("{:<12}{:>16}{:>14}", "algorithm", "nodes expanded", "time");
+    println!("{:<12}{:>16}{:>14.2?}", "astar_8dir", a_nodes, a_time);
+    println!("{:<12}{:>16}{:>14.2?}", "jps", j_nodes, j_time);
+    println!(
+        "\nJPS expanded {:.1}x fewer nodes and ran {:.1}x faster.",
+        a_nodes as f64 / j_nodes.max(1) as f64,
+        a_time.as_secs_f64() / j_time.as_secs_f64().max(1e-9),
+    );
+}
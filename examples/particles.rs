@@ -185,8 +185,8 @@ impl ParticleDemo {
 
 impl Game for ParticleDemo {
     fn on_enter(&mut self, engine: &mut jEngine) {
-        let sw = engine.renderer.window.inner_size().width as f32;
-        let sh = engine.renderer.window.inner_size().height as f32;
+        let sw = engine.renderer.size().width as f32;
+        let sh = engine.renderer.size().height as f32;
         // Center camera so world coordinates match screen coordinates (0,0 is top-left)
         engine.set_camera_pos(sw * 0.5, sh * 0.5);
     }
@@ -200,8 +200,8 @@ impl Game for ParticleDemo {
         }
 
         // --- Continuous Fire at bottom ---
-        let sw = engine.renderer.window.inner_size().width as f32;
-        let sh = engine.renderer.window.inner_size().height as f32;
+        let sw = engine.renderer.size().width as f32;
+        let sh = engine.renderer.size().height as f32;
         self.spawn_fire_and_smoke(sw * 0.5, sh * 0.85);
 
         // --- Triggered effects ---
@@ -271,8 +271,8 @@ impl Game for ParticleDemo {
 
         engine.clear();
 
-        let sw = engine.renderer.window.inner_size().width as f32;
-        let sh = engine.renderer.window.inner_size().height as f32;
+        let sw = engine.renderer.size().width as f32;
+        let sh = engine.renderer.size().height as f32;
 
         // Set dark navy background using the tile grid (Pass 1)
         // This ensures particles (Pass 3) are drawn ON TOP of the background.
@@ -85,8 +85,8 @@ impl Game for StressTest {
 
     fn update(&mut self, engine: &mut jEngine) {
         let dt = engine.dt();
-        let sw = engine.renderer.window.inner_size().width as f32;
-        let sh = engine.renderer.window.inner_size().height as f32;
+        let sw = engine.renderer.size().width as f32;
+        let sh = engine.renderer.size().height as f32;
 
         if self.actions.is_pressed(StressAction::Quit, &engine.input) {
             engine.play_sound("UI_click");
@@ -0,0 +1,365 @@
+// =============================================================================
+// AI.RS — Behaviour trees and utility selection for monster decisions
+//
+// Trees are built from `Node`s whose leaves are closures over the ECS `World`.
+// They are stateless and re-evaluated from the root every tick; anything an
+// agent needs to remember between ticks lives in its `Blackboard` component.
+//
+// Archetypes can be described in JSON (`NodeDef`) and built against a
+// `BehaviorLibrary` of named conditions, actions and scorers, so new monster
+// types are data rather than code:
+//
+//   {
+//     "goblin": { "selector": [
+//       { "sequence": [ { "condition": "sees_player" }, { "action": "step_toward_player" } ] },
+//       { "action": "wander" }
+//     ] }
+//   }
+// =============================================================================
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::Deserialize;
+
+use crate::ecs::{Entity, World};
+
+/// Result of ticking a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    /// The action is still in progress; try again next tick.
+    Running,
+}
+
+pub type ConditionFn = Rc<dyn Fn(&World, Entity) -> bool>;
+pub type ActionFn = Rc<dyn Fn(&mut World, Entity) -> Status>;
+pub type ScoreFn = Rc<dyn Fn(&World, Entity) -> f32>;
+
+// ── Blackboard ───────────────────────────────────────────────────────────────
+
+/// A value stored on an agent's blackboard.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BbValue {
+    Bool(bool),
+    Int(i64),
+    Float(f32),
+    Pos(i32, i32),
+    Entity(Entity),
+}
+
+/// Per-entity memory for behaviour trees.  Inserted automatically by
+/// `run_brains` on any entity that has a `Brain` but no blackboard yet.
+#[derive(Debug, Clone, Default)]
+pub struct Blackboard {
+    /// Number of times this agent's brain has been ticked.
+    pub ticks: u64,
+    values: HashMap<String, BbValue>,
+}
+
+impl Blackboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<BbValue> {
+        self.values.get(key).copied()
+    }
+
+    pub fn set(&mut self, key: &str, value: BbValue) {
+        self.values.insert(key.to_string(), value);
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<BbValue> {
+        self.values.remove(key)
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key)? { BbValue::Bool(v) => Some(v), _ => None }
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.get(key)? { BbValue::Int(v) => Some(v), _ => None }
+    }
+
+    pub fn get_float(&self, key: &str) -> Option<f32> {
+        match self.get(key)? { BbValue::Float(v) => Some(v), _ => None }
+    }
+
+    pub fn get_pos(&self, key: &str) -> Option<(i32, i32)> {
+        match self.get(key)? { BbValue::Pos(x, y) => Some((x, y)), _ => None }
+    }
+
+    pub fn get_entity(&self, key: &str) -> Option<Entity> {
+        match self.get(key)? { BbValue::Entity(e) => Some(e), _ => None }
+    }
+}
+
+// ── Nodes ────────────────────────────────────────────────────────────────────
+
+/// A behaviour tree node.
+pub enum Node {
+    /// Ticks children in order; fails or runs at the first child that does.
+    Sequence(Vec<Node>),
+    /// Ticks children in order; succeeds or runs at the first child that does.
+    Selector(Vec<Node>),
+    /// Leaf: `Success` if the predicate holds, `Failure` otherwise.
+    Condition(ConditionFn),
+    /// Leaf: performs work and reports its own status.
+    Action(ActionFn),
+    /// Swaps `Success` and `Failure`; `Running` passes through.
+    Invert(Box<Node>),
+    /// Reports `Success` whenever the child finishes, even if it failed.
+    Succeed(Box<Node>),
+    /// Fails without ticking the child until `ticks` brain ticks have passed
+    /// since the child last succeeded.  The timestamp lives on the blackboard
+    /// under `key`.
+    Cooldown { key: String, ticks: u64, child: Box<Node> },
+    /// Scores every option and ticks the best one with a positive score.
+    /// Fails if no option scores above zero.
+    Utility(Vec<(ScoreFn, Node)>),
+}
+
+impl Node {
+    pub fn sequence(children: Vec<Node>) -> Self { Node::Sequence(children) }
+    pub fn selector(children: Vec<Node>) -> Self { Node::Selector(children) }
+
+    pub fn condition(f: impl Fn(&World, Entity) -> bool + 'static) -> Self {
+        Node::Condition(Rc::new(f))
+    }
+
+    pub fn action(f: impl Fn(&mut World, Entity) -> Status + 'static) -> Self {
+        Node::Action(Rc::new(f))
+    }
+
+    pub fn invert(child: Node) -> Self { Node::Invert(Box::new(child)) }
+    pub fn succeed(child: Node) -> Self { Node::Succeed(Box::new(child)) }
+
+    pub fn cooldown(key: &str, ticks: u64, child: Node) -> Self {
+        Node::Cooldown { key: key.to_string(), ticks, child: Box::new(child) }
+    }
+
+    pub fn utility(options: Vec<(ScoreFn, Node)>) -> Self { Node::Utility(options) }
+
+    /// Evaluate this node for `entity`.
+    pub fn tick(&self, world: &mut World, entity: Entity) -> Status {
+        match self {
+            Node::Sequence(children) => {
+                for child in children {
+                    match child.tick(world, entity) {
+                        Status::Success => continue,
+                        other => return other,
+                    }
+                }
+                Status::Success
+            }
+            Node::Selector(children) => {
+                for child in children {
+                    match child.tick(world, entity) {
+                        Status::Failure => continue,
+                        other => return other,
+                    }
+                }
+                Status::Failure
+            }
+            Node::Condition(f) => {
+                if f(world, entity) { Status::Success } else { Status::Failure }
+            }
+            Node::Action(f) => f(world, entity),
+            Node::Invert(child) => match child.tick(world, entity) {
+                Status::Success => Status::Failure,
+                Status::Failure => Status::Success,
+                Status::Running => Status::Running,
+            },
+            Node::Succeed(child) => match child.tick(world, entity) {
+                Status::Running => Status::Running,
+                _ => Status::Success,
+            },
+            Node::Cooldown { key, ticks, child } => {
+                let now = world.get::<Blackboard>(entity).map_or(0, |bb| bb.ticks);
+                let last = world.get::<Blackboard>(entity).and_then(|bb| bb.get_int(key));
+                if let Some(last) = last
+                    && now < last as u64 + ticks
+                {
+                    return Status::Failure;
+                }
+                let status = child.tick(world, entity);
+                if status == Status::Success
+                    && let Some(bb) = world.get_mut::<Blackboard>(entity)
+                {
+                    bb.set(key, BbValue::Int(now as i64));
+                }
+                status
+            }
+            Node::Utility(options) => {
+                let best = options
+                    .iter()
+                    .map(|(score, node)| (score(world, entity), node))
+                    .filter(|(s, _)| *s > 0.0)
+                    // First option wins ties, so ordering in data is meaningful.
+                    .fold(None, |best: Option<(f32, &Node)>, (s, n)| match best {
+                        Some((bs, _)) if bs >= s => best,
+                        _ => Some((s, n)),
+                    });
+                match best {
+                    Some((_, node)) => node.tick(world, entity),
+                    None => Status::Failure,
+                }
+            }
+        }
+    }
+}
+
+// ── Brain component & runner ─────────────────────────────────────────────────
+
+/// Component: the behaviour tree that drives this entity.
+///
+/// Trees are reference counted so every entity of an archetype shares one.
+#[derive(Clone)]
+pub struct Brain {
+    pub tree: Rc<Node>,
+}
+
+impl Brain {
+    pub fn new(tree: Node) -> Self {
+        Self { tree: Rc::new(tree) }
+    }
+}
+
+/// Tick the brain of every entity that has one.
+///
+/// Call once per game turn (or fixed tick) from `update`.  Entities are
+/// visited in ascending id order so results are deterministic.
+pub fn run_brains(world: &mut World) {
+    let mut agents: Vec<(Entity, Rc<Node>)> = world
+        .query::<Brain>()
+        .map(|(e, b)| (e, Rc::clone(&b.tree)))
+        .collect();
+    agents.sort_by_key(|(e, _)| e.id());
+
+    for (entity, tree) in agents {
+        // An earlier agent may have despawned this one.
+        if !world.is_alive(entity) {
+            continue;
+        }
+        match world.get_mut::<Blackboard>(entity) {
+            Some(bb) => bb.ticks += 1,
+            None => world.insert(entity, Blackboard { ticks: 1, ..Blackboard::default() }),
+        }
+        tree.tick(world, entity);
+    }
+}
+
+// ── Data-driven archetypes ───────────────────────────────────────────────────
+
+/// Serializable description of a behaviour tree.  Leaves refer to closures
+/// registered by name in a `BehaviorLibrary`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeDef {
+    Sequence(Vec<NodeDef>),
+    Selector(Vec<NodeDef>),
+    Condition(String),
+    Action(String),
+    Invert(Box<NodeDef>),
+    Succeed(Box<NodeDef>),
+    Cooldown { key: String, ticks: u64, child: Box<NodeDef> },
+    Utility(Vec<UtilityDef>),
+}
+
+/// One option of a `NodeDef::Utility` selector.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UtilityDef {
+    /// Name of a registered scorer.
+    pub score: String,
+    pub node: NodeDef,
+}
+
+/// Named conditions, actions and scorers, plus the archetype trees built from them.
+#[derive(Default)]
+pub struct BehaviorLibrary {
+    conditions: HashMap<String, ConditionFn>,
+    actions: HashMap<String, ActionFn>,
+    scorers: HashMap<String, ScoreFn>,
+    archetypes: HashMap<String, Rc<Node>>,
+}
+
+impl BehaviorLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_condition(&mut self, name: &str, f: impl Fn(&World, Entity) -> bool + 'static) {
+        self.conditions.insert(name.to_string(), Rc::new(f));
+    }
+
+    pub fn register_action(&mut self, name: &str, f: impl Fn(&mut World, Entity) -> Status + 'static) {
+        self.actions.insert(name.to_string(), Rc::new(f));
+    }
+
+    pub fn register_scorer(&mut self, name: &str, f: impl Fn(&World, Entity) -> f32 + 'static) {
+        self.scorers.insert(name.to_string(), Rc::new(f));
+    }
+
+    /// Resolve a `NodeDef` against the registered leaves.
+    pub fn build(&self, def: &NodeDef) -> Result<Node, String> {
+        Ok(match def {
+            NodeDef::Sequence(children) => Node::Sequence(self.build_all(children)?),
+            NodeDef::Selector(children) => Node::Selector(self.build_all(children)?),
+            NodeDef::Condition(name) => Node::Condition(
+                self.conditions.get(name).cloned().ok_or_else(|| format!("unknown condition '{name}'"))?,
+            ),
+            NodeDef::Action(name) => Node::Action(
+                self.actions.get(name).cloned().ok_or_else(|| format!("unknown action '{name}'"))?,
+            ),
+            NodeDef::Invert(child) => Node::Invert(Box::new(self.build(child)?)),
+            NodeDef::Succeed(child) => Node::Succeed(Box::new(self.build(child)?)),
+            NodeDef::Cooldown { key, ticks, child } => Node::Cooldown {
+                key: key.clone(),
+                ticks: *ticks,
+                child: Box::new(self.build(child)?),
+            },
+            NodeDef::Utility(options) => Node::Utility(
+                options
+                    .iter()
+                    .map(|o| {
+                        let score = self.scorers.get(&o.score).cloned()
+                            .ok_or_else(|| format!("unknown scorer '{}'", o.score))?;
+                        Ok((score, self.build(&o.node)?))
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+        })
+    }
+
+    fn build_all(&self, defs: &[NodeDef]) -> Result<Vec<Node>, String> {
+        defs.iter().map(|d| self.build(d)).collect()
+    }
+
+    /// Register a named archetype from a tree definition.
+    pub fn add_archetype(&mut self, name: &str, def: &NodeDef) -> Result<(), String> {
+        let tree = self.build(def)?;
+        self.archetypes.insert(name.to_string(), Rc::new(tree));
+        Ok(())
+    }
+
+    /// Load archetypes from a JSON object mapping names to tree definitions.
+    pub fn load_archetypes_json(&mut self, json: &str) -> Result<(), String> {
+        let defs: HashMap<String, NodeDef> =
+            serde_json::from_str(json).map_err(|e| format!("invalid behaviour JSON: {e}"))?;
+        for (name, def) in &defs {
+            self.add_archetype(name, def).map_err(|e| format!("archetype '{name}': {e}"))?;
+        }
+        Ok(())
+    }
+
+    /// A `Brain` component sharing the named archetype's tree.
+    pub fn brain(&self, archetype: &str) -> Option<Brain> {
+        self.archetypes.get(archetype).map(|tree| Brain { tree: Rc::clone(tree) })
+    }
+}
@@ -0,0 +1,482 @@
+// =============================================================================
+// DICE.RS — Dice notation parser and evaluator
+//
+// Parses expressions such as "2d6+3", "1d20 adv", "4d6dl1" or "(1d8+2)*2"
+// into an AST, rolls them with the engine `Rng`, and reports the exact
+// minimum, maximum and average without sampling.
+//
+// Grammar (whitespace is ignored between tokens):
+//
+//   expr   := term (('+' | '-') term)*
+//   term   := unary ('*' unary)*
+//   unary  := '-' unary | atom
+//   atom   := NUMBER | dice | '(' expr ')'
+//   dice   := [NUMBER] 'd' (NUMBER | '%') [keep] [adv]
+//   keep   := ('kh' | 'kl' | 'dh' | 'dl' | 'k') NUMBER
+//   adv    := 'adv' | 'dis'
+//
+// `adv` rolls the whole group twice and keeps the higher total; `dis` keeps
+// the lower.  `Dice` (de)serializes as its notation string, so data files can
+// write `"damage": "2d6+3"` directly.
+// =============================================================================
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::rng::Rng;
+
+/// Largest number of dice allowed in one group.
+pub const MAX_DICE: u32 = 100;
+/// Largest number of sides allowed on a die.
+pub const MAX_SIDES: u32 = 1000;
+
+/// Which dice of a group count towards its total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    All,
+    Highest(u32),
+    Lowest(u32),
+}
+
+/// Roll-twice modifier for a dice group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Advantage {
+    None,
+    /// Roll twice, keep the higher total.
+    Advantage,
+    /// Roll twice, keep the lower total.
+    Disadvantage,
+}
+
+/// A group of identical dice, e.g. `4d6kh3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiceGroup {
+    pub count: u32,
+    pub sides: u32,
+    pub keep: Keep,
+    pub advantage: Advantage,
+}
+
+/// Parsed dice expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(i64),
+    Dice(DiceGroup),
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+/// A parse error with the byte offset where it was detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceError {
+    pub pos: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.pos)
+    }
+}
+
+impl std::error::Error for DiceError {}
+
+// ── Dice ─────────────────────────────────────────────────────────────────────
+
+/// A parsed dice expression together with its source notation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dice {
+    source: String,
+    expr: Expr,
+}
+
+impl Dice {
+    pub fn parse(s: &str) -> Result<Self, DiceError> {
+        let mut p = Parser { src: s.as_bytes(), pos: 0 };
+        let expr = p.expr()?;
+        p.skip_ws();
+        if p.pos < p.src.len() {
+            return Err(p.error(format!("unexpected '{}'", p.src[p.pos] as char)));
+        }
+        Ok(Self { source: s.trim().to_string(), expr })
+    }
+
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// Roll the expression.
+    pub fn roll(&self, rng: &mut Rng) -> i64 {
+        eval(&self.expr, rng)
+    }
+
+    /// Smallest possible result.
+    pub fn min(&self) -> i64 {
+        bounds(&self.expr).0
+    }
+
+    /// Largest possible result.
+    pub fn max(&self) -> i64 {
+        bounds(&self.expr).1
+    }
+
+    /// Exact expected value.
+    pub fn average(&self) -> f64 {
+        average(&self.expr)
+    }
+}
+
+impl FromStr for Dice {
+    type Err = DiceError;
+    fn from_str(s: &str) -> Result<Self, DiceError> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for Dice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Serialize for Dice {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Dice {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(d)?;
+        Dice::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parse and roll `notation` in one step.
+pub fn roll(notation: &str, rng: &mut Rng) -> Result<i64, DiceError> {
+    Ok(Dice::parse(notation)?.roll(rng))
+}
+
+// ── Parser ───────────────────────────────────────────────────────────────────
+
+struct Parser<'a> {
+    src: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<String>) -> DiceError {
+        DiceError { pos: self.pos, message: message.into() }
+    }
+
+    fn skip_ws(&mut self) {
+        while self.pos < self.src.len() && self.src[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_ws();
+        self.src.get(self.pos).map(u8::to_ascii_lowercase)
+    }
+
+    /// Consume `word` (case-insensitive) if it comes next.
+    fn eat(&mut self, word: &str) -> bool {
+        self.skip_ws();
+        let end = self.pos + word.len();
+        if end <= self.src.len() && self.src[self.pos..end].eq_ignore_ascii_case(word.as_bytes()) {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn number(&mut self) -> Result<u32, DiceError> {
+        self.skip_ws();
+        let start = self.pos;
+        while self.pos < self.src.len() && self.src[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a number"));
+        }
+        let text = std::str::from_utf8(&self.src[start..self.pos]).unwrap();
+        text.parse().map_err(|_| DiceError { pos: start, message: format!("number '{text}' is too large") })
+    }
+
+    fn expr(&mut self) -> Result<Expr, DiceError> {
+        let mut lhs = self.term()?;
+        loop {
+            match self.peek() {
+                Some(b'+') => { self.pos += 1; lhs = Expr::Add(Box::new(lhs), Box::new(self.term()?)); }
+                Some(b'-') => { self.pos += 1; lhs = Expr::Sub(Box::new(lhs), Box::new(self.term()?)); }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, DiceError> {
+        let mut lhs = self.unary()?;
+        while self.peek() == Some(b'*') {
+            self.pos += 1;
+            lhs = Expr::Mul(Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, DiceError> {
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, DiceError> {
+        match self.peek() {
+            Some(b'(') => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                if self.peek() != Some(b')') {
+                    return Err(self.error("expected ')'"));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Some(b'd') => self.dice(1, self.pos),
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.pos;
+                let n = self.number()?;
+                if self.src.get(self.pos).is_some_and(|c| c.eq_ignore_ascii_case(&b'd')) {
+                    self.dice(n, start)
+                } else {
+                    Ok(Expr::Num(n as i64))
+                }
+            }
+            Some(c) => Err(self.error(format!("unexpected '{}'", c as char))),
+            None => Err(self.error("unexpected end of expression")),
+        }
+    }
+
+    /// Parse from the `d` onwards; `count` has already been read.
+    fn dice(&mut self, count: u32, start: usize) -> Result<Expr, DiceError> {
+        self.pos += 1; // 'd'
+        let sides = if self.src.get(self.pos) == Some(&b'%') {
+            self.pos += 1;
+            100
+        } else {
+            self.number()?
+        };
+        if count == 0 || count > MAX_DICE {
+            return Err(DiceError { pos: start, message: format!("dice count must be between 1 and {MAX_DICE}") });
+        }
+        if sides == 0 || sides > MAX_SIDES {
+            return Err(DiceError { pos: start, message: format!("dice sides must be between 1 and {MAX_SIDES}") });
+        }
+
+        let keep_pos = { self.skip_ws(); self.pos };
+        let keep = if self.eat("kh") {
+            Keep::Highest(self.number()?)
+        } else if self.eat("kl") {
+            Keep::Lowest(self.number()?)
+        } else if self.eat("dh") {
+            Keep::Lowest(count.saturating_sub(self.number()?))
+        } else if self.eat("dl") {
+            Keep::Highest(count.saturating_sub(self.number()?))
+        } else if self.eat("k") {
+            Keep::Highest(self.number()?)
+        } else {
+            Keep::All
+        };
+        let keep = match keep {
+            Keep::Highest(k) | Keep::Lowest(k) if k == 0 || k > count => {
+                return Err(DiceError {
+                    pos: keep_pos,
+                    message: format!("must keep between 1 and {count} dice"),
+                });
+            }
+            Keep::Highest(k) | Keep::Lowest(k) if k == count => Keep::All,
+            other => other,
+        };
+
+        let adv_pos = { self.skip_ws(); self.pos };
+        let advantage = if self.eat("adv") {
+            Advantage::Advantage
+        } else if self.eat("dis") {
+            Advantage::Disadvantage
+        } else {
+            Advantage::None
+        };
+        if advantage != Advantage::None && keep != Keep::All {
+            return Err(DiceError { pos: adv_pos, message: "cannot combine adv/dis with keep or drop".into() });
+        }
+        Ok(Expr::Dice(DiceGroup { count, sides, keep, advantage }))
+    }
+}
+
+// ── Evaluation ───────────────────────────────────────────────────────────────
+//
+// Arithmetic saturates at the i64 range: constants are only bounded by u32,
+// so products such as "4000000000*4000000000*4000000000" parse fine but
+// would overflow.
+
+fn eval(expr: &Expr, rng: &mut Rng) -> i64 {
+    match expr {
+        Expr::Num(n) => *n,
+        Expr::Dice(g) => roll_group(g, rng),
+        Expr::Neg(e) => eval(e, rng).saturating_neg(),
+        Expr::Add(a, b) => eval(a, rng).saturating_add(eval(b, rng)),
+        Expr::Sub(a, b) => eval(a, rng).saturating_sub(eval(b, rng)),
+        Expr::Mul(a, b) => eval(a, rng).saturating_mul(eval(b, rng)),
+    }
+}
+
+fn roll_group(g: &DiceGroup, rng: &mut Rng) -> i64 {
+    let mut once = || {
+        let mut rolls: Vec<i64> =
+            (0..g.count).map(|_| rng.range_inclusive(1, g.sides as i32) as i64).collect();
+        match g.keep {
+            Keep::All => rolls.iter().sum(),
+            Keep::Highest(k) => {
+                rolls.sort_unstable_by(|a, b| b.cmp(a));
+                rolls[..k as usize].iter().sum()
+            }
+            Keep::Lowest(k) => {
+                rolls.sort_unstable();
+                rolls[..k as usize].iter().sum()
+            }
+        }
+    };
+    match g.advantage {
+        Advantage::None => once(),
+        Advantage::Advantage => once().max(once()),
+        Advantage::Disadvantage => once().min(once()),
+    }
+}
+
+fn bounds(expr: &Expr) -> (i64, i64) {
+    match expr {
+        Expr::Num(n) => (*n, *n),
+        Expr::Dice(g) => {
+            let kept = match g.keep {
+                Keep::All => g.count,
+                Keep::Highest(k) | Keep::Lowest(k) => k,
+            } as i64;
+            (kept, kept * g.sides as i64)
+        }
+        Expr::Neg(e) => {
+            let (lo, hi) = bounds(e);
+            (hi.saturating_neg(), lo.saturating_neg())
+        }
+        Expr::Add(a, b) => {
+            let ((al, ah), (bl, bh)) = (bounds(a), bounds(b));
+            (al.saturating_add(bl), ah.saturating_add(bh))
+        }
+        Expr::Sub(a, b) => {
+            let ((al, ah), (bl, bh)) = (bounds(a), bounds(b));
+            (al.saturating_sub(bh), ah.saturating_sub(bl))
+        }
+        Expr::Mul(a, b) => {
+            let ((al, ah), (bl, bh)) = (bounds(a), bounds(b));
+            let c = [al.saturating_mul(bl), al.saturating_mul(bh), ah.saturating_mul(bl), ah.saturating_mul(bh)];
+            (*c.iter().min().unwrap(), *c.iter().max().unwrap())
+        }
+    }
+}
+
+/// Operands of every operator are rolled independently, so expectation is
+/// linear for `+`/`-` and multiplicative for `*`.
+fn average(expr: &Expr) -> f64 {
+    match expr {
+        Expr::Num(n) => *n as f64,
+        Expr::Dice(g) => group_average(g),
+        Expr::Neg(e) => -average(e),
+        Expr::Add(a, b) => average(a) + average(b),
+        Expr::Sub(a, b) => average(a) - average(b),
+        Expr::Mul(a, b) => average(a) * average(b),
+    }
+}
+
+fn group_average(g: &DiceGroup) -> f64 {
+    let (n, m) = (g.count as usize, g.sides as usize);
+    match (g.keep, g.advantage) {
+        (Keep::All, Advantage::None) => n as f64 * (m as f64 + 1.0) / 2.0,
+        (Keep::All, adv) => {
+            // P(max(X, Y) <= t) = P(X <= t)²;  P(min(X, Y) <= t) = 1 - P(X > t)².
+            let mut cdf = 0.0;
+            let mut prev = 0.0;
+            let mut avg = 0.0;
+            for (t, p) in sum_distribution(n, m).iter().enumerate() {
+                cdf += p;
+                let combined = match adv {
+                    Advantage::Advantage => cdf * cdf,
+                    _ => 1.0 - (1.0 - cdf) * (1.0 - cdf),
+                };
+                avg += t as f64 * (combined - prev);
+                prev = combined;
+            }
+            avg
+        }
+        // The kept sum equals Σ_v (number of kept dice showing ≥ v).  With
+        // N_v ~ Binomial(n, p_v) dice showing ≥ v, the highest k contribute
+        // min(k, N_v) and the lowest k contribute max(0, N_v - (n - k)).
+        (Keep::Highest(k) | Keep::Lowest(k), _) => {
+            let k = k as usize;
+            (1..=m)
+                .map(|v| {
+                    let p = (m - v + 1) as f64 / m as f64;
+                    binomial_pmf(n, p)
+                        .iter()
+                        .enumerate()
+                        .map(|(c, pr)| {
+                            let kept = match g.keep {
+                                Keep::Highest(_) => c.min(k),
+                                _ => c.saturating_sub(n - k),
+                            };
+                            kept as f64 * pr
+                        })
+                        .sum::<f64>()
+                })
+                .sum()
+        }
+    }
+}
+
+/// Probability of each total (indexed by total) for the sum of `n` d`m`,
+/// convolving one die at a time with a sliding window.
+fn sum_distribution(n: usize, m: usize) -> Vec<f64> {
+    let face = 1.0 / m as f64;
+    let mut dist = vec![1.0];
+    for _ in 0..n {
+        let mut next = vec![0.0; dist.len() + m];
+        let mut window = 0.0;
+        for (t, slot) in next.iter_mut().enumerate() {
+            if t >= 1 && t - 1 < dist.len() {
+                window += dist[t - 1];
+            }
+            if t > m && t - m - 1 < dist.len() {
+                window -= dist[t - m - 1];
+            }
+            *slot = window * face;
+        }
+        dist = next;
+    }
+    dist
+}
+
+fn binomial_pmf(n: usize, p: f64) -> Vec<f64> {
+    let mut pmf = vec![0.0; n + 1];
+    pmf[0] = 1.0;
+    for _ in 0..n {
+        for c in (0..n).rev() {
+            pmf[c + 1] += pmf[c] * p;
+            pmf[c] *= 1.0 - p;
+        }
+    }
+    pmf
+}
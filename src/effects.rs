@@ -0,0 +1,413 @@
+// =============================================================================
+// EFFECTS.RS — Timed status effects and derived stats
+//
+// `StatusEffects` is a component holding the effects active on an entity
+// (poison, haste, stun, buffs …).  Each effect has a duration in turns or
+// seconds, a stacking rule, optional tick/expire hooks, and a list of stat
+// modifiers that are mirrored onto the entity's `Stats` component while the
+// effect is active.
+//
+// Effects advance from the fixed-tick loop.  Real-time games call
+// `update_effects` from `Game::update` with `engine.dt()`; turn-based games
+// call `end_turn` whenever a game turn passes:
+//
+//   fn update(&mut self, engine: &mut jEngine) {
+//       effects::update_effects(&mut self.world, engine.dt());
+//       // ...
+//   }
+// =============================================================================
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::ecs::{Entity, World};
+
+/// Hook invoked with the affected entity and the effect's current stack count.
+pub type EffectHook = Rc<dyn Fn(&mut World, Entity, u32)>;
+
+// ── Stats ────────────────────────────────────────────────────────────────────
+
+/// How a modifier changes a stat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModifierKind {
+    /// Added to the base value.
+    Add(f32),
+    /// Multiplies the sum of base and additive modifiers.
+    Mul(f32),
+}
+
+/// A single modifier on a named stat.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Modifier {
+    pub stat: String,
+    pub kind: ModifierKind,
+}
+
+impl Modifier {
+    pub fn add(stat: &str, amount: f32) -> Self {
+        Self { stat: stat.to_string(), kind: ModifierKind::Add(amount) }
+    }
+
+    pub fn mul(stat: &str, factor: f32) -> Self {
+        Self { stat: stat.to_string(), kind: ModifierKind::Mul(factor) }
+    }
+}
+
+/// Component: base stat values plus the modifiers applied on top of them.
+///
+/// `value(stat) = (base + Σ Add) × Π Mul`.  Derived values are cached and only
+/// recomputed after the base values or modifiers change.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    base: HashMap<String, f32>,
+    /// `(source, modifier)` pairs; the source lets a whole group be removed.
+    modifiers: Vec<(String, Modifier)>,
+    cache: RefCell<HashMap<String, f32>>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style helper for setting a base value.
+    pub fn with(mut self, stat: &str, base: f32) -> Self {
+        self.set_base(stat, base);
+        self
+    }
+
+    pub fn base(&self, stat: &str) -> f32 {
+        self.base.get(stat).copied().unwrap_or(0.0)
+    }
+
+    pub fn set_base(&mut self, stat: &str, value: f32) {
+        self.base.insert(stat.to_string(), value);
+        self.cache.get_mut().remove(stat);
+    }
+
+    /// Final value of `stat` after all modifiers.  Unknown stats have base 0.
+    pub fn value(&self, stat: &str) -> f32 {
+        if let Some(&v) = self.cache.borrow().get(stat) {
+            return v;
+        }
+        let mut add = 0.0;
+        let mut mul = 1.0;
+        for (_, m) in self.modifiers.iter().filter(|(_, m)| m.stat == stat) {
+            match m.kind {
+                ModifierKind::Add(a) => add += a,
+                ModifierKind::Mul(f) => mul *= f,
+            }
+        }
+        let v = (self.base(stat) + add) * mul;
+        self.cache.borrow_mut().insert(stat.to_string(), v);
+        v
+    }
+
+    pub fn add_modifier(&mut self, source: &str, modifier: Modifier) {
+        self.cache.get_mut().remove(&modifier.stat);
+        self.modifiers.push((source.to_string(), modifier));
+    }
+
+    /// Remove every modifier added under `source`.
+    pub fn remove_source(&mut self, source: &str) {
+        let cache = self.cache.get_mut();
+        self.modifiers.retain(|(s, m)| {
+            let keep = s != source;
+            if !keep {
+                cache.remove(&m.stat);
+            }
+            keep
+        });
+    }
+
+    pub fn modifiers(&self) -> impl Iterator<Item = (&str, &Modifier)> {
+        self.modifiers.iter().map(|(s, m)| (s.as_str(), m))
+    }
+}
+
+// ── Effects ──────────────────────────────────────────────────────────────────
+
+/// How long an effect lasts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Duration {
+    /// Expires after this many calls to `end_turn`.
+    Turns(u32),
+    /// Expires after this much time has been passed to `update_effects`.
+    Seconds(f32),
+    /// Lasts until removed explicitly.
+    Permanent,
+}
+
+/// What happens when an effect is applied to an entity that already has it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// Keep one instance and reset its duration to the new one.
+    Refresh,
+    /// Keep one instance and add the new duration to the remaining one.
+    Extend,
+    /// Add a stack (up to `max_stacks`) and refresh the duration.
+    Stack,
+    /// Leave the existing instance untouched.
+    Ignore,
+}
+
+/// A timed effect on an entity.
+#[derive(Clone)]
+pub struct StatusEffect {
+    /// Identifier used for stacking, lookup and removal (e.g. `"poison"`).
+    pub id: String,
+    pub duration: Duration,
+    pub stacking: Stacking,
+    pub stacks: u32,
+    pub max_stacks: u32,
+    /// Modifiers applied once per stack while the effect is active.
+    pub modifiers: Vec<Modifier>,
+    /// For `Duration::Seconds` effects, seconds between `on_tick` calls.
+    pub tick_interval: f32,
+    /// Called every turn (turn-based and permanent effects) or every
+    /// `tick_interval` seconds (timed effects).
+    pub on_tick: Option<EffectHook>,
+    /// Called once when the effect runs out or is removed.
+    pub on_expire: Option<EffectHook>,
+    tick_timer: f32,
+}
+
+impl StatusEffect {
+    pub fn new(id: &str, duration: Duration) -> Self {
+        Self {
+            id: id.to_string(),
+            duration,
+            stacking: Stacking::Refresh,
+            stacks: 1,
+            max_stacks: 1,
+            modifiers: Vec::new(),
+            tick_interval: 1.0,
+            on_tick: None,
+            on_expire: None,
+            tick_timer: 0.0,
+        }
+    }
+
+    pub fn with_stacking(mut self, stacking: Stacking, max_stacks: u32) -> Self {
+        self.stacking = stacking;
+        self.max_stacks = max_stacks.max(1);
+        self
+    }
+
+    pub fn with_modifier(mut self, modifier: Modifier) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+
+    pub fn with_tick_interval(mut self, seconds: f32) -> Self {
+        self.tick_interval = seconds;
+        self
+    }
+
+    pub fn on_tick(mut self, f: impl Fn(&mut World, Entity, u32) + 'static) -> Self {
+        self.on_tick = Some(Rc::new(f));
+        self
+    }
+
+    pub fn on_expire(mut self, f: impl Fn(&mut World, Entity, u32) + 'static) -> Self {
+        self.on_expire = Some(Rc::new(f));
+        self
+    }
+
+    fn is_expired(&self) -> bool {
+        match self.duration {
+            Duration::Turns(t) => t == 0,
+            Duration::Seconds(s) => s <= 0.0,
+            Duration::Permanent => false,
+        }
+    }
+}
+
+/// Component: the effects currently active on an entity.
+#[derive(Clone, Default)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn has(&self, id: &str) -> bool {
+        self.effects.iter().any(|e| e.id == id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&StatusEffect> {
+        self.effects.iter().find(|e| e.id == id)
+    }
+
+    pub fn stacks(&self, id: &str) -> u32 {
+        self.get(id).map_or(0, |e| e.stacks)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+/// Returns true if `entity` currently has the effect `id`.
+pub fn has_effect(world: &World, entity: Entity, id: &str) -> bool {
+    world.get::<StatusEffects>(entity).is_some_and(|s| s.has(id))
+}
+
+/// Apply `effect` to `entity`, following the effect's stacking rule.
+///
+/// Inserts a `StatusEffects` component if the entity has none, and mirrors the
+/// effect's modifiers onto its `Stats` component if it has one.
+pub fn apply_effect(world: &mut World, entity: Entity, effect: StatusEffect) {
+    if !world.is_alive(entity) {
+        return;
+    }
+    if world.get::<StatusEffects>(entity).is_none() {
+        world.insert(entity, StatusEffects::new());
+    }
+    let list = world.get_mut::<StatusEffects>(entity).unwrap();
+
+    let id = effect.id.clone();
+    match list.effects.iter_mut().find(|e| e.id == id) {
+        None => list.effects.push(effect),
+        Some(existing) => match existing.stacking {
+            Stacking::Ignore => return,
+            Stacking::Refresh => existing.duration = effect.duration,
+            Stacking::Extend => {
+                existing.duration = match (existing.duration, effect.duration) {
+                    (Duration::Turns(a), Duration::Turns(b)) => Duration::Turns(a + b),
+                    (Duration::Seconds(a), Duration::Seconds(b)) => Duration::Seconds(a + b),
+                    (_, new) => new,
+                };
+            }
+            Stacking::Stack => {
+                existing.stacks = (existing.stacks + 1).min(existing.max_stacks);
+                existing.duration = effect.duration;
+            }
+        },
+    }
+    sync_modifiers(world, entity, &id);
+}
+
+/// Remove effect `id` from `entity`, running its expire hook.
+/// Returns false if the entity did not have it.
+pub fn remove_effect(world: &mut World, entity: Entity, id: &str) -> bool {
+    let Some(list) = world.get_mut::<StatusEffects>(entity) else { return false };
+    let Some(idx) = list.effects.iter().position(|e| e.id == id) else { return false };
+    let effect = list.effects.remove(idx);
+    finish(world, entity, effect);
+    true
+}
+
+/// Advance every timed (`Duration::Seconds`) effect by `dt` seconds.
+///
+/// Call once per fixed tick with `engine.dt()`.
+pub fn update_effects(world: &mut World, dt: f32) {
+    advance(world, |effect| {
+        let Duration::Seconds(remaining) = effect.duration else { return 0 };
+        let elapsed = dt.min(remaining.max(0.0));
+        effect.duration = Duration::Seconds(remaining - dt);
+        if effect.tick_interval <= 0.0 {
+            return 0;
+        }
+        effect.tick_timer += elapsed;
+        let mut ticks = 0;
+        while effect.tick_timer >= effect.tick_interval {
+            effect.tick_timer -= effect.tick_interval;
+            ticks += 1;
+        }
+        ticks
+    });
+}
+
+/// Advance every turn-based effect by one turn.  Permanent effects also
+/// receive their `on_tick` call.
+pub fn end_turn(world: &mut World) {
+    advance(world, |effect| match effect.duration {
+        Duration::Turns(t) => {
+            effect.duration = Duration::Turns(t.saturating_sub(1));
+            1
+        }
+        Duration::Permanent => 1,
+        Duration::Seconds(_) => 0,
+    });
+}
+
+/// Shared driver: `step` advances one effect and returns how many times its
+/// tick hook should fire.  Hooks run after the component has been updated so
+/// they are free to apply or remove effects themselves.
+fn advance(world: &mut World, mut step: impl FnMut(&mut StatusEffect) -> u32) {
+    let mut entities: Vec<Entity> = world.query::<StatusEffects>().map(|(e, _)| e).collect();
+    entities.sort_by_key(|e| e.id());
+
+    for entity in entities {
+        let mut ticks: Vec<(EffectHook, u32, u32)> = Vec::new();
+        let mut expired = Vec::new();
+        {
+            let Some(list) = world.get_mut::<StatusEffects>(entity) else { continue };
+            for effect in &mut list.effects {
+                let n = step(effect);
+                if let Some(hook) = &effect.on_tick
+                    && n > 0
+                {
+                    ticks.push((Rc::clone(hook), effect.stacks, n));
+                }
+            }
+            let mut i = 0;
+            while i < list.effects.len() {
+                if list.effects[i].is_expired() {
+                    expired.push(list.effects.remove(i));
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        for (hook, stacks, n) in ticks {
+            for _ in 0..n {
+                if !world.is_alive(entity) {
+                    break;
+                }
+                hook(world, entity, stacks);
+            }
+        }
+        for effect in expired {
+            if !world.is_alive(entity) {
+                break;
+            }
+            finish(world, entity, effect);
+        }
+    }
+}
+
+fn finish(world: &mut World, entity: Entity, effect: StatusEffect) {
+    if let Some(stats) = world.get_mut::<Stats>(entity) {
+        stats.remove_source(&effect.id);
+    }
+    if let Some(hook) = &effect.on_expire {
+        hook(world, entity, effect.stacks);
+    }
+}
+
+/// Rewrite the `Stats` modifiers contributed by effect `id` to match its
+/// current stack count.
+fn sync_modifiers(world: &mut World, entity: Entity, id: &str) {
+    let Some(effect) = world.get::<StatusEffects>(entity).and_then(|l| l.get(id)) else { return };
+    let stacks = effect.stacks;
+    let modifiers = effect.modifiers.clone();
+    let Some(stats) = world.get_mut::<Stats>(entity) else { return };
+    stats.remove_source(id);
+    for m in modifiers {
+        let kind = match m.kind {
+            ModifierKind::Add(a) => ModifierKind::Add(a * stacks as f32),
+            ModifierKind::Mul(f) => ModifierKind::Mul(f.powi(stacks as i32)),
+        };
+        stats.add_modifier(id, Modifier { stat: m.stat, kind });
+    }
+}
@@ -17,11 +17,15 @@ pub const MAX_ANIMATED_ENTITIES: usize = 10_000;
 use crate::camera::Camera;
 use crate::ui::{UI, Padding, BorderStyle};
 use crate::ecs::Entity;
+use crate::hex::{Hex, HexLayout};
 use crate::input::InputState;
+use crate::input::replay::{Replay, ReplayPlayer, ReplayRecorder};
+use crate::rng::Rng;
 use crate::audio::AudioContext;
-use crate::renderer::Renderer;
+use crate::renderer::{FrameVertices, Renderer};
+use crate::renderer::atlas::TilesetId;
 use crate::renderer::particle_pipeline::ParticleVertex;
-use crate::renderer::pipeline::TileVertex;
+use crate::renderer::pipeline::{TileBatch, TileVertex};
 use crate::renderer::sprite_atlas::SpriteData;
 
 // ── Color ──────────────────────────────────────────────────────────────────
@@ -134,11 +138,13 @@ impl Default for BgCell {
     }
 }
 
-/// Layer 1 — foreground character glyph (char atlas, optional entity animation).
+/// Layer 1 — foreground glyph (any registered tileset, optional entity animation).
 #[derive(Copy, Clone)]
 struct FgCell {
     /// Atlas glyph index, or `NO_GLYPH` if this cell is empty.
     index: u32,
+    /// Tileset the glyph index refers to.
+    tileset: TilesetId,
     fg: Color,
     /// ECS entity ID owning this cell, or `NO_ENTITY`.
     entity_id: u32,
@@ -146,15 +152,16 @@ struct FgCell {
 
 impl Default for FgCell {
     fn default() -> Self {
-        Self { index: NO_GLYPH, fg: Color::WHITE, entity_id: NO_ENTITY }
+        Self { index: NO_GLYPH, tileset: TilesetId::DEFAULT, fg: Color::WHITE, entity_id: NO_ENTITY }
     }
 }
 
 // ── Sprite draw command ──────────────────────────────────────────────────────
 
 struct SpriteCommand {
-    x: u32,
-    y: u32,
+    /// World-space pixel position of the sprite's top-left corner.
+    px: f32,
+    py: f32,
     data: SpriteData,
     /// 0 = drawn below layer-1 sprites; 1 = drawn above layer-0 sprites.
     layer: u8,
@@ -207,6 +214,11 @@ pub struct jEngine {
     grid_dirty: bool,
     /// Cached vertices for the background and foreground layers.
     cached_char_verts: Vec<TileVertex>,
+    /// Per-tileset draw ranges into `cached_char_verts`.
+    cached_char_batches: Vec<TileBatch>,
+    /// Reused per-tileset glyph buffers for grid rebuilds (index 0 unused:
+    /// default-tileset glyphs are written straight into the cache).
+    glyph_scratch: Vec<Vec<TileVertex>>,
     /// Queued sprite draw calls (sprite atlas path); cleared before each render.
     sprite_commands: Vec<SpriteCommand>,
     particle_vertices: Vec<ParticleVertex>,
@@ -232,6 +244,9 @@ pub struct jEngine {
     pub debug: DebugState,
     /// Audio subsystem for music and sound effects.
     pub audio: AudioContext,
+    /// Deterministic RNG for game logic.  Its seed is stored in replays, so
+    /// anything drawn from it during `update` is reproduced on playback.
+    pub rng: Rng,
     /// Rolling buffer of recent frame times for FPS calculation.
     pub(crate) frame_times: VecDeque<f32>,
 }
@@ -241,8 +256,8 @@ impl jEngine {
         EngineBuilder::default()
     }
 
-    fn from_builder(renderer: Renderer, tile_w: u32, tile_h: u32, debug_enabled: bool) -> Self {
-        let size = renderer.window.inner_size();
+    fn from_builder(renderer: Renderer, tile_w: u32, tile_h: u32, debug_enabled: bool, seed: u64) -> Self {
+        let size = renderer.size();
         let grid_w = size.width / tile_w;
         let grid_h = size.height / tile_h;
         let grid_size = (grid_w * grid_h) as usize;
@@ -264,6 +279,8 @@ impl jEngine {
             fg_grid: vec![FgCell::default(); grid_size],
             grid_dirty: true,
             cached_char_verts: Vec::new(),
+            cached_char_batches: Vec::new(),
+            glyph_scratch: Vec::new(),
             sprite_commands: Vec::new(),
             particle_vertices: Vec::new(),
             ui_sprite_vertices: Vec::new(),
@@ -277,6 +294,7 @@ impl jEngine {
             quit_requested: false,
             debug: DebugState::new(debug_enabled, size.width as f32, size.height as f32),
             audio: AudioContext::new(),
+            rng: Rng::new(seed),
             frame_times: VecDeque::with_capacity(60),
         }
     }
@@ -333,7 +351,7 @@ impl jEngine {
     /// **Note:** camera rotation is not accounted for. If `camera.rotation != 0.0`
     /// the returned coordinates will be incorrect.
     pub fn screen_to_world(&self, screen_x: f32, screen_y: f32) -> [f32; 2] {
-        let size = self.renderer.window.inner_size();
+        let size = self.renderer.size();
         let sw = size.width as f32;
         let sh = size.height as f32;
         
@@ -354,7 +372,7 @@ impl jEngine {
     /// **Note:** camera rotation is not accounted for. If `camera.rotation != 0.0`
     /// the returned coordinates will be incorrect.
     pub fn world_to_screen(&self, world_x: f32, world_y: f32) -> [f32; 2] {
-        let size = self.renderer.window.inner_size();
+        let size = self.renderer.size();
         let sw = size.width as f32;
         let sh = size.height as f32;
         
@@ -419,6 +437,7 @@ impl jEngine {
         if x < self.grid_w && y < self.grid_h {
             self.fg_grid[(y * self.grid_w + x) as usize] = FgCell {
                 index: ch as u32,
+                tileset: TilesetId::DEFAULT,
                 fg,
                 entity_id: NO_ENTITY,
             };
@@ -432,6 +451,7 @@ impl jEngine {
         if x < self.grid_w && y < self.grid_h {
             self.fg_grid[(y * self.grid_w + x) as usize] = FgCell {
                 index: ch as u32,
+                tileset: TilesetId::DEFAULT,
                 fg,
                 entity_id: entity.id(),
             };
@@ -439,6 +459,48 @@ impl jEngine {
         }
     }
 
+    /// Place tile `index` (row-major) of a registered tileset in the
+    /// foreground (Layer 1) at `(x, y)`, e.g. a terrain tile next to text
+    /// drawn from the default font.
+    pub fn set_foreground_from(&mut self, x: u32, y: u32, tileset: TilesetId, index: u32, fg: Color) {
+        if x < self.grid_w && y < self.grid_h {
+            self.fg_grid[(y * self.grid_w + x) as usize] = FgCell {
+                index,
+                tileset,
+                fg,
+                entity_id: NO_ENTITY,
+            };
+            self.grid_dirty = true;
+        }
+    }
+
+    /// `set_foreground_from`, linked to an ECS entity so that animation
+    /// offsets apply to it.
+    pub fn set_foreground_entity_from(&mut self, x: u32, y: u32, entity: Entity, tileset: TilesetId, index: u32, fg: Color) {
+        if x < self.grid_w && y < self.grid_h {
+            self.fg_grid[(y * self.grid_w + x) as usize] = FgCell {
+                index,
+                tileset,
+                fg,
+                entity_id: entity.id(),
+            };
+            self.grid_dirty = true;
+        }
+    }
+
+    /// Register a tile atlas under `name` for `set_foreground_from`.
+    /// Re-registering a name swaps its atlas and keeps the handle.
+    pub fn register_tileset(&mut self, name: &str, png_bytes: &[u8], tile_w: u32, tile_h: u32) -> TilesetId {
+        self.grid_dirty = true;
+        self.renderer.register_tileset(name, png_bytes, tile_w, tile_h)
+    }
+
+    /// Handle of the tileset registered as `name`; the builder's tileset is
+    /// `"default"`.
+    pub fn tileset(&self, name: &str) -> Option<TilesetId> {
+        self.renderer.tileset_id(name)
+    }
+
     // ── Sprite drawing (sprite-atlas path) ────────────────────────────────
 
     /// Queue a sprite from the loaded sprite folder at grid position `(x, y)`.
@@ -446,10 +508,36 @@ impl jEngine {
     /// `layer`: `0` = drawn before layer-1 sprites (background objects),
     ///          `1` = drawn after layer-0 sprites (foreground entities).
     pub fn draw_sprite(&mut self, x: u32, y: u32, name: &str, layer: u8, tint: Color) {
+        let px = (x * self.ui.tile_w) as f32;
+        let py = (y * self.ui.tile_h) as f32;
+        self.draw_sprite_world(px, py, name, layer, tint);
+    }
+
+    /// Queue a sprite with its top-left corner at world-pixel position `(px, py)`.
+    ///
+    /// Same as `draw_sprite` but not snapped to the square grid.
+    pub fn draw_sprite_world(&mut self, px: f32, py: f32, name: &str, layer: u8, tint: Color) {
+        if let Some(data) = self.renderer.get_sprite_data(name) {
+            self.sprite_commands.push(SpriteCommand {
+                px,
+                py,
+                data,
+                layer,
+                tint,
+                entity_id: NO_ENTITY,
+            });
+        }
+    }
+
+    /// Queue a sprite centred on `hex`, positioned by `layout`.
+    pub fn draw_sprite_hex(&mut self, layout: &HexLayout, hex: Hex, name: &str, layer: u8, tint: Color) {
         if let Some(data) = self.renderer.get_sprite_data(name) {
+            let [cx, cy] = layout.hex_to_pixel(hex);
+            let pw = (data.tile_w_span * self.ui.tile_w) as f32;
+            let ph = (data.tile_h_span * self.ui.tile_h) as f32;
             self.sprite_commands.push(SpriteCommand {
-                x,
-                y,
+                px: cx - pw * 0.5,
+                py: cy - ph * 0.5,
                 data,
                 layer,
                 tint,
@@ -474,8 +562,8 @@ impl jEngine {
     pub fn draw_sprite_entity(&mut self, x: u32, y: u32, name: &str, entity: Entity, tint: Color) {
         if let Some(data) = self.renderer.get_sprite_data(name) {
             self.sprite_commands.push(SpriteCommand {
-                x,
-                y,
+                px: (x * self.ui.tile_w) as f32,
+                py: (y * self.ui.tile_h) as f32,
                 data,
                 layer: 1,
                 tint,
@@ -561,7 +649,7 @@ impl jEngine {
     /// Upload the current camera view-projection matrix to the GPU.
     /// Must be called once per frame before `renderer.render()`.
     pub(crate) fn sync_camera(&mut self) {
-        let size = self.renderer.window.inner_size();
+        let size = self.renderer.size();
         let uniform = self.camera.build_view_proj(
             size.width as f32,
             size.height as f32,
@@ -657,7 +745,10 @@ impl jEngine {
         // ── 1. Grid Reconstruction (only if dirty) ──
         if self.grid_dirty {
             let cells = (self.grid_w * self.grid_h) as usize;
-            let mut char_verts = Vec::with_capacity(cells * 12);
+            // Reuse the previous rebuild's allocation.
+            let mut char_verts = std::mem::take(&mut self.cached_char_verts);
+            char_verts.clear();
+            char_verts.reserve(cells * 12);
 
             // Layer 0: background
             for y in 0..self.grid_h {
@@ -677,26 +768,51 @@ impl jEngine {
                 }
             }
 
-            // Layer 1: glyphs
+            // Layer 1: glyphs, grouped by tileset so each atlas is one draw.
+            // Default-tileset glyphs go straight after the background quads,
+            // which don't sample and so share their batch; other tilesets
+            // collect in reused scratch buffers and are appended after.
+            // Grouping only changes the draw order where an entity's
+            // animation offset pushes its glyph over a neighbouring cell.
+            self.glyph_scratch.resize_with(self.renderer.tileset_count(), Vec::new);
+            for scratch in &mut self.glyph_scratch {
+                scratch.clear();
+            }
             for y in 0..self.grid_h {
                 for x in 0..self.grid_w {
                     let cell = &self.fg_grid[(y * self.grid_w + x) as usize];
                     if cell.index == NO_GLYPH { continue; }
+                    let Some(atlas) = self.renderer.tileset_atlas(cell.tileset) else { continue };
 
                     let px = (x * tile_w) as f32;
                     let py = (y * tile_h) as f32;
                     let pw = tile_w as f32;
                     let ph = tile_h as f32;
 
-                    let (uv_min, uv_max) = self.renderer.atlas.uv_for_index(cell.index);
-                    
+                    let (uv_min, uv_max) = atlas.uv_for_index(cell.index);
+
                     let tl = TileVertex { position: [px,      py,      0.9], uv: uv_min,                  fg_color: cell.fg.0, bg_color: [0.0; 4], entity_id: cell.entity_id, layer_id: 1.0 };
                     let tr = TileVertex { position: [px + pw, py,      0.9], uv: [uv_max[0], uv_min[1]], fg_color: cell.fg.0, bg_color: [0.0; 4], entity_id: cell.entity_id, layer_id: 1.0 };
                     let bl = TileVertex { position: [px,      py + ph, 0.9], uv: [uv_min[0], uv_max[1]], fg_color: cell.fg.0, bg_color: [0.0; 4], entity_id: cell.entity_id, layer_id: 1.0 };
                     let br = TileVertex { position: [px + pw, py + ph, 0.9], uv: uv_max,                  fg_color: cell.fg.0, bg_color: [0.0; 4], entity_id: cell.entity_id, layer_id: 1.0 };
-                    char_verts.extend_from_slice(&[tl, bl, tr, tr, bl, br]);
+                    let quad = [tl, bl, tr, tr, bl, br];
+                    match cell.tileset {
+                        TilesetId::DEFAULT => char_verts.extend_from_slice(&quad),
+                        id => self.glyph_scratch[id.0 as usize].extend_from_slice(&quad),
+                    }
                 }
             }
+
+            self.cached_char_batches.clear();
+            if !char_verts.is_empty() {
+                self.cached_char_batches.push(TileBatch { tileset: TilesetId::DEFAULT, vertices: 0..char_verts.len() as u32 });
+            }
+            for (i, verts) in self.glyph_scratch.iter().enumerate().skip(1) {
+                if verts.is_empty() { continue; }
+                let start = char_verts.len() as u32;
+                char_verts.extend_from_slice(verts);
+                self.cached_char_batches.push(TileBatch { tileset: TilesetId(i as u16), vertices: start..char_verts.len() as u32 });
+            }
             self.cached_char_verts = char_verts;
             self.grid_dirty = false; // Grid is now static!
         }
@@ -708,8 +824,8 @@ impl jEngine {
         let mut sprite_verts = Vec::with_capacity(sorted.len() * 6);
 
         for cmd in sorted {
-            let px = (cmd.x * tile_w) as f32;
-            let py = (cmd.y * tile_h) as f32;
+            let px = cmd.px;
+            let py = cmd.py;
             let pw = (cmd.data.tile_w_span * tile_w) as f32;
             let ph = (cmd.data.tile_h_span * tile_h) as f32;
 
@@ -742,8 +858,48 @@ impl jEngine {
         (std::mem::take(&mut self.cached_char_verts), sprite_verts, self.ui_sprite_vertices.clone())
     }
 
+    /// Let `game` draw, then build the frame's vertices and render it.
+    fn draw_frame(&mut self, game: &mut dyn Game) -> Result<(), wgpu::SurfaceError> {
+        self.sprite_commands.clear();
+        self.particle_vertices.clear();
+        self.ui_sprite_vertices.clear();
+        self.ui.clear();
+        game.render(self);
+
+        // Draw debug inspector if active
+        if self.debug.enabled && self.debug.active {
+            let debug_widget = game.debug_render(self);
+            self.draw_debug_inspector(debug_widget);
+        }
+
+        let (char_verts, sprite_verts, ui_sprite_verts) = self.build_vertices();
+        let particle_verts = std::mem::take(&mut self.particle_vertices);
+        let ui_verts = std::mem::take(&mut self.ui.ui_vertices);
+
+        // Upload the current camera matrix to the GPU before rendering.
+        self.sync_camera();
+
+        let text_verts   = std::mem::take(&mut self.ui.text.vertices);
+        let text_indices = std::mem::take(&mut self.ui.text.indices);
+        let result = self.renderer.render(&FrameVertices {
+            char_verts: &char_verts,
+            char_batches: &self.cached_char_batches,
+            sprite_verts: &sprite_verts,
+            particle_verts: &particle_verts,
+            ui_verts: &ui_verts,
+            ui_sprite_verts: &ui_sprite_verts,
+            text_verts: &text_verts,
+            text_indices: &text_indices,
+        });
+
+        // Restore the char vert cache so it survives to the next frame
+        // without reallocation. O(1) pointer move — no heap allocation.
+        self.cached_char_verts = char_verts;
+        result
+    }
+
     fn handle_resize(&mut self) {
-        let size = self.renderer.window.inner_size();
+        let size = self.renderer.size();
         let new_gw = size.width / self.ui.tile_w;
         let new_gh = size.height / self.ui.tile_h;
         if new_gw != self.grid_w || new_gh != self.grid_h {
@@ -756,11 +912,72 @@ impl jEngine {
         }
     }
 
+    // ── Headless API ───────────────────────────────────────────────────────
+
+    /// Run one fixed update of `game` on a headless engine
+    /// (`EngineBuilder::build_headless`): sets `dt`, advances the tick, calls
+    /// `Game::update` and advances animations.
+    pub fn step(&mut self, game: &mut dyn Game, dt: f32) {
+        self.dt = dt;
+        self.tick += 1;
+        game.update(self);
+        self.tick_animations(dt);
+    }
+
+    /// Draw one frame of `game` and return it after post-processing, like a
+    /// redraw of the window would.  Per-frame input state is cleared
+    /// afterwards.
+    ///
+    /// Returns None unless the engine was built with
+    /// `EngineBuilder::build_headless`.
+    pub fn render_offscreen(&mut self, game: &mut dyn Game) -> Option<image::RgbaImage> {
+        if !self.renderer.is_headless() {
+            return None;
+        }
+        let result = self.draw_frame(game);
+        self.input.clear_frame_state();
+        result.ok()?;
+        self.renderer.read_frame()
+    }
+
+    // ── Capture API ────────────────────────────────────────────────────────
+
+    /// Save the next rendered frame, after post-processing, as a PNG at
+    /// `path`.  The frame is read back asynchronously and encoded on a
+    /// background thread, so the game loop does not stall.
+    pub fn capture_screenshot(&mut self, path: impl Into<std::path::PathBuf>) {
+        self.renderer.capture.request_screenshot(path);
+    }
+
+    /// Keep a rolling buffer of the last `seconds` of frames, sampled at up
+    /// to `fps` frames per second, for bug captures.  Frames are held
+    /// uncompressed in memory, so keep both modest (e.g. 5 s at 10 fps).
+    pub fn start_frame_recording(&mut self, seconds: f32, fps: f32) {
+        self.renderer.capture.start_recording(seconds, fps);
+    }
+
+    /// Stop the rolling recording and drop its frames.
+    pub fn stop_frame_recording(&mut self) {
+        self.renderer.capture.stop_recording();
+    }
+
+    /// Write the recorded frames to `dir` as `frame_00000.png`, … (oldest
+    /// first) on a background thread, replacing numbered frames from an
+    /// earlier dump.  Returns the number of frames written.
+    pub fn dump_frame_recording(&mut self, dir: impl AsRef<std::path::Path>) -> usize {
+        self.renderer.capture.dump_recording(dir)
+    }
+
+    /// Block until pending screenshots and recording dumps are on disk.
+    pub fn flush_captures(&mut self) {
+        self.renderer.flush_captures();
+    }
+
     // ── Post-processing API ───────────────────────────────────────────────
 
     pub fn set_scanlines(&mut self, enabled: bool) {
         if enabled {
-            let scale = self.renderer.window.scale_factor() as f32;
+            let scale = self.renderer.scale_factor() as f32;
             self.renderer.post_process.add_effect(Box::new(
                 crate::renderer::post_process::ScanlineEffect::new(
                     &self.renderer.device,
@@ -824,8 +1041,13 @@ pub struct EngineBuilder {
     tile_h: u32,
     target_ups: u32,
     sprite_folder: Option<String>,
+    /// Extra grid tilesets: (name, png, tile_w, tile_h).
+    tilesets: Vec<(String, &'static [u8], u32, u32)>,
     use_scanlines: bool,
     debug_enabled: bool,
+    seed: Option<u64>,
+    record_path: Option<String>,
+    replay_path: Option<String>,
 }
 
 impl Default for EngineBuilder {
@@ -839,8 +1061,12 @@ impl Default for EngineBuilder {
             tile_h: 16,
             target_ups: 60,
             sprite_folder: None,
+            tilesets: Vec::new(),
             use_scanlines: false,
             debug_enabled: false,
+            seed: None,
+            record_path: None,
+            replay_path: None,
         }
     }
 }
@@ -853,6 +1079,13 @@ impl EngineBuilder {
     }
     pub fn with_ups(mut self, ups: u32) -> Self { self.target_ups = ups; self }
 
+    /// Register an extra grid tileset under `name`, alongside the one from
+    /// `with_tileset`.  Look its handle up with `jEngine::tileset(name)` and
+    /// draw from it with `jEngine::set_foreground_from`.
+    pub fn with_named_tileset(mut self, name: &str, png_bytes: &'static [u8], tile_w: u32, tile_h: u32) -> Self {
+        self.tilesets.push((name.to_string(), png_bytes, tile_w, tile_h)); self
+    }
+
     /// Specify a directory to scan recursively for `.png` sprite files.
     /// The atlas is baked once at startup before the game loop begins.
     pub fn with_sprite_folder(mut self, path: &str) -> Self {
@@ -865,12 +1098,94 @@ impl EngineBuilder {
         self.use_scanlines = true; self
     }
 
+    /// Seed the engine RNG with a fixed value instead of the system clock.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed); self
+    }
+
+    /// Record the RNG seed and the input seen by every fixed update tick,
+    /// writing the replay to `path` (JSON) when the application exits.
+    pub fn record_replay(mut self, path: &str) -> Self {
+        self.record_path = Some(path.to_string()); self
+    }
+
+    /// Play back a replay recorded with `record_replay`.  OS input events are
+    /// ignored while the replay runs; live input resumes once it is exhausted.
+    pub fn play_replay(mut self, path: &str) -> Self {
+        self.replay_path = Some(path.to_string()); self
+    }
+
+    /// Build an engine without a window or event loop.  It renders into an
+    /// offscreen texture of the configured size, for golden-image tests and
+    /// tooling: drive the game with `jEngine::step` and grab frames with
+    /// `jEngine::render_offscreen`.  `Game::on_enter` is not called.
+    ///
+    /// The RNG seed defaults to 0 rather than the clock, so runs are
+    /// reproducible.  Returns None if no graphics adapter is available.
+    ///
+    /// ```ignore
+    /// let mut engine = jEngine::builder().with_size(320, 240).with_tileset(TILES, 16, 16).build_headless()?;
+    /// let mut game = MyGame::new();
+    /// game.on_enter(&mut engine);
+    /// engine.step(&mut game, 1.0 / 60.0);
+    /// let frame = engine.render_offscreen(&mut game)?;
+    /// ```
+    pub fn build_headless(self) -> Option<jEngine> {
+        let mut renderer = pollster::block_on(Renderer::new_headless(
+            self.width,
+            self.height,
+            self.png_bytes,
+            self.tile_w,
+            self.tile_h,
+            self.use_scanlines,
+        ))?;
+        if let Some(folder) = &self.sprite_folder {
+            renderer.load_sprite_folder(folder, self.tile_w, self.tile_h);
+        }
+        for (name, png_bytes, tile_w, tile_h) in &self.tilesets {
+            renderer.register_tileset(name, png_bytes, *tile_w, *tile_h);
+        }
+        Some(jEngine::from_builder(
+            renderer,
+            self.tile_w,
+            self.tile_h,
+            self.debug_enabled,
+            self.seed.unwrap_or(0),
+        ))
+    }
+
     pub fn run(mut self, game: impl Game + 'static) {
         // Check for --debug flag in command line arguments
         if std::env::args().any(|arg| arg == "--debug") {
             self.debug_enabled = true;
         }
 
+        let replay_player = match &self.replay_path {
+            Some(path) => match Replay::load(path) {
+                Ok(replay) => {
+                    if replay.ups != self.target_ups {
+                        eprintln!(
+                            "[replay] recorded at {} UPS, overriding configured {} UPS",
+                            replay.ups, self.target_ups
+                        );
+                        self.target_ups = replay.ups;
+                    }
+                    self.seed = Some(replay.seed);
+                    Some(ReplayPlayer::new(replay))
+                }
+                Err(e) => {
+                    eprintln!("[replay] {e}. Playback disabled.");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let seed = self.seed.unwrap_or_else(|| Rng::from_time().seed());
+        self.seed = Some(seed);
+        let recorder = self.record_path.as_ref()
+            .map(|_| ReplayRecorder::new(seed, self.target_ups));
+
         let event_loop = EventLoop::new().unwrap();
         let fixed_dt = 1.0 / self.target_ups as f32;
         let mut app = App {
@@ -880,6 +1195,8 @@ impl EngineBuilder {
             last_instant: None,
             accumulator: 0.0,
             fixed_dt,
+            recorder,
+            replay_player,
         };
         event_loop.run_app(&mut app).unwrap();
     }
@@ -894,6 +1211,22 @@ struct App {
     last_instant: Option<Instant>,
     accumulator: f32,
     fixed_dt: f32,
+    /// Active input recorder (`EngineBuilder::record_replay`).
+    recorder: Option<ReplayRecorder>,
+    /// Active replay playback (`EngineBuilder::play_replay`).
+    replay_player: Option<ReplayPlayer>,
+}
+
+impl App {
+    /// Write the recorded replay (if any) to the configured path.
+    fn save_recording(&mut self) {
+        let (Some(recorder), Some(path)) = (self.recorder.take(), self.config.record_path.as_ref()) else { return };
+        let ticks = recorder.len();
+        match recorder.finish().save(path) {
+            Ok(()) => eprintln!("[replay] saved {ticks} ticks to '{path}'"),
+            Err(e) => eprintln!("[replay] {e}"),
+        }
+    }
 }
 
 impl ApplicationHandler for App {
@@ -922,12 +1255,16 @@ impl ApplicationHandler for App {
         if let Some(folder) = &self.config.sprite_folder {
             renderer.load_sprite_folder(folder, self.config.tile_w, self.config.tile_h);
         }
+        for (name, png_bytes, tile_w, tile_h) in &self.config.tilesets {
+            renderer.register_tileset(name, png_bytes, *tile_w, *tile_h);
+        }
 
         let mut engine = jEngine::from_builder(
             renderer,
             self.config.tile_w,
             self.config.tile_h,
             self.config.debug_enabled,
+            self.config.seed.unwrap_or(0),
         );
 
         self.game.on_enter(&mut engine);
@@ -935,14 +1272,33 @@ impl ApplicationHandler for App {
     }
 
     fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
-        if let Some(engine) = self.engine.as_ref() {
-            engine.renderer.window.request_redraw();
+        if let Some(window) = self.engine.as_ref().and_then(|e| e.renderer.window()) {
+            window.request_redraw();
+        }
+    }
+
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        self.save_recording();
+        if let Some(engine) = self.engine.as_mut() {
+            engine.flush_captures();
         }
     }
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
         let Some(engine) = self.engine.as_mut() else { return };
 
+        // During playback the replay is the only input source.
+        let replaying = self.replay_player.is_some();
+        if replaying && matches!(
+            event,
+            WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::KeyboardInput { .. }
+        ) {
+            return;
+        }
+
         match event {
             WindowEvent::CloseRequested => event_loop.exit(),
 
@@ -989,6 +1345,18 @@ impl ApplicationHandler for App {
                 while self.accumulator >= self.fixed_dt {
                     engine.dt = self.fixed_dt;
                     engine.tick += 1;
+                    if let Some(player) = self.replay_player.as_mut()
+                        && !player.apply_next(&mut engine.input)
+                    {
+                        eprintln!("[replay] playback finished after {} ticks", player.position());
+                        self.replay_player = None;
+                        let mouse_pos = engine.input.mouse_pos;
+                        engine.input = InputState::new();
+                        engine.input.mouse_pos = mouse_pos;
+                    }
+                    if let Some(recorder) = self.recorder.as_mut() {
+                        recorder.record(&engine.input);
+                    }
                     self.game.update(engine);
                     if engine.quit_requested {
                         event_loop.exit();
@@ -1007,40 +1375,15 @@ impl ApplicationHandler for App {
                     engine.frame_times.push_back(elapsed);
                 }
 
-                engine.sprite_commands.clear();
-                engine.particle_vertices.clear();
-                engine.ui_sprite_vertices.clear();
-                engine.ui.clear();
-                self.game.render(engine);
-
-                // Draw debug inspector if active
-                if engine.debug.enabled && engine.debug.active {
-                    let debug_widget = self.game.debug_render(engine);
-                    engine.draw_debug_inspector(debug_widget);
-                }
-
-                let (char_verts, sprite_verts, ui_sprite_verts) = engine.build_vertices();
-                let particle_verts = std::mem::take(&mut engine.particle_vertices);
-                let ui_verts = std::mem::take(&mut engine.ui.ui_vertices);
-
-                // Upload the current camera matrix to the GPU before rendering.
-                engine.sync_camera();
-
-                let text_verts   = std::mem::take(&mut engine.ui.text.vertices);
-                let text_indices = std::mem::take(&mut engine.ui.text.indices);
-                match engine.renderer.render(&char_verts, &sprite_verts, &particle_verts, &ui_verts, &ui_sprite_verts, &text_verts, &text_indices) {
+                match engine.draw_frame(self.game.as_mut()) {
                     Ok(_) => {}
                     Err(wgpu::SurfaceError::Lost) => {
-                        let size = engine.renderer.window.inner_size();
+                        let size = engine.renderer.size();
                         engine.renderer.resize(size);
                     }
                     Err(e) => eprintln!("render error: {e}"),
                 }
 
-                // Restore the char vert cache so it survives to the next frame
-                // without reallocation. O(1) pointer move — no heap allocation.
-                engine.cached_char_verts = char_verts;
-
                 // End of frame cleanup
                 engine.input.clear_frame_state();
             }
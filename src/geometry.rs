@@ -3,8 +3,12 @@
 //
 // Common geometric operations needed in roguelikes:
 // - Distance calculations (for FOV radius, attack range, etc.)
-// - Line drawing (for line-of-sight, projectiles)
+// - Line drawing (for line-of-sight, projectiles), with symmetric,
+//   permissive and DDA variants
 // - Circle/radius operations (for area effects)
+// - Area-of-effect shapes (cones, beams, rings, blasts)
+// - Integer rectangles for rooms and BSP (`GridRect`)
+// - Rotating and flipping grids (`GridTransform`)
 // =============================================================================
 
 /// Calculate Manhattan distance between two points.
@@ -161,6 +165,203 @@ pub fn walk_line(
     true
 }
 
+// =============================================================================
+// ALTERNATIVE LINE ALGORITHMS
+// =============================================================================
+//
+// Plain Bresenham is asymmetric: the line A→B can differ from B→A, so one
+// monster may see the player while the player can't see it.  These variants
+// fix that in different ways:
+// - Symmetric: Bresenham, always drawn in the same direction.
+// - Permissive: every digital line between the endpoints; sight is clear if
+//   any of them is.
+// - DDA: the cells a float ray between tile centres actually passes through.
+
+/// Line algorithm for `line_of_sight_with` and `walk_line_with`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LineAlgorithm {
+    /// Classic Bresenham, as `line`.  A→B may differ from B→A.
+    #[default]
+    Bresenham,
+    /// Bresenham with a fixed drawing direction (`line_symmetric`).
+    Symmetric,
+    /// Every valid digital line (`permissive_lines`).  Most generous for
+    /// sight; symmetric.
+    Permissive,
+    /// Ray marching between tile centres (`line_dda`).  Diagonal gaps
+    /// between two walls still let the ray through, but grazing a single
+    /// corner does not.  Symmetric.
+    Dda,
+}
+
+/// Symmetric Bresenham line: the same tiles as `line`, but always drawn from
+/// the smaller endpoint, so `line_symmetric(a, b)` is `line_symmetric(b, a)`
+/// reversed.  Includes both endpoints, starting at (x1, y1).
+pub fn line_symmetric(x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<(i32, i32)> {
+    if (x1, y1) <= (x2, y2) {
+        line(x1, y1, x2, y2)
+    } else {
+        let mut points = line(x2, y2, x1, y1);
+        points.reverse();
+        points
+    }
+}
+
+/// Every digital straight line from (x1, y1) to (x2, y2).
+///
+/// All Bresenham variants (that is, every way to round the minor axis) take
+/// one step along the major axis per tile; they differ only in where the
+/// minor-axis steps fall.  The set is the same in both directions.  Each line
+/// includes both endpoints and starts at (x1, y1).
+pub fn permissive_lines(x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<Vec<(i32, i32)>> {
+    let n = (x2 - x1).abs().max((y2 - y1).abs()).max(1);
+    let mut lines: Vec<Vec<(i32, i32)>> = (0..n).map(|offset| digital_line(x1, y1, x2, y2, offset)).collect();
+    // Neighbouring offsets often round the same way.
+    lines.dedup();
+    lines
+}
+
+/// The digital line whose minor coordinate at major step `i` is
+/// `floor((i * minor + offset) / major)`, for `offset` in `0..major`.
+fn digital_line(x1: i32, y1: i32, x2: i32, y2: i32, offset: i32) -> Vec<(i32, i32)> {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let x_major = dx.abs() >= dy.abs();
+    let (major, minor) = if x_major { (dx, dy) } else { (dy, dx) };
+    let n = major.abs();
+    (0..=n)
+        .map(|i| {
+            let a = i * major.signum();
+            let b = if n == 0 { 0 } else { (i * minor.abs() + offset) / n * minor.signum() };
+            if x_major { (x1 + a, y1 + b) } else { (x1 + b, y1 + a) }
+        })
+        .collect()
+}
+
+/// Tiles crossed by a ray from the centre of (x1, y1) to the centre of
+/// (x2, y2), in order.  Includes both endpoints.
+///
+/// Unlike Bresenham this is a "supercover" line: it contains every tile the
+/// ray touches, stepping diagonally only where it passes exactly through a
+/// corner.
+pub fn line_dda(x1: i32, y1: i32, x2: i32, y2: i32) -> Vec<(i32, i32)> {
+    ray_cells(x1 as f32 + 0.5, y1 as f32 + 0.5, x2 as f32 + 0.5, y2 as f32 + 0.5)
+}
+
+/// Tiles crossed by a ray between two float positions in tile units (tile
+/// (x, y) covers `x..x + 1`).  Useful for projectiles that move off-grid.
+pub fn ray_cells(x1: f32, y1: f32, x2: f32, y2: f32) -> Vec<(i32, i32)> {
+    let mut cells = Vec::new();
+    walk_ray(x1, y1, x2, y2, |x, y| {
+        cells.push((x, y));
+        true
+    });
+    cells
+}
+
+/// DDA ray march (Amanatides & Woo): call `callback` for every tile the ray
+/// from (x1, y1) to (x2, y2) crosses, in tile units.  Stops early if the
+/// callback returns false.
+///
+/// Returns true if the ray completed, false if it was interrupted.
+pub fn walk_ray(x1: f32, y1: f32, x2: f32, y2: f32, mut callback: impl FnMut(i32, i32) -> bool) -> bool {
+    // Rays that pass within this of a corner step diagonally through it.
+    const TIE: f32 = 1e-5;
+
+    let (mut x, mut y) = (x1.floor() as i32, y1.floor() as i32);
+    let (end_x, end_y) = (x2.floor() as i32, y2.floor() as i32);
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let sx = if dx < 0.0 { -1 } else { 1 };
+    let sy = if dy < 0.0 { -1 } else { 1 };
+
+    // Ray parameter t (0 at the start, 1 at the end) of the next vertical and
+    // horizontal tile boundary, and the t between boundaries.
+    let first_boundary = |pos: f32, cell: i32, d: f32| {
+        if d > 0.0 {
+            (cell as f32 + 1.0 - pos) / d
+        } else if d < 0.0 {
+            (pos - cell as f32) / -d
+        } else {
+            f32::INFINITY
+        }
+    };
+    let mut t_max_x = first_boundary(x1, x, dx);
+    let mut t_max_y = first_boundary(y1, y, dy);
+    let t_delta_x = if dx != 0.0 { 1.0 / dx.abs() } else { f32::INFINITY };
+    let t_delta_y = if dy != 0.0 { 1.0 / dy.abs() } else { f32::INFINITY };
+
+    // Float error can't make the march run away: it never needs more steps
+    // than the Manhattan distance between the end tiles.
+    let max_steps = (end_x - x).abs() + (end_y - y).abs();
+    for _ in 0..=max_steps {
+        if !callback(x, y) {
+            return false;
+        }
+        if x == end_x && y == end_y {
+            break;
+        }
+        if (t_max_x - t_max_y).abs() < TIE {
+            x += sx;
+            y += sy;
+            t_max_x += t_delta_x;
+            t_max_y += t_delta_y;
+        } else if t_max_x < t_max_y {
+            x += sx;
+            t_max_x += t_delta_x;
+        } else {
+            y += sy;
+            t_max_y += t_delta_y;
+        }
+    }
+
+    true
+}
+
+/// `line_of_sight` with a choice of line algorithm.  With
+/// `LineAlgorithm::Permissive` the sight is clear if any digital line is.
+///
+/// The start and end points themselves are NOT checked.
+pub fn line_of_sight_with(
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+    algorithm: LineAlgorithm,
+    is_blocking: impl Fn(i32, i32) -> bool,
+) -> bool {
+    walk_line_with(x1, y1, x2, y2, algorithm, |x, y| {
+        (x == x1 && y == y1) || (x == x2 && y == y2) || !is_blocking(x, y)
+    })
+}
+
+/// `walk_line` with a choice of line algorithm.
+///
+/// With `LineAlgorithm::Permissive` each digital line is walked in turn until
+/// one completes, so the callback may see a point more than once; it returns
+/// true as soon as any line completes.
+pub fn walk_line_with(
+    x1: i32,
+    y1: i32,
+    x2: i32,
+    y2: i32,
+    algorithm: LineAlgorithm,
+    mut callback: impl FnMut(i32, i32) -> bool,
+) -> bool {
+    match algorithm {
+        LineAlgorithm::Bresenham => walk_line(x1, y1, x2, y2, callback),
+        LineAlgorithm::Symmetric => line_symmetric(x1, y1, x2, y2).into_iter().all(|(x, y)| callback(x, y)),
+        LineAlgorithm::Permissive => permissive_lines(x1, y1, x2, y2)
+            .into_iter()
+            .any(|points| points.into_iter().all(|(x, y)| callback(x, y))),
+        LineAlgorithm::Dda => walk_ray(
+            x1 as f32 + 0.5,
+            y1 as f32 + 0.5,
+            x2 as f32 + 0.5,
+            y2 as f32 + 0.5,
+            callback,
+        ),
+    }
+}
+
 // =============================================================================
 // CIRCLE/RADIUS OPERATIONS
 // =============================================================================
@@ -273,3 +474,380 @@ pub fn direction_toward(x1: i32, y1: i32, x2: i32, y2: i32) -> (i32, i32) {
 pub fn normalize_direction(dx: i32, dy: i32) -> (i32, i32) {
     (dx.signum(), dy.signum())
 }
+
+// =============================================================================
+// AREA-OF-EFFECT SHAPES
+// =============================================================================
+//
+// All shape generators return lazy iterators that scan the shape's bounding
+// box, so computing affected tiles (e.g. for a per-frame spell preview) does
+// not allocate.  Collect into a `Vec` only if you need to keep the result.
+
+/// Iterate every point in the inclusive box `[x0, x1] × [y0, y1]`, row by row.
+fn scan_box(x0: i32, y0: i32, x1: i32, y1: i32) -> impl Iterator<Item = (i32, i32)> {
+    (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+}
+
+/// Like `line_of_sight`, but walks the line in place instead of collecting it.
+fn clear_between(x1: i32, y1: i32, x2: i32, y2: i32, is_blocking: &impl Fn(i32, i32) -> bool) -> bool {
+    walk_line(x1, y1, x2, y2, |x, y| {
+        (x == x1 && y == y1) || (x == x2 && y == y2) || !is_blocking(x, y)
+    })
+}
+
+/// Filled rectangle with top-left corner `(x, y)` and size `w × h`.
+pub fn rect_filled(x: i32, y: i32, w: i32, h: i32) -> impl Iterator<Item = (i32, i32)> {
+    scan_box(x, y, x + w - 1, y + h - 1)
+}
+
+/// Rectangle outline with top-left corner `(x, y)` and size `w × h`.
+pub fn rect_hollow(x: i32, y: i32, w: i32, h: i32) -> impl Iterator<Item = (i32, i32)> {
+    let (x1, y1) = (x + w - 1, y + h - 1);
+    scan_box(x, y, x1, y1).filter(move |&(px, py)| px == x || px == x1 || py == y || py == y1)
+}
+
+/// Filled diamond: all points within Manhattan distance `radius` of `(cx, cy)`.
+pub fn diamond(cx: i32, cy: i32, radius: i32) -> impl Iterator<Item = (i32, i32)> {
+    scan_box(cx - radius, cy - radius, cx + radius, cy + radius)
+        .filter(move |&(x, y)| distance_manhattan(cx, cy, x, y) <= radius)
+}
+
+/// Ring between two Euclidean radii (both inclusive).
+///
+/// Uses the same metric as `points_in_radius`, so `ring(c, 0, r)` equals the
+/// filled circle and consecutive rings `(0, r)`, `(r + 1, s)` never overlap.
+pub fn ring(cx: i32, cy: i32, inner: i32, outer: i32) -> impl Iterator<Item = (i32, i32)> {
+    let outer_sq = outer * outer;
+    let inner_sq = (inner - 1) * (inner - 1);
+    scan_box(cx - outer, cy - outer, cx + outer, cy + outer).filter(move |&(x, y)| {
+        let d = distance_squared(cx, cy, x, y);
+        d <= outer_sq && (inner <= 0 || d > inner_sq)
+    })
+}
+
+/// Cone from `(ox, oy)` toward `(tx, ty)`.
+///
+/// Includes every point within Euclidean `range` whose direction from the
+/// origin is at most `angle_deg / 2` away from the aim direction.  The origin
+/// itself is excluded.  If origin and target coincide the cone is empty.
+pub fn cone(ox: i32, oy: i32, tx: i32, ty: i32, angle_deg: f32, range: i32) -> impl Iterator<Item = (i32, i32)> {
+    let (ax, ay) = ((tx - ox) as f32, (ty - oy) as f32);
+    let aim_len = (ax * ax + ay * ay).sqrt();
+    let cos_half = (angle_deg.to_radians() * 0.5).cos();
+    let range_sq = range * range;
+    scan_box(ox - range, oy - range, ox + range, oy + range).filter(move |&(x, y)| {
+        if aim_len == 0.0 || (x == ox && y == oy) {
+            return false;
+        }
+        if distance_squared(ox, oy, x, y) > range_sq {
+            return false;
+        }
+        let (dx, dy) = ((x - ox) as f32, (y - oy) as f32);
+        let cos = (dx * ax + dy * ay) / ((dx * dx + dy * dy).sqrt() * aim_len);
+        // Small epsilon so points exactly on the cone edge are kept.
+        cos >= cos_half - 1e-4
+    })
+}
+
+/// Beam of `width` tiles along the segment `(x1, y1) → (x2, y2)`.
+///
+/// Includes every point whose centre lies within `width / 2` tiles of the
+/// segment.  The start point is excluded (it is usually the caster).
+pub fn beam(x1: i32, y1: i32, x2: i32, y2: i32, width: i32) -> impl Iterator<Item = (i32, i32)> {
+    let half = width.max(1) as f32 * 0.5;
+    let pad = half.ceil() as i32;
+    let (sx, sy) = (x1 as f32, y1 as f32);
+    let (vx, vy) = ((x2 - x1) as f32, (y2 - y1) as f32);
+    let len_sq = vx * vx + vy * vy;
+    scan_box(x1.min(x2) - pad, y1.min(y2) - pad, x1.max(x2) + pad, y1.max(y2) + pad).filter(move |&(x, y)| {
+        if x == x1 && y == y1 {
+            return false;
+        }
+        let (px, py) = (x as f32 - sx, y as f32 - sy);
+        let t = if len_sq == 0.0 { 0.0 } else { ((px * vx + py * vy) / len_sq).clamp(0.0, 1.0) };
+        let (dx, dy) = (px - t * vx, py - t * vy);
+        dx * dx + dy * dy <= half * half
+    })
+}
+
+/// Blast: the filled circle of `radius` around `(cx, cy)`, clipped to the
+/// points visible from the centre.
+///
+/// Blocking tiles that are hit directly (e.g. the wall next to the blast) are
+/// included; anything behind them is not.
+pub fn blast(cx: i32, cy: i32, radius: i32, is_blocking: impl Fn(i32, i32) -> bool) -> impl Iterator<Item = (i32, i32)> {
+    let r_sq = radius * radius;
+    scan_box(cx - radius, cy - radius, cx + radius, cy + radius).filter(move |&(x, y)| {
+        distance_squared(cx, cy, x, y) <= r_sq && clear_between(cx, cy, x, y, &is_blocking)
+    })
+}
+
+// =============================================================================
+// GRID RECTANGLES
+// =============================================================================
+
+/// Integer rectangle of tiles with top-left corner `(x, y)` and size `w × h`,
+/// for rooms, map regions and BSP leaves.  (`ui::Rect` is the f32 screen
+/// rectangle.)
+///
+/// Covers columns `x..x + w` and rows `y..y + h`; a rectangle with `w` or `h`
+/// of 0 or less is empty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GridRect {
+    pub x: i32,
+    pub y: i32,
+    pub w: i32,
+    pub h: i32,
+}
+
+impl GridRect {
+    pub fn new(x: i32, y: i32, w: i32, h: i32) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Rectangle spanning two corner tiles (both inclusive, in any order).
+    pub fn from_corners(x1: i32, y1: i32, x2: i32, y2: i32) -> Self {
+        let (x, y) = (x1.min(x2), y1.min(y2));
+        Self { x, y, w: (x1 - x2).abs() + 1, h: (y1 - y2).abs() + 1 }
+    }
+
+    /// First column past the right edge (`x + w`).
+    pub fn right(&self) -> i32 {
+        self.x + self.w
+    }
+
+    /// First row past the bottom edge (`y + h`).
+    pub fn bottom(&self) -> i32 {
+        self.y + self.h
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.w <= 0 || self.h <= 0
+    }
+
+    /// Number of tiles covered.
+    pub fn area(&self) -> i32 {
+        if self.is_empty() { 0 } else { self.w * self.h }
+    }
+
+    /// Centre tile.  For even sizes this is the tile right/below the middle.
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.w / 2, self.y + self.h / 2)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// True if every tile of `other` is inside `self`.  Empty rectangles are
+    /// contained in anything.
+    pub fn contains_rect(&self, other: &GridRect) -> bool {
+        other.is_empty()
+            || (other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom())
+    }
+
+    /// True if the rectangles share at least one tile.
+    pub fn intersects(&self, other: &GridRect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Overlapping tiles, or None if the rectangles don't overlap.
+    pub fn intersection(&self, other: &GridRect) -> Option<GridRect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let rect = GridRect::new(x, y, self.right().min(other.right()) - x, self.bottom().min(other.bottom()) - y);
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// Smallest rectangle covering both.  Empty rectangles are ignored.
+    pub fn union(&self, other: &GridRect) -> GridRect {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        GridRect::new(x, y, self.right().max(other.right()) - x, self.bottom().max(other.bottom()) - y)
+    }
+
+    /// Grow by `dx` tiles on the left and right and `dy` on the top and
+    /// bottom.  Negative amounts shrink; the size never goes below 0.
+    pub fn inflate(&self, dx: i32, dy: i32) -> GridRect {
+        GridRect::new(self.x - dx, self.y - dy, (self.w + 2 * dx).max(0), (self.h + 2 * dy).max(0))
+    }
+
+    pub fn translate(&self, dx: i32, dy: i32) -> GridRect {
+        GridRect::new(self.x + dx, self.y + dy, self.w, self.h)
+    }
+
+    /// Every tile, row by row.
+    pub fn points(self) -> impl Iterator<Item = (i32, i32)> {
+        rect_filled(self.x, self.y, self.w, self.h)
+    }
+
+    /// The outermost ring of tiles (a room's walls), row by row.
+    pub fn edge(self) -> impl Iterator<Item = (i32, i32)> {
+        rect_hollow(self.x, self.y, self.w, self.h)
+    }
+
+    /// Every tile except the edge (a room's floor), row by row.
+    pub fn interior(self) -> impl Iterator<Item = (i32, i32)> {
+        self.inflate(-1, -1).points()
+    }
+
+    /// Split into a left part `at` columns wide and the right remainder.
+    /// None unless both parts are non-empty.
+    pub fn split_x(&self, at: i32) -> Option<(GridRect, GridRect)> {
+        (at > 0 && at < self.w && self.h > 0).then(|| {
+            (GridRect::new(self.x, self.y, at, self.h), GridRect::new(self.x + at, self.y, self.w - at, self.h))
+        })
+    }
+
+    /// Split into a top part `at` rows high and the bottom remainder.
+    /// None unless both parts are non-empty.
+    pub fn split_y(&self, at: i32) -> Option<(GridRect, GridRect)> {
+        (at > 0 && at < self.h && self.w > 0).then(|| {
+            (GridRect::new(self.x, self.y, self.w, at), GridRect::new(self.x, self.y + at, self.w, self.h - at))
+        })
+    }
+
+    /// One BSP step: split at a random position so that both parts are at
+    /// least `min_size` in the split direction.  Wide rectangles split
+    /// vertically and tall ones horizontally; near-square ones pick at
+    /// random.  None if the rectangle is too small to split.
+    ///
+    /// ```ignore
+    /// let mut leaves = vec![GridRect::new(0, 0, 80, 50)];
+    /// while let Some(i) = leaves.iter().position(|r| r.area() > 200) {
+    ///     let Some((a, b)) = leaves[i].split_random(&mut rng, 6) else { break };
+    ///     leaves.splice(i..=i, [a, b]);
+    /// }
+    /// ```
+    pub fn split_random(&self, rng: &mut crate::rng::Rng, min_size: i32) -> Option<(GridRect, GridRect)> {
+        let min_size = min_size.max(1);
+        let can_x = self.w >= 2 * min_size && self.h > 0;
+        let can_y = self.h >= 2 * min_size && self.w > 0;
+        let along_x = match (can_x, can_y) {
+            (false, false) => return None,
+            (true, false) => true,
+            (false, true) => false,
+            // Ratio test keeps BSP leaves from getting long and thin.
+            _ if self.w * 4 >= self.h * 5 => true,
+            _ if self.h * 4 >= self.w * 5 => false,
+            _ => rng.chance(0.5),
+        };
+        if along_x {
+            self.split_x(rng.range_inclusive(min_size, self.w - min_size))
+        } else {
+            self.split_y(rng.range_inclusive(min_size, self.h - min_size))
+        }
+    }
+}
+
+// =============================================================================
+// GRID TRANSFORMS
+// =============================================================================
+
+/// One of the 8 ways to rotate and flip a grid, for placing prefabs and
+/// vaults in any orientation.  Rotations are clockwise on screen (y down).
+///
+/// Coordinates are transformed inside a `w × h` grid; the result lies in a
+/// grid of `transformed_size(w, h)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GridTransform {
+    #[default]
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror left to right.
+    FlipX,
+    /// Mirror top to bottom.
+    FlipY,
+    /// Mirror across the main diagonal: `(x, y) → (y, x)`.
+    Transpose,
+    /// Mirror across the other diagonal.
+    AntiTranspose,
+}
+
+impl GridTransform {
+    /// All 8 orientations, for picking one at random.
+    pub const ALL: [GridTransform; 8] = [
+        GridTransform::Identity,
+        GridTransform::Rotate90,
+        GridTransform::Rotate180,
+        GridTransform::Rotate270,
+        GridTransform::FlipX,
+        GridTransform::FlipY,
+        GridTransform::Transpose,
+        GridTransform::AntiTranspose,
+    ];
+
+    /// True if the transform exchanges width and height.
+    pub fn swaps_axes(self) -> bool {
+        matches!(self, Self::Rotate90 | Self::Rotate270 | Self::Transpose | Self::AntiTranspose)
+    }
+
+    /// Size of a `w × h` grid after the transform.
+    pub fn transformed_size(self, w: i32, h: i32) -> (i32, i32) {
+        if self.swaps_axes() { (h, w) } else { (w, h) }
+    }
+
+    /// The transform that undoes this one.
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Rotate90 => Self::Rotate270,
+            Self::Rotate270 => Self::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Where tile `(x, y)` of a `w × h` grid ends up.
+    pub fn apply(self, x: i32, y: i32, w: i32, h: i32) -> (i32, i32) {
+        match self {
+            Self::Identity => (x, y),
+            Self::Rotate90 => (h - 1 - y, x),
+            Self::Rotate180 => (w - 1 - x, h - 1 - y),
+            Self::Rotate270 => (y, w - 1 - x),
+            Self::FlipX => (w - 1 - x, y),
+            Self::FlipY => (x, h - 1 - y),
+            Self::Transpose => (y, x),
+            Self::AntiTranspose => (h - 1 - y, w - 1 - x),
+        }
+    }
+
+    /// Transform an offset or direction around the origin, e.g. a door
+    /// facing or a relative spawn point.
+    pub fn apply_offset(self, dx: i32, dy: i32) -> (i32, i32) {
+        match self {
+            Self::Identity => (dx, dy),
+            Self::Rotate90 => (-dy, dx),
+            Self::Rotate180 => (-dx, -dy),
+            Self::Rotate270 => (dy, -dx),
+            Self::FlipX => (-dx, dy),
+            Self::FlipY => (dx, -dy),
+            Self::Transpose => (dy, dx),
+            Self::AntiTranspose => (-dy, -dx),
+        }
+    }
+
+    /// Transform a whole row-major `w × h` grid.  Returns the new cells and
+    /// the new `(w, h)`.
+    ///
+    /// Panics if `cells.len() != w * h`.
+    pub fn apply_grid<T: Clone>(self, cells: &[T], w: i32, h: i32) -> (Vec<T>, i32, i32) {
+        assert_eq!(cells.len(), (w.max(0) * h.max(0)) as usize, "grid size does not match w × h");
+        let (nw, nh) = self.transformed_size(w, h);
+        // Pull each output cell from its source through the inverse.
+        let inverse = self.inverse();
+        let out = rect_filled(0, 0, nw, nh)
+            .map(|(x, y)| {
+                let (sx, sy) = inverse.apply(x, y, nw, nh);
+                cells[(sy * w + sx) as usize].clone()
+            })
+            .collect();
+        (out, nw, nh)
+    }
+}
@@ -0,0 +1,242 @@
+// =============================================================================
+// HEX.RS — Hexagonal grid geometry
+//
+// Axial / cube coordinates, offset-coordinate conversion, neighbours,
+// distance, line drawing, rings, spirals and pixel ↔ hex conversion.
+//
+// Conventions follow Red Blob Games' hex grid guide:
+// - `Hex { q, r }` is an axial coordinate; the implied cube `s = -q - r`.
+// - Directions are numbered 0..6 counter-clockwise starting at "east" (+q).
+// =============================================================================
+
+use std::ops::{Add, Sub};
+
+/// Axial hex coordinate.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+/// Cube hex coordinate.  Always satisfies `q + r + s == 0`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub q: i32,
+    pub r: i32,
+    pub s: i32,
+}
+
+/// The 6 neighbour offsets in axial coordinates.
+pub const HEX_DIRECTIONS: [Hex; 6] = [
+    Hex { q: 1, r: 0 },
+    Hex { q: 1, r: -1 },
+    Hex { q: 0, r: -1 },
+    Hex { q: -1, r: 0 },
+    Hex { q: -1, r: 1 },
+    Hex { q: 0, r: 1 },
+];
+
+impl Hex {
+    pub const fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// The implied third cube coordinate.
+    #[inline]
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn to_cube(self) -> Cube {
+        Cube { q: self.q, r: self.r, s: self.s() }
+    }
+
+    pub fn from_cube(c: Cube) -> Self {
+        Self { q: c.q, r: c.r }
+    }
+
+    /// The neighbour in direction `dir` (taken modulo 6).
+    pub fn neighbor(self, dir: usize) -> Hex {
+        self + HEX_DIRECTIONS[dir % 6]
+    }
+
+    /// All 6 neighbours, in direction order.
+    pub fn neighbors(self) -> [Hex; 6] {
+        HEX_DIRECTIONS.map(|d| self + d)
+    }
+
+    /// Number of hex steps between two hexes.
+    pub fn distance(self, other: Hex) -> i32 {
+        let d = self - other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    /// Round fractional cube coordinates to the nearest hex.
+    pub fn round(q: f32, r: f32) -> Hex {
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        Hex { q: rq as i32, r: rr as i32 }
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+    fn add(self, o: Hex) -> Hex { Hex { q: self.q + o.q, r: self.r + o.r } }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+    fn sub(self, o: Hex) -> Hex { Hex { q: self.q - o.q, r: self.r - o.r } }
+}
+
+// =============================================================================
+// LINES, RINGS AND SPIRALS
+// =============================================================================
+
+/// All hexes on the straight line from `a` to `b`, including both endpoints.
+///
+/// Samples `distance + 1` evenly spaced points and rounds each to a hex.  A
+/// tiny nudge keeps points that fall exactly on an edge from flip-flopping.
+pub fn hex_line(a: Hex, b: Hex) -> Vec<Hex> {
+    let n = a.distance(b);
+    if n == 0 {
+        return vec![a];
+    }
+    let (aq, ar) = (a.q as f32 + 1e-6, a.r as f32 + 1e-6);
+    let (bq, br) = (b.q as f32 + 1e-6, b.r as f32 + 1e-6);
+    (0..=n)
+        .map(|i| {
+            let t = i as f32 / n as f32;
+            Hex::round(aq + (bq - aq) * t, ar + (br - ar) * t)
+        })
+        .collect()
+}
+
+/// The hexes at exactly `radius` steps from `center`.
+///
+/// A radius of 0 yields just the centre.  Hexes are returned walking the
+/// ring counter-clockwise, starting from the south-west corner.
+pub fn hex_ring(center: Hex, radius: i32) -> Vec<Hex> {
+    if radius <= 0 {
+        return vec![center];
+    }
+    let mut results = Vec::with_capacity(6 * radius as usize);
+    let step = HEX_DIRECTIONS[4];
+    let mut hex = Hex::new(center.q + step.q * radius, center.r + step.r * radius);
+    for dir in 0..6 {
+        for _ in 0..radius {
+            results.push(hex);
+            hex = hex.neighbor(dir);
+        }
+    }
+    results
+}
+
+/// Every hex within `radius` of `center`, ordered ring by ring outward.
+pub fn hex_spiral(center: Hex, radius: i32) -> Vec<Hex> {
+    let mut results = vec![center];
+    for k in 1..=radius {
+        results.extend(hex_ring(center, k));
+    }
+    results
+}
+
+// =============================================================================
+// OFFSET COORDINATES
+// =============================================================================
+
+/// Offset-coordinate layouts, for storing hex maps in rectangular arrays.
+///
+/// - `OddR` / `EvenR`: pointy-top hexes, odd/even rows shoved right.
+/// - `OddQ` / `EvenQ`: flat-top hexes, odd/even columns shoved down.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OffsetLayout {
+    OddR,
+    EvenR,
+    OddQ,
+    EvenQ,
+}
+
+/// Convert an axial hex to `(col, row)` offset coordinates.
+pub fn hex_to_offset(hex: Hex, layout: OffsetLayout) -> (i32, i32) {
+    match layout {
+        OffsetLayout::OddR => (hex.q + (hex.r - (hex.r & 1)) / 2, hex.r),
+        OffsetLayout::EvenR => (hex.q + (hex.r + (hex.r & 1)) / 2, hex.r),
+        OffsetLayout::OddQ => (hex.q, hex.r + (hex.q - (hex.q & 1)) / 2),
+        OffsetLayout::EvenQ => (hex.q, hex.r + (hex.q + (hex.q & 1)) / 2),
+    }
+}
+
+/// Convert `(col, row)` offset coordinates to an axial hex.
+pub fn offset_to_hex(col: i32, row: i32, layout: OffsetLayout) -> Hex {
+    match layout {
+        OffsetLayout::OddR => Hex::new(col - (row - (row & 1)) / 2, row),
+        OffsetLayout::EvenR => Hex::new(col - (row + (row & 1)) / 2, row),
+        OffsetLayout::OddQ => Hex::new(col, row - (col - (col & 1)) / 2),
+        OffsetLayout::EvenQ => Hex::new(col, row - (col + (col & 1)) / 2),
+    }
+}
+
+// =============================================================================
+// PIXEL CONVERSION
+// =============================================================================
+
+/// Whether hexes have a pointy or a flat top.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HexOrientation {
+    PointyTop,
+    FlatTop,
+}
+
+/// Maps hexes to world-space pixel positions and back.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HexLayout {
+    pub orientation: HexOrientation,
+    /// Hex "radius" in pixels (centre to corner), per axis.
+    pub size: [f32; 2],
+    /// Pixel position of the centre of hex `(0, 0)`.
+    pub origin: [f32; 2],
+}
+
+const SQRT_3: f32 = 1.732_050_8;
+
+impl HexLayout {
+    pub fn new(orientation: HexOrientation, size: [f32; 2], origin: [f32; 2]) -> Self {
+        Self { orientation, size, origin }
+    }
+
+    /// Pixel position of the centre of `hex`.
+    pub fn hex_to_pixel(&self, hex: Hex) -> [f32; 2] {
+        let (q, r) = (hex.q as f32, hex.r as f32);
+        let (x, y) = match self.orientation {
+            HexOrientation::PointyTop => (SQRT_3 * q + SQRT_3 / 2.0 * r, 1.5 * r),
+            HexOrientation::FlatTop => (1.5 * q, SQRT_3 / 2.0 * q + SQRT_3 * r),
+        };
+        [x * self.size[0] + self.origin[0], y * self.size[1] + self.origin[1]]
+    }
+
+    /// The hex containing pixel position `(x, y)`.
+    pub fn pixel_to_hex(&self, x: f32, y: f32) -> Hex {
+        let px = (x - self.origin[0]) / self.size[0];
+        let py = (y - self.origin[1]) / self.size[1];
+        let (q, r) = match self.orientation {
+            HexOrientation::PointyTop => (SQRT_3 / 3.0 * px - py / 3.0, 2.0 / 3.0 * py),
+            HexOrientation::FlatTop => (2.0 / 3.0 * px, -px / 3.0 + SQRT_3 / 3.0 * py),
+        };
+        Hex::round(q, r)
+    }
+
+    /// Width and height in pixels of a single hex's bounding box.
+    pub fn hex_pixel_size(&self) -> [f32; 2] {
+        match self.orientation {
+            HexOrientation::PointyTop => [SQRT_3 * self.size[0], 2.0 * self.size[1]],
+            HexOrientation::FlatTop => [2.0 * self.size[0], SQRT_3 * self.size[1]],
+        }
+    }
+}
@@ -1,3 +1,5 @@
+pub mod replay;
+
 use std::collections::{HashSet, HashMap};
 use std::hash::Hash;
 pub use winit::keyboard::KeyCode;
@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::{InputState, KeyCode, MouseButton};
+
+// =============================================================================
+// INPUT REPLAY
+// =============================================================================
+//
+// A replay is the RNG seed plus one `InputFrame` per fixed update tick.  Held
+// keys/buttons and the cursor position are stored as deltas against the
+// previous tick; the per-frame sets (pressed, released, typed chars, wheel)
+// are stored verbatim because they are what `Game::update` actually observes.
+
+/// Input delta observed by a single fixed update tick.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputFrame {
+    /// Keys that became held since the previous tick.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys_down: Vec<KeyCode>,
+    /// Keys that stopped being held since the previous tick.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys_up: Vec<KeyCode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys_pressed: Vec<KeyCode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys_released: Vec<KeyCode>,
+
+    /// Mouse buttons that became held since the previous tick.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mouse_down: Vec<MouseButton>,
+    /// Mouse buttons that stopped being held since the previous tick.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mouse_up: Vec<MouseButton>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mouse_pressed: Vec<MouseButton>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mouse_released: Vec<MouseButton>,
+
+    /// New cursor position, or `None` if the cursor did not move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mouse_pos: Option<[f32; 2]>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub mouse_wheel: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chars_typed: Vec<char>,
+}
+
+fn is_zero(v: &f32) -> bool {
+    *v == 0.0
+}
+
+/// A complete recording: everything needed to reproduce a run tick by tick.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    /// Seed the engine RNG was started with.
+    pub seed: u64,
+    /// Fixed updates per second the recording was made at.
+    pub ups: u32,
+    /// One entry per fixed update tick, in order.
+    pub frames: Vec<InputFrame>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, ups: u32) -> Self {
+        Self { seed, ups, frames: Vec::new() }
+    }
+
+    /// Load a replay from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("failed to read '{}': {e}", path.as_ref().display()))?;
+        Self::from_json(&text)
+    }
+
+    /// Write the replay to a JSON file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let text = self.to_json()?;
+        std::fs::write(path.as_ref(), text)
+            .map_err(|e| format!("failed to write '{}': {e}", path.as_ref().display()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| format!("invalid replay: {e}"))
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("failed to serialize replay: {e}"))
+    }
+}
+
+// ── Recording ─────────────────────────────────────────────────────────────────
+
+/// Captures the `InputState` seen by each fixed tick as an `InputFrame`.
+#[derive(Debug)]
+pub struct ReplayRecorder {
+    replay: Replay,
+    prev_keys: HashSet<KeyCode>,
+    prev_mouse: HashSet<MouseButton>,
+    prev_mouse_pos: [f32; 2],
+}
+
+impl ReplayRecorder {
+    pub fn new(seed: u64, ups: u32) -> Self {
+        Self {
+            replay: Replay::new(seed, ups),
+            prev_keys: HashSet::new(),
+            prev_mouse: HashSet::new(),
+            prev_mouse_pos: [0.0, 0.0],
+        }
+    }
+
+    /// Record the input observed by the tick that is about to run.
+    pub fn record(&mut self, input: &InputState) {
+        let frame = InputFrame {
+            keys_down: input.keys_held.difference(&self.prev_keys).copied().collect(),
+            keys_up: self.prev_keys.difference(&input.keys_held).copied().collect(),
+            keys_pressed: input.keys_pressed.iter().copied().collect(),
+            keys_released: input.keys_released.iter().copied().collect(),
+            mouse_down: input.mouse_held.difference(&self.prev_mouse).copied().collect(),
+            mouse_up: self.prev_mouse.difference(&input.mouse_held).copied().collect(),
+            mouse_pressed: input.mouse_pressed.iter().copied().collect(),
+            mouse_released: input.mouse_released.iter().copied().collect(),
+            mouse_pos: (input.mouse_pos != self.prev_mouse_pos).then_some(input.mouse_pos),
+            mouse_wheel: input.mouse_wheel,
+            chars_typed: input.chars_typed.clone(),
+        };
+
+        self.prev_keys.clone_from(&input.keys_held);
+        self.prev_mouse.clone_from(&input.mouse_held);
+        self.prev_mouse_pos = input.mouse_pos;
+        self.replay.frames.push(frame);
+    }
+
+    /// Number of ticks recorded so far.
+    pub fn len(&self) -> usize {
+        self.replay.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.replay.frames.is_empty()
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn finish(self) -> Replay {
+        self.replay
+    }
+}
+
+// ── Playback ──────────────────────────────────────────────────────────────────
+
+/// Feeds a recorded `Replay` back into an `InputState`, one tick at a time.
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    cursor: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, cursor: 0 }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.replay.seed
+    }
+
+    pub fn ups(&self) -> u32 {
+        self.replay.ups
+    }
+
+    /// Returns true once every recorded tick has been applied.
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.replay.frames.len()
+    }
+
+    /// Index of the next tick to be applied.
+    pub fn position(&self) -> usize {
+        self.cursor
+    }
+
+    /// Overwrite `input` with the state recorded for the next tick.
+    ///
+    /// Returns `false` (leaving `input` untouched) when the replay is exhausted.
+    pub fn apply_next(&mut self, input: &mut InputState) -> bool {
+        let Some(frame) = self.replay.frames.get(self.cursor) else { return false };
+        self.cursor += 1;
+
+        for k in &frame.keys_up { input.keys_held.remove(k); }
+        input.keys_held.extend(frame.keys_down.iter().copied());
+        input.keys_pressed = frame.keys_pressed.iter().copied().collect();
+        input.keys_released = frame.keys_released.iter().copied().collect();
+
+        for b in &frame.mouse_up { input.mouse_held.remove(b); }
+        input.mouse_held.extend(frame.mouse_down.iter().copied());
+        input.mouse_pressed = frame.mouse_pressed.iter().copied().collect();
+        input.mouse_released = frame.mouse_released.iter().copied().collect();
+
+        if let Some(pos) = frame.mouse_pos {
+            input.mouse_pos = pos;
+        }
+        input.mouse_wheel = frame.mouse_wheel;
+        input.chars_typed.clone_from(&frame.chars_typed);
+        input.key_consumed = false;
+        input.mouse_consumed = false;
+        true
+    }
+}
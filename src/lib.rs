@@ -1,12 +1,19 @@
+pub mod ai;
 pub mod camera;
+pub mod dice;
 pub mod ecs;
+pub mod effects;
 pub mod engine;
 pub mod geometry;
+pub mod hex;
 pub mod input;
+pub mod loot;
 pub mod audio;
 pub mod pathfinding;
 pub mod renderer;
+pub mod rng;
 pub mod scene;
+pub mod targeting;
 pub mod ui;
 pub mod window;
 
@@ -0,0 +1,282 @@
+// =============================================================================
+// LOOT.RS — Weighted loot and spawn tables with depth scaling
+//
+// A `TableSet` holds named `LootTable`s, usually loaded from JSON.  Rolling a
+// table at a dungeon depth returns the names of the prefabs to spawn; what a
+// name means (a monster, an item, a trap …) is up to the game.
+//
+//   {
+//     "monsters": {
+//       "rolls": "1d3+1",
+//       "unique": true,
+//       "guaranteed": [ { "prefab": "rat" } ],
+//       "entries": [
+//         { "prefab": "goblin", "weight": 10, "max_depth": 5 },
+//         { "prefab": "orc",    "curve": [[1, 0], [3, 5], [10, 20]] },
+//         { "table":  "rare_monsters", "weight": 1, "min_depth": 4 }
+//       ]
+//     },
+//     "rare_monsters": { "entries": [ { "prefab": "troll" } ] }
+//   }
+//
+// Rolls draw only from the `Rng` passed in, so the same seed and depth always
+// produce the same spawns.
+// =============================================================================
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::dice::Dice;
+use crate::rng::Rng;
+
+/// Nested tables deeper than this are not followed (guards against cycles
+/// that slipped past validation).
+const MAX_NESTING: usize = 16;
+
+/// What an entry produces when picked.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LootDrop {
+    /// A prefab name, returned as-is.
+    Prefab(String),
+    /// Roll another table in the same `TableSet` and include its results.
+    Table(String),
+}
+
+/// One weighted entry of a table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootEntry {
+    #[serde(flatten)]
+    pub drop: LootDrop,
+    /// Weight used when no `curve` is given.
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    /// Piecewise-linear weight over depth as `(depth, weight)` points, sorted
+    /// by depth.  Depths outside the curve use the nearest end point.
+    #[serde(default)]
+    pub curve: Vec<(u32, f32)>,
+    #[serde(default)]
+    pub min_depth: Option<u32>,
+    #[serde(default)]
+    pub max_depth: Option<u32>,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl LootEntry {
+    pub fn prefab(name: &str, weight: f32) -> Self {
+        Self::new(LootDrop::Prefab(name.to_string()), weight)
+    }
+
+    pub fn table(name: &str, weight: f32) -> Self {
+        Self::new(LootDrop::Table(name.to_string()), weight)
+    }
+
+    fn new(drop: LootDrop, weight: f32) -> Self {
+        Self { drop, weight, curve: Vec::new(), min_depth: None, max_depth: None }
+    }
+
+    pub fn with_depth(mut self, min: Option<u32>, max: Option<u32>) -> Self {
+        self.min_depth = min;
+        self.max_depth = max;
+        self
+    }
+
+    pub fn with_curve(mut self, curve: Vec<(u32, f32)>) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Effective weight at `depth`; 0 when the entry is out of its depth range.
+    pub fn weight_at(&self, depth: u32) -> f32 {
+        if self.min_depth.is_some_and(|d| depth < d) || self.max_depth.is_some_and(|d| depth > d) {
+            return 0.0;
+        }
+        let w = match (self.curve.first(), self.curve.last()) {
+            (None, _) | (_, None) => self.weight,
+            (Some(&(d0, w0)), _) if depth <= d0 => w0,
+            (_, Some(&(d1, w1))) if depth >= d1 => w1,
+            _ => {
+                let i = self.curve.iter().position(|&(d, _)| d > depth).unwrap();
+                let ((d0, w0), (d1, w1)) = (self.curve[i - 1], self.curve[i]);
+                w0 + (w1 - w0) * (depth - d0) as f32 / (d1 - d0) as f32
+            }
+        };
+        w.max(0.0)
+    }
+}
+
+/// A weighted table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LootTable {
+    /// How many picks to make; defaults to one.
+    #[serde(default)]
+    pub rolls: Option<Dice>,
+    /// Pick without replacement: no entry is chosen twice in one roll.
+    #[serde(default)]
+    pub unique: bool,
+    /// Drops included on every roll, before the weighted picks.
+    #[serde(default)]
+    pub guaranteed: Vec<LootDrop>,
+    #[serde(default)]
+    pub entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub fn new() -> Self {
+        Self { rolls: None, unique: false, guaranteed: Vec::new(), entries: Vec::new() }
+    }
+
+    pub fn with_rolls(mut self, rolls: Dice) -> Self {
+        self.rolls = Some(rolls);
+        self
+    }
+
+    pub fn unique(mut self) -> Self {
+        self.unique = true;
+        self
+    }
+
+    pub fn with_guaranteed(mut self, drop: LootDrop) -> Self {
+        self.guaranteed.push(drop);
+        self
+    }
+
+    pub fn with_entry(mut self, entry: LootEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+}
+
+impl Default for LootTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A collection of named tables that may reference each other.
+#[derive(Debug, Clone, Default)]
+pub struct TableSet {
+    tables: HashMap<String, LootTable>,
+}
+
+impl TableSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, name: &str, table: LootTable) {
+        self.tables.insert(name.to_string(), table);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LootTable> {
+        self.tables.get(name)
+    }
+
+    /// Load tables from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("failed to read '{}': {e}", path.as_ref().display()))?;
+        Self::from_json(&text)
+    }
+
+    /// Parse a JSON object mapping table names to tables, then `validate`.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let tables: HashMap<String, LootTable> =
+            serde_json::from_str(text).map_err(|e| format!("invalid loot tables: {e}"))?;
+        let set = Self { tables };
+        set.validate()?;
+        Ok(set)
+    }
+
+    /// Check that every sub-table reference exists and that no table
+    /// (directly or indirectly) contains itself.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names: Vec<&String> = self.tables.keys().collect();
+        names.sort();
+        for name in names {
+            self.check(name, &mut Vec::new())?;
+        }
+        Ok(())
+    }
+
+    fn check<'a>(&'a self, name: &'a str, stack: &mut Vec<&'a str>) -> Result<(), String> {
+        if stack.contains(&name) {
+            return Err(format!("loot table cycle: {} -> {name}", stack.join(" -> ")));
+        }
+        let Some(table) = self.tables.get(name) else {
+            return Err(format!("unknown loot table '{name}' (referenced from '{}')", stack.last().unwrap_or(&"")));
+        };
+        stack.push(name);
+        let refs = table.guaranteed.iter().chain(table.entries.iter().map(|e| &e.drop));
+        for drop in refs {
+            if let LootDrop::Table(sub) = drop {
+                self.check(sub, stack)?;
+            }
+        }
+        stack.pop();
+        Ok(())
+    }
+
+    /// Roll table `name` at `depth` and return the prefab names to spawn.
+    ///
+    /// Unknown tables yield nothing.
+    pub fn roll(&self, name: &str, depth: u32, rng: &mut Rng) -> Vec<String> {
+        let mut out = Vec::new();
+        self.roll_into(name, depth, rng, &mut out, 0);
+        out
+    }
+
+    fn roll_into(&self, name: &str, depth: u32, rng: &mut Rng, out: &mut Vec<String>, nesting: usize) {
+        if nesting >= MAX_NESTING {
+            return;
+        }
+        let Some(table) = self.tables.get(name) else { return };
+
+        for drop in &table.guaranteed {
+            self.resolve(drop, depth, rng, out, nesting);
+        }
+
+        let picks = table.rolls.as_ref().map_or(1, |d| d.roll(rng).max(0));
+        let mut weights: Vec<f32> = table.entries.iter().map(|e| e.weight_at(depth)).collect();
+        for _ in 0..picks {
+            let Some(i) = pick_weighted(&weights, rng) else { break };
+            if table.unique {
+                weights[i] = 0.0;
+            }
+            self.resolve(&table.entries[i].drop, depth, rng, out, nesting);
+        }
+    }
+
+    fn resolve(&self, drop: &LootDrop, depth: u32, rng: &mut Rng, out: &mut Vec<String>, nesting: usize) {
+        match drop {
+            LootDrop::Prefab(p) => out.push(p.clone()),
+            LootDrop::Table(t) => self.roll_into(t, depth, rng, out, nesting + 1),
+        }
+    }
+}
+
+/// Index chosen with probability proportional to its weight, or `None` if
+/// every weight is zero.
+pub fn pick_weighted(weights: &[f32], rng: &mut Rng) -> Option<usize> {
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut target = rng.next_f32() * total;
+    for (i, &w) in weights.iter().enumerate() {
+        if w <= 0.0 {
+            continue;
+        }
+        if target < w {
+            return Some(i);
+        }
+        target -= w;
+    }
+    // Float rounding: fall back to the last entry with weight.
+    weights.iter().rposition(|&w| w > 0.0)
+}
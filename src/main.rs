@@ -516,7 +516,9 @@ impl Scene for OptionsScene {
             self.window_config.physical_height = ph;
             self.window_config.logical_width   = pw;
             self.window_config.logical_height  = ph;
-            apply_window_settings(&engine.renderer.window, &self.window_config);
+            if let Some(window) = engine.renderer.window() {
+                apply_window_settings(window, &self.window_config);
+            }
         }
 
         // Row 1 — Window mode toggle
@@ -529,7 +531,9 @@ impl Scene for OptionsScene {
                 2 => WindowMode::Fullscreen,
                 _ => WindowMode::Windowed,
             };
-            apply_window_settings(&engine.renderer.window, &self.window_config);
+            if let Some(window) = engine.renderer.window() {
+                apply_window_settings(window, &self.window_config);
+            }
         }
 
         // Back button
@@ -1137,7 +1141,9 @@ impl Scene for GameScene {
                 WindowMode::Borderless |
                 WindowMode::Fullscreen => WindowMode::Windowed,
             };
-            apply_window_settings(&engine.renderer.window, &self.window_config);
+            if let Some(window) = engine.renderer.window() {
+                apply_window_settings(window, &self.window_config);
+            }
         }
 
         update_particles(&mut self.world, engine.dt());
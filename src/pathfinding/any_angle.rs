@@ -0,0 +1,227 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use glam::Vec2;
+
+use crate::geometry::line_of_sight;
+
+use super::weighted::DIRS_8;
+
+// =============================================================================
+// ANY-ANGLE PATHS
+// =============================================================================
+
+/// Theta*: A* whose paths may run at any angle instead of following the
+/// grid's staircase.
+///
+/// When a tile is reached, Theta* checks `line_of_sight` from the current
+/// tile's parent.  If the line is clear it links straight back to that
+/// parent, so the result is a short list of turning points.  Consecutive
+/// waypoints always have a clear Bresenham line between them.
+///
+/// Neighbours and the always-enterable goal work as in `astar_8dir`;
+/// distances are Euclidean.  Returns the waypoints including start and goal,
+/// or None if no path exists or `max_iterations` is exceeded.
+pub fn theta_star(
+    start: (i32, i32),
+    goal: (i32, i32),
+    width: i32,
+    height: i32,
+    is_passable: impl Fn(i32, i32) -> bool,
+    max_iterations: usize,
+) -> Option<Vec<(i32, i32)>> {
+    if width <= 0 || height <= 0 { return None; }
+    if start.0 < 0 || start.0 >= width || start.1 < 0 || start.1 >= height { return None; }
+    if goal.0  < 0 || goal.0  >= width || goal.1  < 0 || goal.1  >= height { return None; }
+
+    if start == goal {
+        return Some(vec![start]);
+    }
+
+    let size = (width * height) as usize;
+    let idx = |(x, y): (i32, i32)| (y * width + x) as usize;
+    let blocking = |x: i32, y: i32| !is_passable(x, y);
+    let dist = |a: (i32, i32), b: (i32, i32)| {
+        Vec2::new((a.0 - b.0) as f32, (a.1 - b.1) as f32).length()
+    };
+
+    // Non-negative floats order the same as their bit patterns, so f-scores
+    // go into the heap as u32.
+    let mut open: BinaryHeap<Reverse<(u32, i32, i32)>> = BinaryHeap::new();
+    let mut g_score = vec![f32::INFINITY; size];
+    let mut parent = vec![usize::MAX; size];
+    let mut closed = vec![false; size];
+
+    g_score[idx(start)] = 0.0;
+    parent[idx(start)] = idx(start);
+    open.push(Reverse((dist(start, goal).to_bits(), start.0, start.1)));
+
+    let mut iterations = 0;
+
+    while let Some(Reverse((_, cx, cy))) = open.pop() {
+        let current = (cx, cy);
+        let current_idx = idx(current);
+        if closed[current_idx] {
+            continue;
+        }
+        closed[current_idx] = true;
+
+        iterations += 1;
+        if iterations > max_iterations {
+            return None;
+        }
+
+        if current == goal {
+            let mut path = vec![goal];
+            let mut i = current_idx;
+            while parent[i] != i {
+                i = parent[i];
+                path.push((i as i32 % width, i as i32 / width));
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let parent_idx = parent[current_idx];
+        let parent_pos = (parent_idx as i32 % width, parent_idx as i32 / width);
+
+        for (dx, dy) in DIRS_8 {
+            let next = (cx + dx, cy + dy);
+            if next.0 < 0 || next.0 >= width || next.1 < 0 || next.1 >= height { continue; }
+            // Allow moving to goal even if "impassable" (it's often the target entity)
+            if next != goal && !is_passable(next.0, next.1) {
+                continue;
+            }
+            let next_idx = idx(next);
+            if closed[next_idx] {
+                continue;
+            }
+
+            // Path 2: skip the current tile if its parent can see `next`.
+            let (from, from_idx) = if line_of_sight(parent_pos.0, parent_pos.1, next.0, next.1, blocking) {
+                (parent_pos, parent_idx)
+            } else {
+                (current, current_idx)
+            };
+            let new_g = g_score[from_idx] + dist(from, next);
+            if new_g < g_score[next_idx] {
+                g_score[next_idx] = new_g;
+                parent[next_idx] = from_idx;
+                let f = new_g + dist(next, goal);
+                open.push(Reverse((f.to_bits(), next.0, next.1)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Shorten a tile path by skipping every tile that has line of sight past
+/// it.  This is string pulling: from each kept waypoint, jump to the furthest
+/// tile it can see along the path.
+///
+/// Cheaper than `theta_star`, but only as good as the input path's shape.
+/// Returns the kept waypoints, including the first and last tile.
+pub fn smooth_path(path: &[(i32, i32)], is_passable: impl Fn(i32, i32) -> bool) -> Vec<(i32, i32)> {
+    let Some(&first) = path.first() else { return Vec::new() };
+    let blocking = |x: i32, y: i32| !is_passable(x, y);
+    let mut waypoints = vec![first];
+    let mut anchor = 0;
+    while anchor + 1 < path.len() {
+        let (ax, ay) = path[anchor];
+        let mut next = anchor + 1;
+        while next + 1 < path.len() && line_of_sight(ax, ay, path[next + 1].0, path[next + 1].1, blocking) {
+            next += 1;
+        }
+        waypoints.push(path[next]);
+        anchor = next;
+    }
+    waypoints
+}
+
+/// Tile waypoints as world-space points at tile centres, for the `Camera`
+/// world (tile `(x, y)` covers `x * tile_w .. (x + 1) * tile_w`).
+pub fn waypoints_to_world(waypoints: &[(i32, i32)], tile_w: f32, tile_h: f32) -> Vec<Vec2> {
+    waypoints.iter()
+        .map(|&(x, y)| Vec2::new((x as f32 + 0.5) * tile_w, (y as f32 + 0.5) * tile_h))
+        .collect()
+}
+
+/// Moves a point along a polyline at a given speed.
+///
+/// Starts on the first waypoint.  Each `advance` moves the given distance
+/// along the line, carrying leftover movement around corners, so speed is
+/// constant however short the segments are.
+///
+/// ```ignore
+/// let path = theta_star(from, to, w, h, passable, 5000).unwrap();
+/// let mut follower = WaypointFollower::new(waypoints_to_world(&path, tw, th));
+/// // Each tick:
+/// let pos = follower.update(speed, engine.dt());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaypointFollower {
+    waypoints: Vec<Vec2>,
+    /// Index of the waypoint being headed for.
+    next: usize,
+    position: Vec2,
+}
+
+impl WaypointFollower {
+    pub fn new(waypoints: Vec<Vec2>) -> Self {
+        let position = waypoints.first().copied().unwrap_or(Vec2::ZERO);
+        Self { waypoints, next: 1, position }
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn waypoints(&self) -> &[Vec2] {
+        &self.waypoints
+    }
+
+    /// The waypoint being headed for, or None once the end is reached.
+    pub fn next_waypoint(&self) -> Option<Vec2> {
+        self.waypoints.get(self.next).copied()
+    }
+
+    /// True once the last waypoint has been reached.
+    pub fn is_done(&self) -> bool {
+        self.next >= self.waypoints.len()
+    }
+
+    /// Unit vector of the current heading; zero when done.
+    pub fn direction(&self) -> Vec2 {
+        self.next_waypoint().map_or(Vec2::ZERO, |w| (w - self.position).normalize_or_zero())
+    }
+
+    /// Distance left to travel along the remaining waypoints.
+    pub fn remaining_distance(&self) -> f32 {
+        let Some(next) = self.next_waypoint() else { return 0.0 };
+        let rest: f32 = self.waypoints[self.next..].windows(2).map(|w| w[0].distance(w[1])).sum();
+        self.position.distance(next) + rest
+    }
+
+    /// Move `distance` along the path and return the new position.
+    pub fn advance(&mut self, mut distance: f32) -> Vec2 {
+        while distance > 0.0 {
+            let Some(target) = self.next_waypoint() else { break };
+            let to_target = self.position.distance(target);
+            if to_target <= distance {
+                self.position = target;
+                distance -= to_target;
+                self.next += 1;
+            } else {
+                self.position += (target - self.position) * (distance / to_target);
+                break;
+            }
+        }
+        self.position
+    }
+
+    /// Move at `speed` units per second for `dt` seconds.
+    pub fn update(&mut self, speed: f32, dt: f32) -> Vec2 {
+        self.advance(speed * dt)
+    }
+}
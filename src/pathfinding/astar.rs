@@ -90,6 +90,13 @@ pub fn astar(
     None // No path found
 }
 
+/// Counters reported by the `*_with_stats` search variants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Nodes popped from the open list and expanded.
+    pub expanded: usize,
+}
+
 /// A* pathfinding with 8-directional movement (including diagonals).
 pub fn astar_8dir(
     start: (i32, i32),
@@ -99,12 +106,25 @@ pub fn astar_8dir(
     is_passable: impl Fn(i32, i32) -> bool,
     max_iterations: usize,
 ) -> Option<Vec<(i32, i32)>> {
-    if width <= 0 || height <= 0 { return None; }
-    if start.0 < 0 || start.0 >= width || start.1 < 0 || start.1 >= height { return None; }
-    if goal.0  < 0 || goal.0  >= width || goal.1  < 0 || goal.1  >= height { return None; }
+    astar_8dir_with_stats(start, goal, width, height, is_passable, max_iterations).0
+}
+
+/// `astar_8dir`, also reporting how many nodes were expanded.
+pub fn astar_8dir_with_stats(
+    start: (i32, i32),
+    goal: (i32, i32),
+    width: i32,
+    height: i32,
+    is_passable: impl Fn(i32, i32) -> bool,
+    max_iterations: usize,
+) -> (Option<Vec<(i32, i32)>>, SearchStats) {
+    let mut stats = SearchStats::default();
+    if width <= 0 || height <= 0 { return (None, stats); }
+    if start.0 < 0 || start.0 >= width || start.1 < 0 || start.1 >= height { return (None, stats); }
+    if goal.0  < 0 || goal.0  >= width || goal.1  < 0 || goal.1  >= height { return (None, stats); }
 
     if start == goal {
-        return Some(vec![start]);
+        return (Some(vec![start]), stats);
     }
 
     let size = (width * height) as usize;
@@ -128,13 +148,14 @@ pub fn astar_8dir(
     while let Some(Reverse((_, cx, cy))) = open.pop() {
         iterations += 1;
         if iterations > max_iterations {
-            return None;
+            return (None, stats);
         }
+        stats.expanded = iterations;
 
         let current = (cx, cy);
 
         if current == goal {
-            return Some(reconstruct_path(&came_from, start, goal, width));
+            return (Some(reconstruct_path(&came_from, start, goal, width)), stats);
         }
 
         let current_idx = (cy * width + cx) as usize;
@@ -167,7 +188,7 @@ pub fn astar_8dir(
         }
     }
 
-    None
+    (None, stats)
 }
 
 /// Reconstruct path from came_from map.
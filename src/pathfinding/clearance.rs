@@ -0,0 +1,184 @@
+use super::weighted::{AStarOptions, DiagonalRule, Heuristic, astar_weighted};
+
+// =============================================================================
+// CLEARANCE MAPS (MULTI-TILE AGENTS)
+// =============================================================================
+///
+/// "True clearance" annotation of a grid, for pathfinding agents bigger than
+/// one tile.
+///
+/// `clearance(x, y)` is the side of the largest all-passable square whose
+/// top-left corner is (x, y); 0 on walls.  An N×N body fits at (x, y) exactly
+/// when the clearance is at least N.  A separate run-length table answers
+/// the same question for rectangular N×M bodies.
+///
+/// Positions are the top-left tile of the body, like `Position` + `Size` in
+/// the demo.
+#[derive(Debug, Clone)]
+pub struct ClearanceMap {
+    width: i32,
+    height: i32,
+    passable: Vec<bool>,
+    clearance: Vec<u32>,
+    /// Passable tiles from each tile rightwards, including itself.
+    run: Vec<u32>,
+}
+
+impl ClearanceMap {
+    pub fn new(width: i32, height: i32, is_passable: impl Fn(i32, i32) -> bool) -> Self {
+        let width = width.max(0);
+        let height = height.max(0);
+        let size = (width * height) as usize;
+        let mut map = Self {
+            width,
+            height,
+            passable: (0..size as i32).map(|i| is_passable(i % width, i / width)).collect(),
+            clearance: vec![0; size],
+            run: vec![0; size],
+        };
+        for y in (0..height).rev() {
+            map.annotate_row(y, width - 1);
+        }
+        map
+    }
+
+    pub fn width(&self) -> i32 { self.width }
+    pub fn height(&self) -> i32 { self.height }
+
+    #[inline]
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    #[inline]
+    fn idx(&self, x: i32, y: i32) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    pub fn is_passable(&self, x: i32, y: i32) -> bool {
+        self.in_bounds(x, y) && self.passable[self.idx(x, y)]
+    }
+
+    /// Side of the largest passable square with its top-left corner at
+    /// (x, y); 0 for walls and out-of-bounds tiles.
+    pub fn clearance(&self, x: i32, y: i32) -> u32 {
+        if self.in_bounds(x, y) { self.clearance[self.idx(x, y)] } else { 0 }
+    }
+
+    /// True if a `w`×`h` body with its top-left corner at (x, y) covers only
+    /// passable tiles.
+    pub fn fits(&self, x: i32, y: i32, w: i32, h: i32) -> bool {
+        if w <= 0 || h <= 0 {
+            return false;
+        }
+        let c = self.clearance(x, y) as i32;
+        if c >= w.max(h) {
+            return true;
+        }
+        if c < w.min(h) {
+            return false;
+        }
+        // Rectangular body: every covered row needs a long enough run.
+        (y..y + h).all(|row| self.in_bounds(x, row) && self.run[self.idx(x, row)] as i32 >= w)
+    }
+
+    /// Change one tile's passability and repair the annotation.  Only tiles
+    /// above and to the left can change, and the repair stops at the first
+    /// row that comes out unchanged.
+    pub fn set_passable(&mut self, x: i32, y: i32, passable: bool) {
+        if !self.in_bounds(x, y) {
+            return;
+        }
+        let i = self.idx(x, y);
+        if self.passable[i] == passable {
+            return;
+        }
+        self.passable[i] = passable;
+        for row in (0..=y).rev() {
+            if !self.annotate_row(row, x) && row != y {
+                break;
+            }
+        }
+    }
+
+    /// Recompute `run` and `clearance` for columns `0..=last` of a row, from
+    /// right to left.  Returns true if any value changed.
+    fn annotate_row(&mut self, y: i32, last: i32) -> bool {
+        let mut changed = false;
+        for x in (0..=last).rev() {
+            let i = self.idx(x, y);
+            let (run, clearance) = if self.passable[i] {
+                let right = if x + 1 < self.width { self.run[i + 1] } else { 0 };
+                let square = self.clearance(x + 1, y)
+                    .min(self.clearance(x, y + 1))
+                    .min(self.clearance(x + 1, y + 1));
+                (right + 1, square + 1)
+            } else {
+                (0, 0)
+            };
+            changed |= self.run[i] != run || self.clearance[i] != clearance;
+            self.run[i] = run;
+            self.clearance[i] = clearance;
+        }
+        changed
+    }
+}
+
+/// 4-directional A* for a `size = (w, h)` body, which only visits positions
+/// where the whole body fits.  Same costs and heuristic as `astar`.
+///
+/// Unlike `astar`, the goal must fit the body too: to chase a target, aim for
+/// a position next to it.
+pub fn astar_sized(
+    start: (i32, i32),
+    goal: (i32, i32),
+    map: &ClearanceMap,
+    size: (i32, i32),
+    max_iterations: usize,
+) -> Option<Vec<(i32, i32)>> {
+    let options = AStarOptions::new()
+        .with_goal_always_enterable(false)
+        .with_max_iterations(max_iterations);
+    sized_search(start, goal, map, size, &options)
+}
+
+/// 8-directional A* for a `size = (w, h)` body, with `astar_8dir` costs.
+///
+/// A diagonal step needs the body to fit after both of the straight steps it
+/// combines, so large bodies never clip a wall corner.
+pub fn astar_sized_8dir(
+    start: (i32, i32),
+    goal: (i32, i32),
+    map: &ClearanceMap,
+    size: (i32, i32),
+    max_iterations: usize,
+) -> Option<Vec<(i32, i32)>> {
+    let options = AStarOptions::new()
+        .with_heuristic(Heuristic::IntegerOctile)
+        .with_diagonals(DiagonalRule::NoCornerCutting)
+        .with_min_cost(10)
+        .with_goal_always_enterable(false)
+        .with_max_iterations(max_iterations);
+    sized_search(start, goal, map, size, &options)
+}
+
+fn sized_search(
+    start: (i32, i32),
+    goal: (i32, i32),
+    map: &ClearanceMap,
+    (w, h): (i32, i32),
+    options: &AStarOptions,
+) -> Option<Vec<(i32, i32)>> {
+    if !map.fits(start.0, start.1, w, h) || !map.fits(goal.0, goal.1, w, h) {
+        return None;
+    }
+    let eight = options.diagonals != DiagonalRule::Never;
+    let cost = |from: (i32, i32), to: (i32, i32)| {
+        map.fits(to.0, to.1, w, h).then_some(match (eight, from.0 != to.0 && from.1 != to.1) {
+            (false, _) => 1,
+            (true, false) => 10,
+            (true, true) => 14,
+        })
+    };
+    astar_weighted(start, goal, map.width, map.height, cost, options).map(|(path, _)| path)
+}
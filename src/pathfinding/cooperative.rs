@@ -0,0 +1,321 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use super::weighted::{DIRS_4, DIRS_8, DiagonalRule};
+
+// =============================================================================
+// COOPERATIVE PATHFINDING
+// =============================================================================
+
+/// An agent's `(start, goal)` pair.
+pub type StartGoal = ((i32, i32), (i32, i32));
+
+/// A tile at a time step: `(x, y, t)`.
+type SpaceTime = (i32, i32, u32);
+
+/// Which agent holds each tile at each time step.
+///
+/// Cooperative planning fills the table with every planned path so later
+/// agents route around earlier ones.  Callers can add their own reservations
+/// before planning (the player's position, a door being opened) using any
+/// agent id not used by the planned group.
+#[derive(Debug, Clone, Default)]
+pub struct ReservationTable {
+    cells: HashMap<(i32, i32, u32), usize>,
+}
+
+impl ReservationTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Reserve `pos` at time `t` for `agent`, replacing any earlier holder.
+    pub fn reserve(&mut self, pos: (i32, i32), t: u32, agent: usize) {
+        self.cells.insert((pos.0, pos.1, t), agent);
+    }
+
+    /// Drop the reservation of `pos` at time `t` if `agent` holds it.
+    pub fn release(&mut self, pos: (i32, i32), t: u32, agent: usize) {
+        if self.owner(pos, t) == Some(agent) {
+            self.cells.remove(&(pos.0, pos.1, t));
+        }
+    }
+
+    /// Reserve every step of a path, where `path[t]` is the position at time
+    /// `t`.
+    pub fn reserve_path(&mut self, agent: usize, path: &[(i32, i32)]) {
+        for (t, &pos) in path.iter().enumerate() {
+            self.reserve(pos, t as u32, agent);
+        }
+    }
+
+    /// The agent holding `pos` at time `t`, if any.
+    pub fn owner(&self, pos: (i32, i32), t: u32) -> Option<usize> {
+        self.cells.get(&(pos.0, pos.1, t)).copied()
+    }
+
+    /// True if `agent` may stand on `pos` at time `t`.
+    pub fn is_free(&self, pos: (i32, i32), t: u32, agent: usize) -> bool {
+        self.owner(pos, t).is_none_or(|owner| owner == agent)
+    }
+
+    /// True if `agent` may step from `from` (time `t`) to `to` (time `t + 1`):
+    /// the destination is free and no other agent makes the opposite move,
+    /// which would have the two pass through each other.
+    pub fn can_move(&self, from: (i32, i32), to: (i32, i32), t: u32, agent: usize) -> bool {
+        if !self.is_free(to, t + 1, agent) {
+            return false;
+        }
+        if from == to {
+            return true;
+        }
+        match (self.owner(to, t), self.owner(from, t + 1)) {
+            (Some(a), Some(b)) => a != b || a == agent,
+            _ => true,
+        }
+    }
+}
+
+/// Windowed cooperative A* for a group of agents.
+///
+/// Agents are planned one after another in slice order, which doubles as
+/// their priority.  Each agent runs an A* through space *and time*.  Every
+/// turn it may move to an adjacent tile or wait in place, avoiding tiles
+/// and swaps already reserved by higher-priority agents.  Its plan is then
+/// reserved for the agents after it.  Because the order is fixed, a
+/// lower-priority agent can fail to find a plan that a different order would
+/// have allowed.
+///
+/// Plans cover `window` turns.  Agents follow the first step or few and then
+/// replan, which keeps the search cheap and lets the group react to change.
+/// The heuristic is each agent's true walking distance to its goal, ignoring
+/// other agents.  This is what makes a partial plan head somewhere useful.
+///
+/// Every move or wait costs one turn; diagonal moves also take one turn.
+/// Waiting on the goal is free, so agents that arrive early stay there.
+/// Goals are always enterable.
+///
+/// Planning is fully deterministic.  Ties are broken by remaining distance,
+/// then by time, then by tile position.  The same inputs always produce the
+/// same plans, which keeps turn-based games reproducible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CooperativePlanner {
+    pub width: i32,
+    pub height: i32,
+    /// Number of turns each plan covers; 0 is treated as 1.
+    pub window: u32,
+    pub diagonals: DiagonalRule,
+    /// Node expansion limit per agent.
+    pub max_iterations: usize,
+}
+
+impl CooperativePlanner {
+    /// 4-directional planner with a 16-turn window.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self { width, height, window: 16, diagonals: DiagonalRule::Never, max_iterations: 10_000 }
+    }
+
+    pub fn with_window(mut self, window: u32) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    pub fn with_diagonals(mut self, diagonals: DiagonalRule) -> Self {
+        self.diagonals = diagonals;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Plan every `(start, goal)` pair.
+    ///
+    /// Each plan holds the agent's position for each turn from 0 (its start)
+    /// to `window`, so it is `window + 1` long.  An agent gets `None` when its
+    /// goal is unreachable or no plan avoids the agents before it.  Such an
+    /// agent should stay where it is.
+    pub fn plan(
+        &self,
+        agents: &[StartGoal],
+        is_passable: impl Fn(i32, i32) -> bool,
+    ) -> Vec<Option<Vec<(i32, i32)>>> {
+        self.plan_with(agents, is_passable, &mut ReservationTable::new())
+    }
+
+    /// `plan`, honouring and extending an existing reservation table.  Agent
+    /// ids in the table are indices into `agents`.
+    pub fn plan_with(
+        &self,
+        agents: &[StartGoal],
+        is_passable: impl Fn(i32, i32) -> bool,
+        table: &mut ReservationTable,
+    ) -> Vec<Option<Vec<(i32, i32)>>> {
+        // Until an agent has been planned it holds its start for the first
+        // turn.  Higher-priority agents cannot shove it, so it can always wait.
+        for (id, &(start, _)) in agents.iter().enumerate() {
+            table.reserve(start, 0, id);
+            table.reserve(start, 1, id);
+        }
+
+        let mut plans = Vec::with_capacity(agents.len());
+        for (id, &(start, goal)) in agents.iter().enumerate() {
+            let plan = self.plan_agent(id, start, goal, &is_passable, table);
+            match &plan {
+                Some(path) => {
+                    // Free the start for followers if the agent leaves on turn 1.
+                    if path[1] != start {
+                        table.release(start, 1, id);
+                    }
+                    table.reserve_path(id, path);
+                }
+                None => {
+                    for t in 0..=self.window() {
+                        table.reserve(start, t, id);
+                    }
+                }
+            }
+            plans.push(plan);
+        }
+        plans
+    }
+
+    /// `window`, at least 1 so every plan has a turn-1 position.
+    fn window(&self) -> u32 {
+        self.window.max(1)
+    }
+
+    fn in_bounds(&self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    fn neighborhood(&self) -> &'static [(i32, i32)] {
+        if self.diagonals == DiagonalRule::Never { &DIRS_4 } else { &DIRS_8 }
+    }
+
+    /// Whether a single step between adjacent tiles is allowed by the map.
+    fn can_step<F: Fn(i32, i32) -> bool>(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        enterable: &F,
+    ) -> bool {
+        if !self.in_bounds(to) || !enterable(to.0, to.1) {
+            return false;
+        }
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        if dx != 0 && dy != 0 && self.diagonals == DiagonalRule::NoCornerCutting {
+            return enterable(from.0 + dx, from.1) && enterable(from.0, from.1 + dy);
+        }
+        true
+    }
+
+    /// Turns needed to reach `goal` from every tile, ignoring other agents.
+    fn distances<F: Fn(i32, i32) -> bool>(&self, goal: (i32, i32), enterable: &F) -> Vec<u32> {
+        let mut dist = vec![u32::MAX; (self.width * self.height) as usize];
+        let idx = |(x, y): (i32, i32)| (y * self.width + x) as usize;
+        dist[idx(goal)] = 0;
+        let mut queue = VecDeque::from([goal]);
+        while let Some(pos) = queue.pop_front() {
+            let d = dist[idx(pos)];
+            for &(dx, dy) in self.neighborhood() {
+                let prev = (pos.0 + dx, pos.1 + dy);
+                // Walking backwards: the agent steps from `prev` onto `pos`.
+                if self.in_bounds(prev) && dist[idx(prev)] == u32::MAX
+                    && enterable(prev.0, prev.1) && self.can_step(prev, pos, enterable)
+                {
+                    dist[idx(prev)] = d + 1;
+                    queue.push_back(prev);
+                }
+            }
+        }
+        dist
+    }
+
+    fn plan_agent<F: Fn(i32, i32) -> bool>(
+        &self,
+        id: usize,
+        start: (i32, i32),
+        goal: (i32, i32),
+        is_passable: &F,
+        table: &ReservationTable,
+    ) -> Option<Vec<(i32, i32)>> {
+        if !self.in_bounds(start) || !self.in_bounds(goal) {
+            return None;
+        }
+        let enterable = |x: i32, y: i32| (x, y) == goal || (x, y) == start || is_passable(x, y);
+        let dist = self.distances(goal, &enterable);
+        let h = |(x, y): (i32, i32)| dist[(y * self.width + x) as usize];
+        if h(start) == u32::MAX {
+            return None;
+        }
+
+        // Space-time nodes: (x, y, t) -> (g, parent).
+        let mut nodes: HashMap<SpaceTime, (u32, Option<SpaceTime>)> = HashMap::new();
+        // (f, h, t, y, x): lower f, then closer to the goal, then earlier,
+        // then row-major position.
+        let mut open = BinaryHeap::new();
+        nodes.insert((start.0, start.1, 0), (0, None));
+        open.push(Reverse((h(start), h(start), 0, start.1, start.0)));
+
+        let mut iterations = 0;
+        while let Some(Reverse((f, _, t, y, x))) = open.pop() {
+            let g = nodes[&(x, y, t)].0;
+            if f > g + h((x, y)) {
+                continue; // stale entry
+            }
+            iterations += 1;
+            if iterations > self.max_iterations {
+                return None;
+            }
+
+            let at_goal = (x, y) == goal;
+            let window = self.window();
+            if t == window || (at_goal && (t..=window).all(|s| table.is_free(goal, s, id))) {
+                let mut path = self.reconstruct(&nodes, (x, y, t));
+                path.resize(window as usize + 1, (x, y));
+                return Some(path);
+            }
+
+            let wait = std::iter::once((0, 0));
+            for (dx, dy) in wait.chain(self.neighborhood().iter().copied()) {
+                let next = (x + dx, y + dy);
+                if (dx, dy) != (0, 0) && !self.can_step((x, y), next, &enterable) {
+                    continue;
+                }
+                if h(next) == u32::MAX || !table.can_move((x, y), next, t, id) {
+                    continue;
+                }
+                let step = if at_goal && next == goal { 0 } else { 1 };
+                let ng = g + step;
+                let key = (next.0, next.1, t + 1);
+                if nodes.get(&key).is_some_and(|&(old, _)| old <= ng) {
+                    continue;
+                }
+                nodes.insert(key, (ng, Some((x, y, t))));
+                open.push(Reverse((ng + h(next), h(next), t + 1, next.1, next.0)));
+            }
+        }
+        None
+    }
+
+    fn reconstruct(
+        &self,
+        nodes: &HashMap<SpaceTime, (u32, Option<SpaceTime>)>,
+        end: SpaceTime,
+    ) -> Vec<(i32, i32)> {
+        let mut path = vec![(end.0, end.1)];
+        let mut node = end;
+        while let Some(parent) = nodes[&node].1 {
+            path.push((parent.0, parent.1));
+            node = parent;
+        }
+        path.reverse();
+        path
+    }
+}
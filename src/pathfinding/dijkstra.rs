@@ -1,4 +1,5 @@
-use std::collections::VecDeque;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
 
 // =============================================================================
 // DIJKSTRA MAPS
@@ -15,10 +16,37 @@ use std::collections::VecDeque;
 /// - 0.0 = goal
 /// - Higher values = farther from goal
 /// - f32::MAX = unreachable
+///
+/// Maps built with `weighted` remember their per-tile costs and neighbourhood,
+/// so they can be edited and `rescan`ned, or turned into flee maps.
+#[derive(Clone)]
 pub struct DijkstraMap {
     pub width: i32,
     pub height: i32,
     values: Vec<f32>,
+    /// Cost of entering each tile; `f32::INFINITY` for impassable tiles.
+    costs: Vec<f32>,
+    /// Cost multiplier for diagonal steps; `None` for 4-directional maps.
+    diagonal: Option<f32>,
+}
+
+const DIRS_4: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+const DIRS_8: [(i32, i32); 8] = [(0, -1), (0, 1), (-1, 0), (1, 0), (-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+/// Min-heap entry ordered by value.
+struct Open(f32, usize);
+
+impl PartialEq for Open {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl Eq for Open {}
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0).then_with(|| other.1.cmp(&self.1))
+    }
 }
 
 impl DijkstraMap {
@@ -35,11 +63,14 @@ impl DijkstraMap {
         is_passable: impl Fn(i32, i32) -> bool,
     ) -> Self {
         if width <= 0 || height <= 0 {
-            return Self { width, height, values: Vec::new() };
+            return Self { width, height, values: Vec::new(), costs: Vec::new(), diagonal: None };
         }
 
         let size = (width * height) as usize;
         let mut values = vec![f32::MAX; size];
+        let costs: Vec<f32> = (0..size as i32)
+            .map(|i| if is_passable(i % width, i / width) { 1.0 } else { f32::INFINITY })
+            .collect();
 
         // BFS from all goal cells simultaneously — O(cells) instead of O(cells²).
         let mut queue: VecDeque<(i32, i32)> = VecDeque::new();
@@ -59,8 +90,8 @@ impl DijkstraMap {
                 let nx = x + dx;
                 let ny = y + dy;
                 if nx < 0 || ny < 0 || nx >= width || ny >= height { continue; }
-                if !is_passable(nx, ny) { continue; }
                 let nidx = (ny * width + nx) as usize;
+                if costs[nidx] == f32::INFINITY { continue; }
                 if values[nidx] == f32::MAX {
                     values[nidx] = current + 1.0;
                     queue.push_back((nx, ny));
@@ -68,7 +99,115 @@ impl DijkstraMap {
             }
         }
 
-        Self { width, height, values }
+        Self { width, height, values, costs, diagonal: None }
+    }
+
+    /// Create a weighted Dijkstra map.
+    ///
+    /// # Arguments
+    /// * `width`, `height` - Map dimensions
+    /// * `goals` - Goal positions with their starting values (usually 0.0;
+    ///   lower values make a goal more attractive)
+    /// * `cost` - Cost of entering a tile, or `None` if it is impassable
+    /// * `diagonal` - Cost multiplier for diagonal steps (`Some(1.0)` for
+    ///   Chebyshev movement, `Some(SQRT_2)` for Euclidean-like distances), or
+    ///   `None` for 4-directional propagation
+    pub fn weighted(
+        width: i32,
+        height: i32,
+        goals: &[((i32, i32), f32)],
+        cost: impl Fn(i32, i32) -> Option<f32>,
+        diagonal: Option<f32>,
+    ) -> Self {
+        let size = (width.max(0) * height.max(0)) as usize;
+        let costs: Vec<f32> = (0..size as i32)
+            .map(|i| cost(i % width, i / width).unwrap_or(f32::INFINITY))
+            .collect();
+        let mut map = Self { width, height, values: vec![f32::MAX; size], costs, diagonal };
+        for &((gx, gy), value) in goals {
+            if map.in_bounds(gx, gy) {
+                let idx = (gy * width + gx) as usize;
+                map.values[idx] = map.values[idx].min(value);
+            }
+        }
+        map.rescan();
+        map
+    }
+
+    #[inline]
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    fn directions(&self) -> &'static [(i32, i32)] {
+        if self.diagonal.is_some() { &DIRS_8 } else { &DIRS_4 }
+    }
+
+    /// Set the value at (x, y), e.g. to add or remove a goal before `rescan`.
+    pub fn set(&mut self, x: i32, y: i32, value: f32) {
+        if self.in_bounds(x, y) {
+            self.values[(y * self.width + x) as usize] = value;
+        }
+    }
+
+    /// Change the cost of entering (x, y); `None` makes it impassable.
+    /// Call `rescan` (after resetting affected values) to propagate it.
+    pub fn set_cost(&mut self, x: i32, y: i32, cost: Option<f32>) {
+        if self.in_bounds(x, y) {
+            self.costs[(y * self.width + x) as usize] = cost.unwrap_or(f32::INFINITY);
+        }
+    }
+
+    /// Relax the map in place: lower every value that can be reached more
+    /// cheaply from a neighbour, until no value changes.
+    ///
+    /// Every reachable cell acts as a source with its current value, so this
+    /// is what turns an inverted or scaled map back into a consistent one.
+    pub fn rescan(&mut self) {
+        let width = self.width;
+        let mut open: BinaryHeap<Open> = self
+            .values
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v != f32::MAX)
+            .map(|(i, &v)| Open(v, i))
+            .collect();
+
+        while let Some(Open(value, idx)) = open.pop() {
+            if value > self.values[idx] {
+                continue; // stale entry
+            }
+            let (x, y) = (idx as i32 % width, idx as i32 / width);
+            for &(dx, dy) in self.directions() {
+                let (nx, ny) = (x + dx, y + dy);
+                if !self.in_bounds(nx, ny) { continue; }
+                let nidx = (ny * width + nx) as usize;
+                let step = if dx != 0 && dy != 0 {
+                    self.costs[nidx] * self.diagonal.unwrap_or(1.0)
+                } else {
+                    self.costs[nidx]
+                };
+                let candidate = value + step;
+                if candidate < self.values[nidx] {
+                    self.values[nidx] = candidate;
+                    open.push(Open(candidate, nidx));
+                }
+            }
+        }
+    }
+
+    /// Build a "safety map" for fleeing from this map's goals.
+    ///
+    /// Values are multiplied by `-coefficient` and then rescanned, so
+    /// following `direction_to_goal` on the result leads away from the goals
+    /// while still preferring routes towards open, distant areas over dead
+    /// ends.  A coefficient around 1.2 works well: larger values flee more
+    /// directly, values near 1.0 tolerate passing closer to danger.
+    pub fn flee_map(&self, coefficient: f32) -> DijkstraMap {
+        let mut flee = self.clone();
+        flee.multiply(-coefficient);
+        flee.rescan();
+        flee
     }
 
     /// Get the value at (x, y). Returns f32::MAX for out of bounds.
@@ -81,6 +220,7 @@ impl DijkstraMap {
     }
 
     /// Find the direction to move to get closer to goals (toward lower values).
+    /// Diagonal steps are considered on 8-directional maps.
     pub fn direction_to_goal(&self, x: i32, y: i32) -> (i32, i32) {
         let current = self.get(x, y);
         if current == 0.0 || current == f32::MAX {
@@ -90,7 +230,7 @@ impl DijkstraMap {
         let mut best_dir = (0, 0);
         let mut best_value = current;
 
-        for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+        for &(dx, dy) in self.directions() {
             let value = self.get(x + dx, y + dy);
             if value < best_value {
                 best_value = value;
@@ -102,6 +242,7 @@ impl DijkstraMap {
     }
 
     /// Find the direction to move to get away from goals (toward higher values).
+    /// Diagonal steps are considered on 8-directional maps.
     pub fn direction_away(&self, x: i32, y: i32) -> (i32, i32) {
         let current = self.get(x, y);
         if current == f32::MAX {
@@ -111,7 +252,7 @@ impl DijkstraMap {
         let mut best_dir = (0, 0);
         let mut best_value = current;
 
-        for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+        for &(dx, dy) in self.directions() {
             let value = self.get(x + dx, y + dy);
             if value != f32::MAX && value > best_value {
                 best_value = value;
@@ -0,0 +1,336 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::astar::SearchStats;
+use super::weighted::{DIRS_4, DIRS_8, DiagonalRule};
+
+// =============================================================================
+// D* LITE
+// =============================================================================
+///
+/// Incremental pathfinder for maps that change while an agent walks them.
+///
+/// D* Lite searches backwards from the goal and keeps its whole search state
+/// between queries.  When doors open, walls are dug or the agent moves, only
+/// the part of the search affected by the change is repaired.  A fresh
+/// search is never needed, so replanning after small edits usually expands a
+/// small fraction of the nodes a new A* would.
+///
+/// Straight steps cost 10 and diagonals 14, as in `astar_8dir`.  With
+/// `DiagonalRule::Never` every step costs 10.  The goal is always enterable.
+///
+/// ```ignore
+/// let mut planner = DStarLite::new(w, h, pos, goal, |x, y| !is_wall(x, y), DiagonalRule::NoCornerCutting);
+/// while let Some(step) = planner.next_step() {
+///     pos = step;
+///     planner.set_start(pos);
+///     planner.update_cells(&map.take_changes()); // [((x, y), passable)]
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DStarLite {
+    width: i32,
+    height: i32,
+    diagonals: DiagonalRule,
+    passable: Vec<bool>,
+    start: (i32, i32),
+    goal: (i32, i32),
+    /// Start position when `km` was last updated.
+    last_start: (i32, i32),
+    /// Accumulated heuristic offset from start moves.
+    km: u32,
+    g: Vec<u32>,
+    rhs: Vec<u32>,
+    /// Lazy-deletion heap of `(key, index)`; an entry is live only while it
+    /// matches `queued[index]`.
+    open: BinaryHeap<Reverse<(Key, usize)>>,
+    queued: Vec<Option<Key>>,
+    stats: SearchStats,
+}
+
+type Key = (u32, u32);
+
+const INF: u32 = u32::MAX;
+
+impl DStarLite {
+    pub fn new(
+        width: i32,
+        height: i32,
+        start: (i32, i32),
+        goal: (i32, i32),
+        is_passable: impl Fn(i32, i32) -> bool,
+        diagonals: DiagonalRule,
+    ) -> Self {
+        let width = width.max(0);
+        let height = height.max(0);
+        let size = (width * height) as usize;
+        let mut planner = Self {
+            width,
+            height,
+            diagonals,
+            passable: (0..size as i32).map(|i| is_passable(i % width, i / width)).collect(),
+            start,
+            goal,
+            last_start: start,
+            km: 0,
+            g: vec![INF; size],
+            rhs: vec![INF; size],
+            open: BinaryHeap::new(),
+            queued: vec![None; size],
+            stats: SearchStats::default(),
+        };
+        planner.reset();
+        planner
+    }
+
+    pub fn width(&self) -> i32 { self.width }
+    pub fn height(&self) -> i32 { self.height }
+    pub fn start(&self) -> (i32, i32) { self.start }
+    pub fn goal(&self) -> (i32, i32) { self.goal }
+
+    /// Nodes expanded by the most recent `find_path` or `next_step`.
+    pub fn last_stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    pub fn is_passable(&self, x: i32, y: i32) -> bool {
+        self.in_bounds((x, y)) && self.passable[self.idx((x, y))]
+    }
+
+    /// Tell the planner the agent has moved.  Cheap: the search state is kept.
+    pub fn set_start(&mut self, start: (i32, i32)) {
+        self.start = start;
+    }
+
+    /// Change the goal.  The search state is built around the goal, so this
+    /// starts over.
+    pub fn set_goal(&mut self, goal: (i32, i32)) {
+        self.goal = goal;
+        self.reset();
+    }
+
+    /// Apply a batch of map edits, `((x, y), passable)`.  Cells whose
+    /// passability did not change are ignored.
+    pub fn update_cells(&mut self, changes: &[((i32, i32), bool)]) {
+        let mut changed = Vec::new();
+        for &(pos, passable) in changes {
+            if !self.in_bounds(pos) {
+                continue;
+            }
+            let i = self.idx(pos);
+            if self.passable[i] != passable {
+                self.passable[i] = passable;
+                changed.push(pos);
+            }
+        }
+        if changed.is_empty() {
+            return;
+        }
+
+        // Keys computed before the agent moved are now too large by the
+        // heuristic distance it travelled.
+        self.km = self.km.saturating_add(self.heuristic(self.last_start, self.start));
+        self.last_start = self.start;
+
+        // A cell's passability affects stepping onto it, and with
+        // `NoCornerCutting` diagonals squeezing past it; either way only edges
+        // out of its 8 neighbours.
+        for (x, y) in changed {
+            for (dx, dy) in DIRS_8 {
+                let n = (x + dx, y + dy);
+                if self.in_bounds(n) {
+                    self.update_vertex(self.idx(n));
+                }
+            }
+        }
+    }
+
+    /// Shortest path from the current start to the goal and its cost, or None
+    /// if the goal is unreachable.
+    pub fn find_path(&mut self) -> Option<(Vec<(i32, i32)>, u32)> {
+        if !self.in_bounds(self.start) || !self.in_bounds(self.goal) {
+            return None;
+        }
+        self.compute_shortest_path();
+        let cost = self.g[self.idx(self.start)];
+        if cost == INF {
+            return None;
+        }
+        let mut path = vec![self.start];
+        let mut pos = self.start;
+        while pos != self.goal {
+            pos = self.best_successor(pos)?;
+            path.push(pos);
+            if path.len() > self.g.len() {
+                return None;
+            }
+        }
+        Some((path, cost))
+    }
+
+    /// First step towards the goal, or None if at the goal or it is
+    /// unreachable.
+    pub fn next_step(&mut self) -> Option<(i32, i32)> {
+        if !self.in_bounds(self.start) || !self.in_bounds(self.goal) || self.start == self.goal {
+            return None;
+        }
+        self.compute_shortest_path();
+        if self.g[self.idx(self.start)] == INF {
+            return None;
+        }
+        self.best_successor(self.start)
+    }
+
+    fn reset(&mut self) {
+        self.g.fill(INF);
+        self.rhs.fill(INF);
+        self.queued.fill(None);
+        self.open.clear();
+        self.km = 0;
+        self.last_start = self.start;
+        if self.in_bounds(self.goal) {
+            let gi = self.idx(self.goal);
+            self.rhs[gi] = 0;
+            self.enqueue(gi);
+        }
+    }
+
+    #[inline]
+    fn in_bounds(&self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    #[inline]
+    fn idx(&self, (x, y): (i32, i32)) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    #[inline]
+    fn pos(&self, i: usize) -> (i32, i32) {
+        (i as i32 % self.width, i as i32 / self.width)
+    }
+
+    fn neighborhood(&self) -> &'static [(i32, i32)] {
+        if self.diagonals == DiagonalRule::Never { &DIRS_4 } else { &DIRS_8 }
+    }
+
+    fn heuristic(&self, a: (i32, i32), b: (i32, i32)) -> u32 {
+        let dx = (a.0 - b.0).unsigned_abs();
+        let dy = (a.1 - b.1).unsigned_abs();
+        if self.diagonals == DiagonalRule::Never {
+            10 * (dx + dy)
+        } else {
+            10 * dx.max(dy) + 4 * dx.min(dy)
+        }
+    }
+
+    /// Cost of stepping between adjacent tiles, or INF if not allowed.
+    fn cost(&self, from: (i32, i32), to: (i32, i32)) -> u32 {
+        if !self.in_bounds(to) || (to != self.goal && !self.passable[self.idx(to)]) {
+            return INF;
+        }
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        if dx != 0 && dy != 0 {
+            if self.diagonals == DiagonalRule::NoCornerCutting
+                && (!self.is_passable(from.0 + dx, from.1) || !self.is_passable(from.0, from.1 + dy))
+            {
+                return INF;
+            }
+            14
+        } else {
+            10
+        }
+    }
+
+    fn key(&self, i: usize) -> Key {
+        let best = self.g[i].min(self.rhs[i]);
+        let h = self.heuristic(self.start, self.pos(i));
+        (best.saturating_add(h).saturating_add(self.km), best)
+    }
+
+    fn enqueue(&mut self, i: usize) {
+        let key = self.key(i);
+        self.queued[i] = Some(key);
+        self.open.push(Reverse((key, i)));
+    }
+
+    /// Smallest live key in the open list, discarding stale entries.
+    fn top(&mut self) -> Option<(Key, usize)> {
+        while let Some(&Reverse((key, i))) = self.open.peek() {
+            if self.queued[i] == Some(key) {
+                return Some((key, i));
+            }
+            self.open.pop();
+        }
+        None
+    }
+
+    fn update_vertex(&mut self, i: usize) {
+        let u = self.pos(i);
+        if u != self.goal {
+            let mut best = INF;
+            for &(dx, dy) in self.neighborhood() {
+                let v = (u.0 + dx, u.1 + dy);
+                let c = self.cost(u, v);
+                if c != INF {
+                    best = best.min(c.saturating_add(self.g[self.idx(v)]));
+                }
+            }
+            self.rhs[i] = best;
+        }
+        if self.g[i] != self.rhs[i] {
+            self.enqueue(i);
+        } else {
+            self.queued[i] = None;
+        }
+    }
+
+    fn update_predecessors(&mut self, u: (i32, i32)) {
+        for &(dx, dy) in self.neighborhood() {
+            let s = (u.0 - dx, u.1 - dy);
+            if self.in_bounds(s) {
+                self.update_vertex(self.idx(s));
+            }
+        }
+    }
+
+    fn compute_shortest_path(&mut self) {
+        self.stats = SearchStats::default();
+        let si = self.idx(self.start);
+        while let Some((old_key, i)) = self.top() {
+            if old_key >= self.key(si) && self.rhs[si] == self.g[si] {
+                break;
+            }
+            self.stats.expanded += 1;
+            let new_key = self.key(i);
+            if old_key < new_key {
+                self.enqueue(i);
+            } else if self.g[i] > self.rhs[i] {
+                self.g[i] = self.rhs[i];
+                self.queued[i] = None;
+                self.update_predecessors(self.pos(i));
+            } else {
+                self.g[i] = INF;
+                self.update_vertex(i);
+                self.update_predecessors(self.pos(i));
+            }
+        }
+    }
+
+    /// The neighbour minimising step cost plus cost-to-goal.
+    fn best_successor(&self, u: (i32, i32)) -> Option<(i32, i32)> {
+        let mut best: Option<((i32, i32), u32)> = None;
+        for &(dx, dy) in self.neighborhood() {
+            let v = (u.0 + dx, u.1 + dy);
+            let c = self.cost(u, v);
+            if c == INF {
+                continue;
+            }
+            let total = c.saturating_add(self.g[self.idx(v)]);
+            if total != INF && best.is_none_or(|(_, b)| total < b) {
+                best = Some((v, total));
+            }
+        }
+        best.map(|(v, _)| v)
+    }
+}
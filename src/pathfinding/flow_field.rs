@@ -0,0 +1,331 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::weighted::{DIRS_4, DIRS_8, DiagonalRule};
+
+// =============================================================================
+// FLOW FIELDS
+// =============================================================================
+///
+/// A flow field for many agents heading to the same goal(s).
+///
+/// `build` runs a single Dijkstra pass outward from the goals to fill an
+/// *integration field* (the cost for an agent on each tile to reach the
+/// nearest goal), then stores the best direction per tile.  Each agent's
+/// next step is then a constant-time lookup, no matter how many agents share
+/// the field.
+///
+/// Costs are per tile: stepping onto a tile costs `cost × 10` straight or
+/// `cost × 14` diagonally, the same units as `astar_8dir`.  Goals are always
+/// enterable (they are often the target entity); entering an impassable goal
+/// costs as much as a tile of cost 1.
+///
+/// When the goal moves a few tiles, `retarget` updates the field
+/// incrementally instead of rebuilding it from scratch.
+#[derive(Debug, Clone)]
+pub struct FlowField {
+    pub width: i32,
+    pub height: i32,
+    diagonals: DiagonalRule,
+    /// Tile cost; `u32::MAX` for impassable tiles.
+    costs: Vec<u32>,
+    integration: Vec<u32>,
+    directions: Vec<(i8, i8)>,
+    goals: Vec<(i32, i32)>,
+    /// Set when costs changed since the last build, forcing a full rebuild.
+    stale: bool,
+}
+
+/// Expansion budget for the local searches `retarget` uses to measure how
+/// far the goals moved.
+const RETARGET_BUDGET: usize = 1024;
+
+impl FlowField {
+    /// Create a field with per-tile costs (`None` = impassable).  Call
+    /// `build` with the goals before querying it.
+    pub fn new(
+        width: i32,
+        height: i32,
+        cost: impl Fn(i32, i32) -> Option<u32>,
+        diagonals: DiagonalRule,
+    ) -> Self {
+        let width = width.max(0);
+        let height = height.max(0);
+        let size = (width * height) as usize;
+        Self {
+            width,
+            height,
+            diagonals,
+            costs: (0..size as i32).map(|i| cost(i % width, i / width).unwrap_or(u32::MAX)).collect(),
+            integration: vec![u32::MAX; size],
+            directions: vec![(0, 0); size],
+            goals: Vec::new(),
+            stale: true,
+        }
+    }
+
+    #[inline]
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    #[inline]
+    fn idx(&self, x: i32, y: i32) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    /// The goals the field was last built for.
+    pub fn goals(&self) -> &[(i32, i32)] {
+        &self.goals
+    }
+
+    /// Change the cost of a tile (`None` = impassable).  Takes effect on the
+    /// next `build` or `retarget`, which will do a full rebuild.
+    pub fn set_cost(&mut self, x: i32, y: i32, cost: Option<u32>) {
+        if self.in_bounds(x, y) {
+            let i = self.idx(x, y);
+            self.costs[i] = cost.unwrap_or(u32::MAX);
+            self.stale = true;
+        }
+    }
+
+    /// Cost for an agent at (x, y) to reach the nearest goal, or None if it
+    /// cannot.
+    pub fn distance(&self, x: i32, y: i32) -> Option<u32> {
+        if !self.in_bounds(x, y) {
+            return None;
+        }
+        let v = self.integration[self.idx(x, y)];
+        (v != u32::MAX).then_some(v)
+    }
+
+    /// Direction to step from (x, y); `(0, 0)` on a goal or unreachable tile.
+    #[inline]
+    pub fn direction(&self, x: i32, y: i32) -> (i32, i32) {
+        if !self.in_bounds(x, y) {
+            return (0, 0);
+        }
+        let (dx, dy) = self.directions[self.idx(x, y)];
+        (dx as i32, dy as i32)
+    }
+
+    /// Tile to step onto from (x, y), or None on a goal or unreachable tile.
+    pub fn next_step(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        match self.direction(x, y) {
+            (0, 0) => None,
+            (dx, dy) => Some((x + dx, y + dy)),
+        }
+    }
+
+    /// Rebuild the whole field for `goals`.
+    pub fn build(&mut self, goals: &[(i32, i32)]) {
+        self.integration.fill(u32::MAX);
+        self.set_goals(goals);
+        let mut open = BinaryHeap::new();
+        for &(gx, gy) in &self.goals {
+            open.push(Reverse((0, gx, gy)));
+        }
+        self.propagate(open, None);
+        self.directions.fill((0, 0));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.update_direction(x, y);
+            }
+        }
+        self.stale = false;
+    }
+
+    /// Move the field to new goals, reusing the current integration field.
+    ///
+    /// Each old goal is connected to its nearest new goal with a small local
+    /// search, giving a bound `δ` on how far the goals moved.  The old field
+    /// shifted up by `δ` is an upper bound for the new one, so a Dijkstra pass
+    /// from the new goals only has to visit tiles whose cost actually drops;
+    /// everything else keeps its (shifted) value and direction.  The result is
+    /// identical to a full `build`.
+    ///
+    /// Falls back to `build` when costs changed, the field was never built, the
+    /// goals moved too far, or an impassable old goal is dropped.
+    pub fn retarget(&mut self, goals: &[(i32, i32)]) {
+        if self.stale || self.goals.is_empty() {
+            self.build(goals);
+            return;
+        }
+        let new_goals: Vec<(i32, i32)> = goals.iter().copied().filter(|&(x, y)| self.in_bounds(x, y)).collect();
+        // An impassable goal that stops being a goal stops being enterable,
+        // which can raise costs beyond the shifted bound.
+        let closes_tile = self.goals.iter()
+            .any(|&(x, y)| self.costs[self.idx(x, y)] == u32::MAX && !new_goals.contains(&(x, y)));
+        if closes_tile {
+            self.build(goals);
+            return;
+        }
+        let mut delta = 0u32;
+        for &old in &self.goals {
+            match self.distance_to_any(old, &new_goals) {
+                Some(d) => delta = delta.max(d),
+                None => {
+                    self.build(goals);
+                    return;
+                }
+            }
+        }
+
+        for v in &mut self.integration {
+            if *v != u32::MAX {
+                *v = v.saturating_add(delta);
+            }
+        }
+        let old_goals = std::mem::take(&mut self.goals);
+        self.set_goals(goals);
+        let mut open = BinaryHeap::new();
+        for &(gx, gy) in &self.goals {
+            open.push(Reverse((0, gx, gy)));
+        }
+        let mut improved = Vec::new();
+        self.propagate(open, Some(&mut improved));
+
+        // Only tiles whose own value or a neighbour's value dropped can have a
+        // different best direction; old goals had none at all.
+        let dirs = self.neighborhood();
+        for (x, y) in improved.into_iter().chain(old_goals) {
+            self.update_direction(x, y);
+            for &(dx, dy) in dirs {
+                if self.in_bounds(x + dx, y + dy) {
+                    self.update_direction(x + dx, y + dy);
+                }
+            }
+        }
+    }
+
+    fn set_goals(&mut self, goals: &[(i32, i32)]) {
+        self.goals.clear();
+        for &(gx, gy) in goals {
+            if self.in_bounds(gx, gy) {
+                let i = self.idx(gx, gy);
+                self.integration[i] = 0;
+                self.goals.push((gx, gy));
+            }
+        }
+    }
+
+    fn neighborhood(&self) -> &'static [(i32, i32)] {
+        if self.diagonals == DiagonalRule::Never { &DIRS_4 } else { &DIRS_8 }
+    }
+
+    /// Cost for an agent to step from `from` onto the adjacent tile `to`, or
+    /// None if the move is not allowed.
+    fn step_cost(&self, from: (i32, i32), to: (i32, i32)) -> Option<u32> {
+        self.step_cost_towards(from, to, &self.goals)
+    }
+
+    /// `step_cost`, treating `goals` as the always-enterable tiles.
+    fn step_cost_towards(&self, from: (i32, i32), to: (i32, i32), goals: &[(i32, i32)]) -> Option<u32> {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let diagonal = dx != 0 && dy != 0;
+        if diagonal && self.diagonals == DiagonalRule::NoCornerCutting {
+            let a = self.costs[self.idx(from.0 + dx, from.1)];
+            let b = self.costs[self.idx(from.0, from.1 + dy)];
+            if a == u32::MAX || b == u32::MAX {
+                return None;
+            }
+        }
+        let mut cost = self.costs[self.idx(to.0, to.1)];
+        if cost == u32::MAX {
+            if !goals.contains(&to) {
+                return None;
+            }
+            cost = 1;
+        }
+        Some(cost.saturating_mul(if diagonal { 14 } else { 10 }))
+    }
+
+    /// Dijkstra outward from the queued tiles, lowering values only where a
+    /// strictly cheaper route is found.  Lowered tiles are recorded.
+    fn propagate(
+        &mut self,
+        mut open: BinaryHeap<Reverse<(u32, i32, i32)>>,
+        mut improved: Option<&mut Vec<(i32, i32)>>,
+    ) {
+        let dirs = self.neighborhood();
+        while let Some(Reverse((value, x, y))) = open.pop() {
+            if value > self.integration[self.idx(x, y)] {
+                continue; // stale entry
+            }
+            if let Some(list) = improved.as_deref_mut() {
+                list.push((x, y));
+            }
+            for &(dx, dy) in dirs {
+                let (nx, ny) = (x + dx, y + dy);
+                if !self.in_bounds(nx, ny) { continue; }
+                // The agent walks from the neighbour onto this tile.
+                let Some(step) = self.step_cost((nx, ny), (x, y)) else { continue };
+                if self.costs[self.idx(nx, ny)] == u32::MAX { continue; }
+                let candidate = value.saturating_add(step);
+                let ni = self.idx(nx, ny);
+                if candidate < self.integration[ni] {
+                    self.integration[ni] = candidate;
+                    open.push(Reverse((candidate, nx, ny)));
+                }
+            }
+        }
+    }
+
+    fn update_direction(&mut self, x: i32, y: i32) {
+        let i = self.idx(x, y);
+        let here = self.integration[i];
+        let mut best = (0i8, 0i8);
+        if here != 0 && here != u32::MAX {
+            // Follow the step that realises the integration value; straight
+            // steps come first, so they win ties.
+            for &(dx, dy) in self.neighborhood() {
+                let (nx, ny) = (x + dx, y + dy);
+                if !self.in_bounds(nx, ny) { continue; }
+                let there = self.integration[self.idx(nx, ny)];
+                if there == u32::MAX { continue; }
+                if self.step_cost((x, y), (nx, ny)).is_some_and(|s| there.saturating_add(s) == here) {
+                    best = (dx as i8, dy as i8);
+                    break;
+                }
+            }
+        }
+        self.directions[i] = best;
+    }
+
+    /// Forward search from `from` (as an agent) to the nearest of `targets`,
+    /// bounded by `RETARGET_BUDGET` expansions.
+    fn distance_to_any(&self, from: (i32, i32), targets: &[(i32, i32)]) -> Option<u32> {
+        if targets.is_empty() {
+            return None;
+        }
+        let mut best: HashMap<(i32, i32), u32> = HashMap::new();
+        let mut open = BinaryHeap::new();
+        open.push(Reverse((0u32, from.0, from.1)));
+        best.insert(from, 0);
+        let mut expanded = 0;
+        while let Some(Reverse((d, x, y))) = open.pop() {
+            if best.get(&(x, y)).is_some_and(|&v| d > v) {
+                continue;
+            }
+            if targets.contains(&(x, y)) {
+                return Some(d);
+            }
+            expanded += 1;
+            if expanded > RETARGET_BUDGET {
+                return None;
+            }
+            for &(dx, dy) in self.neighborhood() {
+                let (nx, ny) = (x + dx, y + dy);
+                if !self.in_bounds(nx, ny) { continue; }
+                let Some(step) = self.step_cost_towards((x, y), (nx, ny), targets) else { continue };
+                let nd = d.saturating_add(step);
+                if best.get(&(nx, ny)).is_some_and(|&v| v <= nd) {
+                    continue;
+                }
+                best.insert((nx, ny), nd);
+                open.push(Reverse((nd, nx, ny)));
+            }
+        }
+        None
+    }
+}
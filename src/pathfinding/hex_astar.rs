@@ -0,0 +1,72 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::hex::Hex;
+
+// =============================================================================
+// HEX A* PATHFINDING
+// =============================================================================
+///
+/// A* pathfinding on an unbounded axial hex grid.
+///
+/// Returns the full path as a Vec of hexes, including start and goal.
+/// Returns None if no path exists.  Every step costs 1 and the heuristic is
+/// the hex distance, which is exact on an open grid.
+///
+/// Like `astar`, the goal is always considered enterable (it is often the
+/// target entity).  Because the grid is unbounded, `is_passable` must return
+/// false outside the map, and `max_iterations` bounds the search.
+pub fn astar_hex(
+    start: Hex,
+    goal: Hex,
+    is_passable: impl Fn(Hex) -> bool,
+    max_iterations: usize,
+) -> Option<Vec<Hex>> {
+    if start == goal {
+        return Some(vec![start]);
+    }
+
+    // Priority queue: (f_score, hex) - use Reverse for min-heap
+    let mut open: BinaryHeap<Reverse<(i32, Hex)>> = BinaryHeap::new();
+    let mut came_from: HashMap<Hex, Hex> = HashMap::new();
+    let mut g_score: HashMap<Hex, i32> = HashMap::new();
+
+    g_score.insert(start, 0);
+    open.push(Reverse((start.distance(goal), start)));
+
+    let mut iterations = 0;
+
+    while let Some(Reverse((_, current))) = open.pop() {
+        iterations += 1;
+        if iterations > max_iterations {
+            return None;
+        }
+
+        if current == goal {
+            let mut path = vec![goal];
+            let mut node = goal;
+            while let Some(&prev) = came_from.get(&node) {
+                path.push(prev);
+                node = prev;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let current_g = g_score[&current];
+
+        for next in current.neighbors() {
+            if next != goal && !is_passable(next) {
+                continue;
+            }
+            let new_g = current_g + 1;
+            if g_score.get(&next).is_none_or(|&g| new_g < g) {
+                g_score.insert(next, new_g);
+                came_from.insert(next, current);
+                open.push(Reverse((new_g + next.distance(goal), next)));
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,509 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// =============================================================================
+// HIERARCHICAL PATHFINDING (HPA*)
+// =============================================================================
+///
+/// Hierarchical A* for very large grids.
+///
+/// The map is split into square clusters.  Wherever two neighbouring clusters
+/// share a passable stretch of border, an *entrance* is placed (one in the
+/// middle of short stretches, one at each end of long ones).  Entrances
+/// become nodes of a small abstract graph whose edges are the precomputed
+/// path costs between entrances of the same cluster.  A query searches the
+/// abstract graph and then refines each abstract hop into tiles with a search
+/// confined to a single cluster.
+///
+/// Movement is 8-directional with costs of 10 (straight) and 14 (diagonal),
+/// and diagonals never cut wall corners.  Paths are near-optimal, typically
+/// within a few percent of `astar_weighted` with the same rules.
+///
+/// Tile changes only mark clusters dirty; the affected clusters and their
+/// neighbours are rebuilt on the next query (or an explicit `update`).
+pub struct HpaMap {
+    width: i32,
+    height: i32,
+    cluster_size: i32,
+    clusters_w: i32,
+    clusters_h: i32,
+    passable: Vec<bool>,
+    nodes: Vec<Option<AbstractNode>>,
+    free: Vec<usize>,
+    by_pos: HashMap<(i32, i32), usize>,
+    cluster_nodes: Vec<Vec<usize>>,
+    /// Entrance node pairs for each border, keyed by `(cluster_a, cluster_b)`
+    /// with `cluster_a < cluster_b`.
+    borders: HashMap<(usize, usize), Vec<(usize, usize)>>,
+    dirty: Vec<bool>,
+    any_dirty: bool,
+}
+
+struct AbstractNode {
+    pos: (i32, i32),
+    cluster: usize,
+    /// Number of entrances using this node; it is freed when this hits zero.
+    refs: u32,
+    /// `(target, cost)`.  Intra-cluster edges point at nodes of the same
+    /// cluster; inter-cluster edges cross a border.
+    edges: Vec<(usize, i32)>,
+}
+
+/// Stretches of shared border at least this long get two entrances.
+const LONG_ENTRANCE: usize = 6;
+
+const START: usize = usize::MAX;
+const GOAL: usize = usize::MAX - 1;
+
+impl HpaMap {
+    /// Build the abstract graph for a `width × height` map.
+    ///
+    /// `cluster_size` is clamped to at least 4; 16 is a good default.
+    pub fn new(width: i32, height: i32, cluster_size: i32, is_passable: impl Fn(i32, i32) -> bool) -> Self {
+        let width = width.max(0);
+        let height = height.max(0);
+        let cluster_size = cluster_size.max(4);
+        let clusters_w = (width + cluster_size - 1) / cluster_size;
+        let clusters_h = (height + cluster_size - 1) / cluster_size;
+        let cluster_count = (clusters_w * clusters_h) as usize;
+        let passable = (0..width * height).map(|i| is_passable(i % width, i / width)).collect();
+
+        let mut map = Self {
+            width,
+            height,
+            cluster_size,
+            clusters_w,
+            clusters_h,
+            passable,
+            nodes: Vec::new(),
+            free: Vec::new(),
+            by_pos: HashMap::new(),
+            cluster_nodes: vec![Vec::new(); cluster_count],
+            borders: HashMap::new(),
+            dirty: vec![true; cluster_count],
+            any_dirty: cluster_count > 0,
+        };
+        map.update();
+        map
+    }
+
+    pub fn width(&self) -> i32 { self.width }
+    pub fn height(&self) -> i32 { self.height }
+    pub fn cluster_size(&self) -> i32 { self.cluster_size }
+
+    /// Number of clusters the map is divided into.
+    pub fn cluster_count(&self) -> usize {
+        self.cluster_nodes.len()
+    }
+
+    /// Number of entrance nodes in the abstract graph.
+    pub fn node_count(&self) -> usize {
+        self.by_pos.len()
+    }
+
+    pub fn is_passable(&self, x: i32, y: i32) -> bool {
+        self.in_bounds(x, y) && self.passable[(y * self.width + x) as usize]
+    }
+
+    /// Change a tile.  Only its cluster (and neighbours, if the tile lies on a
+    /// border) is rebuilt, lazily on the next query or `update`.
+    pub fn set_passable(&mut self, x: i32, y: i32, passable: bool) {
+        if !self.in_bounds(x, y) {
+            return;
+        }
+        let idx = (y * self.width + x) as usize;
+        if self.passable[idx] != passable {
+            self.passable[idx] = passable;
+            let c = self.cluster_of((x, y));
+            self.dirty[c] = true;
+            self.any_dirty = true;
+        }
+    }
+
+    /// Rebuild every cluster touched by `set_passable` since the last update.
+    pub fn update(&mut self) {
+        if !self.any_dirty {
+            return;
+        }
+        let dirty: Vec<usize> = (0..self.dirty.len()).filter(|&c| self.dirty[c]).collect();
+
+        let mut affected = vec![false; self.dirty.len()];
+        let mut borders = Vec::new();
+        for &c in &dirty {
+            affected[c] = true;
+            for n in self.cluster_neighbors(c) {
+                affected[n] = true;
+                // A border between two dirty clusters is visited from both
+                // sides; take it once.
+                if !self.dirty[n] || c < n {
+                    borders.push((c.min(n), c.max(n)));
+                }
+            }
+        }
+
+        for &(a, b) in &borders {
+            self.remove_border(a, b);
+        }
+        for &(a, b) in &borders {
+            self.build_border(a, b);
+        }
+        // Nodes no longer used by any entrance.
+        let orphans: Vec<usize> = (0..affected.len())
+            .filter(|&c| affected[c])
+            .flat_map(|c| self.cluster_nodes[c].iter().copied())
+            .filter(|&i| self.node(i).refs == 0)
+            .collect();
+        for id in orphans {
+            self.free_node(id);
+        }
+        for c in (0..affected.len()).filter(|&c| affected[c]) {
+            self.build_intra_edges(c);
+        }
+
+        self.dirty.fill(false);
+        self.any_dirty = false;
+    }
+
+    /// Find a tile path from `start` to `goal`, including both.
+    ///
+    /// Both tiles must be passable.  Returns None if no path exists.
+    pub fn find_path(&mut self, start: (i32, i32), goal: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        let mut path = self.find_abstract_path(start, goal)?;
+        let mut tiles = vec![start];
+        while !path.is_done() {
+            tiles.extend(path.refine_next(self)?);
+        }
+        Some(tiles)
+    }
+
+    /// Search only the abstract graph.  The returned `AbstractPath` can be
+    /// refined into tiles one cluster-sized chunk at a time.
+    pub fn find_abstract_path(&mut self, start: (i32, i32), goal: (i32, i32)) -> Option<AbstractPath> {
+        self.update();
+        if !self.is_passable(start.0, start.1) || !self.is_passable(goal.0, goal.1) {
+            return None;
+        }
+        if start == goal {
+            return Some(AbstractPath { waypoints: vec![start], next: 0 });
+        }
+
+        let (sc, gc) = (self.cluster_of(start), self.cluster_of(goal));
+        let (start_dist, _) = self.local_search(sc, start);
+        // Within one cluster, walk there directly unless leaving the cluster
+        // turns out cheaper.
+        let direct = if sc == gc { start_dist[self.local_index(sc, goal)] } else { i32::MAX };
+        let start_edges: Vec<(usize, i32)> = self.cluster_nodes[sc]
+            .iter()
+            .map(|&n| (n, start_dist[self.local_index(sc, self.node(n).pos)]))
+            .filter(|&(_, d)| d != i32::MAX)
+            .collect();
+        let (goal_dist, _) = self.local_search(gc, goal);
+        let goal_cost = |n: usize| -> Option<i32> {
+            let node = self.node(n);
+            if node.cluster != gc {
+                return None;
+            }
+            let d = goal_dist[self.local_index(gc, node.pos)];
+            (d != i32::MAX).then_some(d)
+        };
+
+        let pos_of = |id: usize| match id {
+            START => start,
+            GOAL => goal,
+            n => self.node(n).pos,
+        };
+        let mut g: HashMap<usize, i32> = HashMap::new();
+        let mut came_from: HashMap<usize, usize> = HashMap::new();
+        let mut open: BinaryHeap<Reverse<(i32, usize)>> = BinaryHeap::new();
+        g.insert(START, 0);
+        open.push(Reverse((octile(start, goal), START)));
+
+        while let Some(Reverse((f, id))) = open.pop() {
+            let current_g = g[&id];
+            if f - octile(pos_of(id), goal) > current_g {
+                continue; // stale entry
+            }
+            if id == GOAL {
+                let mut waypoints = vec![goal];
+                let mut cur = GOAL;
+                while let Some(&prev) = came_from.get(&cur) {
+                    waypoints.push(pos_of(prev));
+                    cur = prev;
+                }
+                waypoints.reverse();
+                return Some(AbstractPath { waypoints, next: 0 });
+            }
+
+            let mut relax = |to: usize, cost: i32| {
+                let new_g = current_g + cost;
+                if g.get(&to).is_none_or(|&old| new_g < old) {
+                    g.insert(to, new_g);
+                    came_from.insert(to, id);
+                    open.push(Reverse((new_g + octile(pos_of(to), goal), to)));
+                }
+            };
+            if id == START {
+                for &(n, d) in &start_edges {
+                    relax(n, d);
+                }
+                if direct != i32::MAX {
+                    relax(GOAL, direct);
+                }
+            } else {
+                for &(n, d) in &self.node(id).edges {
+                    relax(n, d);
+                }
+                if let Some(d) = goal_cost(id) {
+                    relax(GOAL, d);
+                }
+            }
+        }
+        None
+    }
+
+    // ── Construction ────────────────────────────────────────────────────────
+
+    fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    fn cluster_of(&self, (x, y): (i32, i32)) -> usize {
+        ((y / self.cluster_size) * self.clusters_w + x / self.cluster_size) as usize
+    }
+
+    /// `(x0, y0, x1, y1)` with exclusive upper bounds.
+    fn cluster_rect(&self, c: usize) -> (i32, i32, i32, i32) {
+        let cx = c as i32 % self.clusters_w;
+        let cy = c as i32 / self.clusters_w;
+        let (x0, y0) = (cx * self.cluster_size, cy * self.cluster_size);
+        (x0, y0, (x0 + self.cluster_size).min(self.width), (y0 + self.cluster_size).min(self.height))
+    }
+
+    fn cluster_neighbors(&self, c: usize) -> Vec<usize> {
+        let cx = c as i32 % self.clusters_w;
+        let cy = c as i32 / self.clusters_w;
+        [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .into_iter()
+            .map(|(dx, dy)| (cx + dx, cy + dy))
+            .filter(|&(x, y)| x >= 0 && y >= 0 && x < self.clusters_w && y < self.clusters_h)
+            .map(|(x, y)| (y * self.clusters_w + x) as usize)
+            .collect()
+    }
+
+    fn node(&self, id: usize) -> &AbstractNode {
+        self.nodes[id].as_ref().expect("live abstract node")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut AbstractNode {
+        self.nodes[id].as_mut().expect("live abstract node")
+    }
+
+    fn node_at(&mut self, pos: (i32, i32)) -> usize {
+        if let Some(&id) = self.by_pos.get(&pos) {
+            return id;
+        }
+        let cluster = self.cluster_of(pos);
+        let node = AbstractNode { pos, cluster, refs: 0, edges: Vec::new() };
+        let id = match self.free.pop() {
+            Some(id) => { self.nodes[id] = Some(node); id }
+            None => { self.nodes.push(Some(node)); self.nodes.len() - 1 }
+        };
+        self.by_pos.insert(pos, id);
+        self.cluster_nodes[cluster].push(id);
+        id
+    }
+
+    fn free_node(&mut self, id: usize) {
+        let Some(node) = self.nodes[id].take() else { return };
+        self.by_pos.remove(&node.pos);
+        self.cluster_nodes[node.cluster].retain(|&n| n != id);
+        self.free.push(id);
+    }
+
+    fn remove_border(&mut self, a: usize, b: usize) {
+        for (na, nb) in self.borders.remove(&(a, b)).unwrap_or_default() {
+            for (from, to) in [(na, nb), (nb, na)] {
+                let node = self.node_mut(from);
+                if let Some(i) = node.edges.iter().position(|&(t, _)| t == to) {
+                    node.edges.swap_remove(i);
+                }
+                node.refs -= 1;
+            }
+        }
+    }
+
+    /// Place entrances along the shared border of clusters `a < b`.
+    fn build_border(&mut self, a: usize, b: usize) {
+        let (ax0, ay0, ax1, ay1) = self.cluster_rect(a);
+        // Pairs of facing tiles (in a, in b) along the border.
+        // Same cluster row means left/right neighbours; `b == a + 1` alone
+        // also matches stacked clusters when there is one cluster per row.
+        let same_row = a as i32 / self.clusters_w == b as i32 / self.clusters_w;
+        let pairs: Vec<((i32, i32), (i32, i32))> = if same_row {
+            (ay0..ay1).map(|y| ((ax1 - 1, y), (ax1, y))).collect()
+        } else {
+            (ax0..ax1).map(|x| ((x, ay1 - 1), (x, ay1))).collect()
+        };
+
+        let mut entrances = Vec::new();
+        let mut run: Vec<((i32, i32), (i32, i32))> = Vec::new();
+        for pair in pairs.into_iter().chain(std::iter::once(((-1, -1), (-1, -1)))) {
+            let open = self.is_passable(pair.0.0, pair.0.1) && self.is_passable(pair.1.0, pair.1.1);
+            if open {
+                run.push(pair);
+                continue;
+            }
+            if run.len() >= LONG_ENTRANCE {
+                entrances.push(run[0]);
+                entrances.push(run[run.len() - 1]);
+            } else if !run.is_empty() {
+                entrances.push(run[run.len() / 2]);
+            }
+            run.clear();
+        }
+
+        let mut list = Vec::with_capacity(entrances.len());
+        for (pa, pb) in entrances {
+            let na = self.node_at(pa);
+            let nb = self.node_at(pb);
+            self.node_mut(na).edges.push((nb, 10));
+            self.node_mut(na).refs += 1;
+            self.node_mut(nb).edges.push((na, 10));
+            self.node_mut(nb).refs += 1;
+            list.push((na, nb));
+        }
+        self.borders.insert((a, b), list);
+    }
+
+    /// Recompute the cached costs between every pair of entrances in `c`.
+    fn build_intra_edges(&mut self, c: usize) {
+        let members = self.cluster_nodes[c].clone();
+        // Keep only border crossings; also drops edges to freed nodes.
+        for &n in &members {
+            let crossings: Vec<(usize, i32)> = self.node(n).edges.iter().copied()
+                .filter(|&(t, _)| self.nodes[t].as_ref().is_some_and(|tn| tn.cluster != c))
+                .collect();
+            self.node_mut(n).edges = crossings;
+        }
+        for &n in &members {
+            let (dist, _) = self.local_search(c, self.node(n).pos);
+            let edges: Vec<(usize, i32)> = members
+                .iter()
+                .filter(|&&m| m != n)
+                .map(|&m| (m, dist[self.local_index(c, self.node(m).pos)]))
+                .filter(|&(_, d)| d != i32::MAX)
+                .collect();
+            self.node_mut(n).edges.extend(edges);
+        }
+    }
+
+    // ── Cluster-local search ────────────────────────────────────────────────
+
+    fn local_index(&self, c: usize, (x, y): (i32, i32)) -> usize {
+        let (x0, y0, x1, _) = self.cluster_rect(c);
+        ((y - y0) * (x1 - x0) + (x - x0)) as usize
+    }
+
+    /// Dijkstra from `from` confined to cluster `c`.  Returns distances and
+    /// predecessors indexed by `local_index`.
+    fn local_search(&self, c: usize, from: (i32, i32)) -> (Vec<i32>, Vec<i32>) {
+        let (x0, y0, x1, y1) = self.cluster_rect(c);
+        let w = x1 - x0;
+        let size = (w * (y1 - y0)) as usize;
+        let mut dist = vec![i32::MAX; size];
+        let mut came_from = vec![-1; size];
+        let inside = |x: i32, y: i32| x >= x0 && y >= y0 && x < x1 && y < y1 && self.is_passable(x, y);
+
+        let mut open: BinaryHeap<Reverse<(i32, i32, i32)>> = BinaryHeap::new();
+        dist[self.local_index(c, from)] = 0;
+        open.push(Reverse((0, from.0, from.1)));
+
+        while let Some(Reverse((d, x, y))) = open.pop() {
+            let idx = ((y - y0) * w + (x - x0)) as usize;
+            if d > dist[idx] {
+                continue;
+            }
+            for (dx, dy) in super::weighted::DIRS_8 {
+                let (nx, ny) = (x + dx, y + dy);
+                if !inside(nx, ny) { continue; }
+                let diagonal = dx != 0 && dy != 0;
+                if diagonal && (!inside(x + dx, y) || !inside(x, y + dy)) { continue; }
+                let nidx = ((ny - y0) * w + (nx - x0)) as usize;
+                let nd = d + if diagonal { 14 } else { 10 };
+                if nd < dist[nidx] {
+                    dist[nidx] = nd;
+                    came_from[nidx] = idx as i32;
+                    open.push(Reverse((nd, nx, ny)));
+                }
+            }
+        }
+        (dist, came_from)
+    }
+
+    /// Tile path from `a` to `b` (excluding `a`) for one abstract hop.
+    fn refine_hop(&self, a: (i32, i32), b: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        if !self.is_passable(b.0, b.1) {
+            return None;
+        }
+        let (ca, cb) = (self.cluster_of(a), self.cluster_of(b));
+        if ca != cb {
+            // Inter-cluster edge: the two tiles face each other across a border.
+            let adjacent = (a.0 - b.0).abs() + (a.1 - b.1).abs() == 1;
+            return adjacent.then(|| vec![b]);
+        }
+        let (dist, came_from) = self.local_search(ca, a);
+        let mut idx = self.local_index(ca, b);
+        if dist[idx] == i32::MAX {
+            return None;
+        }
+        let (x0, y0, x1, _) = self.cluster_rect(ca);
+        let w = x1 - x0;
+        let mut tiles = Vec::new();
+        while came_from[idx] != -1 {
+            tiles.push((x0 + idx as i32 % w, y0 + idx as i32 / w));
+            idx = came_from[idx] as usize;
+        }
+        tiles.reverse();
+        Some(tiles)
+    }
+}
+
+/// An abstract HPA* path: the entrances to pass through, refined into tiles
+/// on demand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbstractPath {
+    waypoints: Vec<(i32, i32)>,
+    next: usize,
+}
+
+impl AbstractPath {
+    /// Start, entrances and goal, in order.
+    pub fn waypoints(&self) -> &[(i32, i32)] {
+        &self.waypoints
+    }
+
+    /// Returns true once every hop has been refined.
+    pub fn is_done(&self) -> bool {
+        self.next + 1 >= self.waypoints.len()
+    }
+
+    /// Refine the next hop into tiles (excluding the tile it starts from).
+    ///
+    /// Returns None when the path is finished, or when the map changed so
+    /// that the hop is no longer walkable — re-plan in that case.
+    pub fn refine_next(&mut self, map: &HpaMap) -> Option<Vec<(i32, i32)>> {
+        if self.is_done() {
+            return None;
+        }
+        let tiles = map.refine_hop(self.waypoints[self.next], self.waypoints[self.next + 1])?;
+        self.next += 1;
+        Some(tiles)
+    }
+}
+
+/// Exact 10/14 distance on an open grid.
+fn octile(a: (i32, i32), b: (i32, i32)) -> i32 {
+    let dx = (a.0 - b.0).abs();
+    let dy = (a.1 - b.1).abs();
+    10 * dx.max(dy) + 4 * dx.min(dy)
+}
@@ -0,0 +1,209 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::astar::SearchStats;
+
+// =============================================================================
+// JUMP POINT SEARCH
+// =============================================================================
+///
+/// Jump Point Search: a drop-in replacement for `astar_8dir` on uniform-cost
+/// grids.
+///
+/// Uses the same movement rules as `astar_8dir` — 8 directions, straight
+/// steps cost 10 and diagonals 14, diagonals may squeeze between two walls,
+/// and the goal is always enterable — so the returned path has the same cost.
+/// Among several equally short paths the two may pick different ones.
+///
+/// Instead of expanding every tile, JPS scans along straight and diagonal
+/// lines and only expands "jump points" where the path may need to turn,
+/// which cuts node expansions dramatically in open areas.  The returned path
+/// is the full tile-by-tile route, including start and goal.
+///
+/// `max_iterations` limits the number of jump points expanded.
+pub fn jps(
+    start: (i32, i32),
+    goal: (i32, i32),
+    width: i32,
+    height: i32,
+    is_passable: impl Fn(i32, i32) -> bool,
+    max_iterations: usize,
+) -> Option<Vec<(i32, i32)>> {
+    jps_with_stats(start, goal, width, height, is_passable, max_iterations).0
+}
+
+/// `jps`, also reporting how many jump points were expanded.
+pub fn jps_with_stats(
+    start: (i32, i32),
+    goal: (i32, i32),
+    width: i32,
+    height: i32,
+    is_passable: impl Fn(i32, i32) -> bool,
+    max_iterations: usize,
+) -> (Option<Vec<(i32, i32)>>, SearchStats) {
+    let mut stats = SearchStats::default();
+    if width <= 0 || height <= 0 { return (None, stats); }
+    if start.0 < 0 || start.0 >= width || start.1 < 0 || start.1 >= height { return (None, stats); }
+    if goal.0  < 0 || goal.0  >= width || goal.1  < 0 || goal.1  >= height { return (None, stats); }
+
+    if start == goal {
+        return (Some(vec![start]), stats);
+    }
+
+    let grid = Grid { width, height, goal, is_passable: &is_passable };
+    let size = (width * height) as usize;
+    // Priority queue: (f_score, x, y) - use Reverse for min-heap
+    let mut open: BinaryHeap<Reverse<(i32, i32, i32)>> = BinaryHeap::new();
+    let mut came_from: Vec<i32> = vec![-1; size];
+    let mut g_score: Vec<i32> = vec![i32::MAX; size];
+    let mut closed: Vec<bool> = vec![false; size];
+
+    g_score[(start.1 * width + start.0) as usize] = 0;
+    open.push(Reverse((octile(start, goal), start.0, start.1)));
+
+    let mut iterations = 0;
+
+    while let Some(Reverse((_, cx, cy))) = open.pop() {
+        let current_idx = (cy * width + cx) as usize;
+        if closed[current_idx] {
+            continue;
+        }
+        closed[current_idx] = true;
+
+        iterations += 1;
+        if iterations > max_iterations {
+            return (None, stats);
+        }
+        stats.expanded = iterations;
+
+        if (cx, cy) == goal {
+            return (Some(expand_path(&came_from, goal, width)), stats);
+        }
+
+        let parent = match came_from[current_idx] {
+            -1 => None,
+            p => Some((p % width, p / width)),
+        };
+        let current_g = g_score[current_idx];
+
+        for (dx, dy) in grid.neighbor_dirs((cx, cy), parent) {
+            let Some(jump) = grid.jump(cx + dx, cy + dy, dx, dy) else { continue };
+            let jump_idx = (jump.1 * width + jump.0) as usize;
+            if closed[jump_idx] {
+                continue;
+            }
+            let new_g = current_g + octile((cx, cy), jump);
+            if new_g < g_score[jump_idx] {
+                g_score[jump_idx] = new_g;
+                came_from[jump_idx] = current_idx as i32;
+                open.push(Reverse((new_g + octile(jump, goal), jump.0, jump.1)));
+            }
+        }
+    }
+
+    (None, stats)
+}
+
+/// Exact 10/14 cost of a straight or diagonal run, and an exact heuristic on
+/// an open grid.
+fn octile(a: (i32, i32), b: (i32, i32)) -> i32 {
+    let dx = (a.0 - b.0).abs();
+    let dy = (a.1 - b.1).abs();
+    10 * dx.max(dy) + 4 * dx.min(dy)
+}
+
+/// Walk the chain of jump points back to the start, filling in every tile of
+/// the straight or diagonal run between each pair.
+fn expand_path(came_from: &[i32], goal: (i32, i32), width: i32) -> Vec<(i32, i32)> {
+    let mut path = vec![goal];
+    let mut idx = (goal.1 * width + goal.0) as usize;
+    while came_from[idx] != -1 {
+        let prev = came_from[idx] as usize;
+        let (mut x, mut y) = (idx as i32 % width, idx as i32 / width);
+        let (px, py) = (prev as i32 % width, prev as i32 / width);
+        let (sx, sy) = ((px - x).signum(), (py - y).signum());
+        while (x, y) != (px, py) {
+            x += sx;
+            y += sy;
+            path.push((x, y));
+        }
+        idx = prev;
+    }
+    path.reverse();
+    path
+}
+
+struct Grid<'a, F: Fn(i32, i32) -> bool> {
+    width: i32,
+    height: i32,
+    goal: (i32, i32),
+    is_passable: &'a F,
+}
+
+impl<F: Fn(i32, i32) -> bool> Grid<'_, F> {
+    fn walkable(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+            && ((x, y) == self.goal || (self.is_passable)(x, y))
+    }
+
+    /// Directions worth exploring from `node`, pruned by the direction of
+    /// travel from `parent`.  The start node explores all 8.
+    fn neighbor_dirs(&self, (x, y): (i32, i32), parent: Option<(i32, i32)>) -> Vec<(i32, i32)> {
+        let Some((px, py)) = parent else {
+            return super::weighted::DIRS_8.to_vec();
+        };
+        let dx = (x - px).signum();
+        let dy = (y - py).signum();
+        let mut dirs = Vec::with_capacity(5);
+        if dx != 0 && dy != 0 {
+            dirs.extend([(0, dy), (dx, 0), (dx, dy)]);
+            if !self.walkable(x - dx, y) { dirs.push((-dx, dy)); }
+            if !self.walkable(x, y - dy) { dirs.push((dx, -dy)); }
+        } else if dx != 0 {
+            dirs.push((dx, 0));
+            if !self.walkable(x, y + 1) { dirs.push((dx, 1)); }
+            if !self.walkable(x, y - 1) { dirs.push((dx, -1)); }
+        } else {
+            dirs.push((0, dy));
+            if !self.walkable(x + 1, y) { dirs.push((1, dy)); }
+            if !self.walkable(x - 1, y) { dirs.push((-1, dy)); }
+        }
+        dirs
+    }
+
+    /// Scan from `(x, y)` in direction `(dx, dy)` until reaching a jump point
+    /// (the goal, or a tile with a forced neighbour) or a wall.
+    fn jump(&self, mut x: i32, mut y: i32, dx: i32, dy: i32) -> Option<(i32, i32)> {
+        loop {
+            if !self.walkable(x, y) {
+                return None;
+            }
+            if (x, y) == self.goal {
+                return Some((x, y));
+            }
+            if dx != 0 && dy != 0 {
+                if (self.walkable(x - dx, y + dy) && !self.walkable(x - dx, y))
+                    || (self.walkable(x + dx, y - dy) && !self.walkable(x, y - dy))
+                {
+                    return Some((x, y));
+                }
+                // A diagonal run stops wherever a straight scan would find something.
+                if self.jump(x + dx, y, dx, 0).is_some() || self.jump(x, y + dy, 0, dy).is_some() {
+                    return Some((x, y));
+                }
+            } else if dx != 0 {
+                if (self.walkable(x + dx, y + 1) && !self.walkable(x, y + 1))
+                    || (self.walkable(x + dx, y - 1) && !self.walkable(x, y - 1))
+                {
+                    return Some((x, y));
+                }
+            } else if (self.walkable(x + 1, y + dy) && !self.walkable(x + 1, y))
+                || (self.walkable(x - 1, y + dy) && !self.walkable(x - 1, y))
+            {
+                return Some((x, y));
+            }
+            x += dx;
+            y += dy;
+        }
+    }
+}
@@ -1,7 +1,31 @@
+mod any_angle;
 mod astar;
+mod clearance;
+mod cooperative;
 mod dijkstra;
+mod dstar_lite;
+mod flow_field;
+mod hex_astar;
+mod hpa;
+mod jps;
+mod path_queue;
+mod pathfinder;
+mod regions;
+mod weighted;
 
 pub mod prelude {
+    pub use crate::pathfinding::any_angle::*;
     pub use crate::pathfinding::astar::*;
+    pub use crate::pathfinding::clearance::*;
+    pub use crate::pathfinding::cooperative::*;
     pub use crate::pathfinding::dijkstra::*;
+    pub use crate::pathfinding::dstar_lite::*;
+    pub use crate::pathfinding::flow_field::*;
+    pub use crate::pathfinding::hex_astar::*;
+    pub use crate::pathfinding::hpa::*;
+    pub use crate::pathfinding::jps::*;
+    pub use crate::pathfinding::path_queue::*;
+    pub use crate::pathfinding::pathfinder::*;
+    pub use crate::pathfinding::regions::*;
+    pub use crate::pathfinding::weighted::*;
 }
@@ -0,0 +1,285 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::geometry::distance_manhattan;
+
+use super::weighted::{DIRS_4, DIRS_8};
+
+// =============================================================================
+// TIME-SLICED PATH REQUESTS
+// =============================================================================
+
+/// Ticket for a submitted path request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathHandle(u64);
+
+/// State of a path request, as reported by `PathQueue::poll`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathStatus {
+    /// Still queued or being searched.
+    Pending,
+    /// Path from start to goal, inclusive.
+    Found(Vec<(i32, i32)>),
+    /// No path, the iteration limit was hit, or the handle is unknown
+    /// (cancelled or already collected).
+    Failed,
+}
+
+/// A queue of A* searches that runs a fixed number of node expansions per
+/// tick, so long paths never stall a frame.
+///
+/// Submit start/goal pairs, call `update` once per tick from `Game::update`,
+/// and poll the handles.  Searches run oldest first: each tick's budget goes
+/// to the oldest pending search, and anything left over goes to the next.
+///
+/// Searches give the same results as `astar` (or `astar_8dir` for an
+/// 8-directional queue), including the always-enterable goal and
+/// `max_iterations`.  Each slice sees the map as `is_passable` reports it
+/// during that tick.  If the map changes while a search is in flight, the
+/// result may cross a tile that has since been closed; check it before
+/// following it.
+///
+/// ```ignore
+/// // In Game::update:
+/// if let Some(handle) = self.pending_path {
+///     match self.paths.poll(handle) {
+///         PathStatus::Pending => {}
+///         PathStatus::Found(path) => { self.route = path; self.pending_path = None; }
+///         PathStatus::Failed => self.pending_path = None,
+///     }
+/// }
+/// self.paths.update(|x, y| !self.map.is_wall(x, y));
+/// ```
+#[derive(Debug)]
+pub struct PathQueue {
+    width: i32,
+    height: i32,
+    eight: bool,
+    budget: usize,
+    max_iterations: usize,
+    next_id: u64,
+    pending: VecDeque<Search>,
+    finished: HashMap<PathHandle, Outcome>,
+}
+
+/// Result of a finished search: the path, or None if it failed.
+type Outcome = Option<Vec<(i32, i32)>>;
+
+impl PathQueue {
+    /// 4-directional queue spending 1000 expansions per tick, with
+    /// `max_iterations` of 10 000 per request.
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            eight: false,
+            budget: 1000,
+            max_iterations: 10_000,
+            next_id: 0,
+            pending: VecDeque::new(),
+            finished: HashMap::new(),
+        }
+    }
+
+    /// Search 8-directionally, like `astar_8dir`.
+    pub fn with_diagonals(mut self, eight: bool) -> Self {
+        self.eight = eight;
+        self
+    }
+
+    /// Node expansions spent per `update`, across all requests.
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = budget.max(1);
+        self
+    }
+
+    /// Expansion limit for each request, as `max_iterations` in `astar`.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget.max(1);
+    }
+
+    /// Number of requests still being searched.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Queue a search.  Trivial requests (out of bounds, or already at the
+    /// goal) finish immediately.
+    pub fn submit(&mut self, start: (i32, i32), goal: (i32, i32)) -> PathHandle {
+        let handle = PathHandle(self.next_id);
+        self.next_id += 1;
+        let in_bounds = |(x, y): (i32, i32)| x >= 0 && y >= 0 && x < self.width && y < self.height;
+        if !in_bounds(start) || !in_bounds(goal) {
+            self.finished.insert(handle, None);
+        } else if start == goal {
+            self.finished.insert(handle, Some(vec![start]));
+        } else {
+            self.pending.push_back(Search::new(handle, start, goal, self.eight));
+        }
+        handle
+    }
+
+    /// Check on a request.  `Found` and `Failed` are reported once; the
+    /// request is forgotten afterwards and later polls return `Failed`.
+    pub fn poll(&mut self, handle: PathHandle) -> PathStatus {
+        if let Some(result) = self.finished.remove(&handle) {
+            return match result {
+                Some(path) => PathStatus::Found(path),
+                None => PathStatus::Failed,
+            };
+        }
+        if self.pending.iter().any(|s| s.handle == handle) {
+            PathStatus::Pending
+        } else {
+            PathStatus::Failed
+        }
+    }
+
+    /// True while the request is still being searched.
+    pub fn is_pending(&self, handle: PathHandle) -> bool {
+        self.pending.iter().any(|s| s.handle == handle)
+    }
+
+    /// Drop a request, pending or finished.  Returns false if the handle was
+    /// unknown.
+    pub fn cancel(&mut self, handle: PathHandle) -> bool {
+        if self.finished.remove(&handle).is_some() {
+            return true;
+        }
+        let before = self.pending.len();
+        self.pending.retain(|s| s.handle != handle);
+        self.pending.len() != before
+    }
+
+    /// Drop every request.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.finished.clear();
+    }
+
+    /// Spend this tick's budget on pending searches.  Returns the number of
+    /// node expansions used.
+    pub fn update(&mut self, is_passable: impl Fn(i32, i32) -> bool) -> usize {
+        let mut remaining = self.budget;
+        while remaining > 0 {
+            let Some(search) = self.pending.front_mut() else { break };
+            let (used, outcome) = search.run(remaining, self.width, self.height, self.max_iterations, &is_passable);
+            remaining -= used;
+            if let Some(result) = outcome {
+                let handle = search.handle;
+                self.pending.pop_front();
+                self.finished.insert(handle, result);
+            }
+        }
+        self.budget - remaining
+    }
+}
+
+/// One in-flight A* search.  Node data lives in hash maps so that many
+/// pending requests on a large map stay cheap.
+#[derive(Debug)]
+struct Search {
+    handle: PathHandle,
+    start: (i32, i32),
+    goal: (i32, i32),
+    eight: bool,
+    open: BinaryHeap<Reverse<(i32, i32, i32)>>,
+    g_score: HashMap<(i32, i32), i32>,
+    came_from: HashMap<(i32, i32), (i32, i32)>,
+    iterations: usize,
+}
+
+impl Search {
+    fn new(handle: PathHandle, start: (i32, i32), goal: (i32, i32), eight: bool) -> Self {
+        let mut search = Self {
+            handle,
+            start,
+            goal,
+            eight,
+            open: BinaryHeap::new(),
+            g_score: HashMap::from([(start, 0)]),
+            came_from: HashMap::new(),
+            iterations: 0,
+        };
+        let h = search.heuristic(start);
+        search.open.push(Reverse((h, start.0, start.1)));
+        search
+    }
+
+    fn heuristic(&self, (x, y): (i32, i32)) -> i32 {
+        if self.eight {
+            (x - self.goal.0).abs().max((y - self.goal.1).abs()) * 10
+        } else {
+            distance_manhattan(x, y, self.goal.0, self.goal.1)
+        }
+    }
+
+    /// Expand up to `budget` nodes.  Returns the expansions used and, once
+    /// the search is over, its result.
+    fn run(
+        &mut self,
+        budget: usize,
+        width: i32,
+        height: i32,
+        max_iterations: usize,
+        is_passable: &impl Fn(i32, i32) -> bool,
+    ) -> (usize, Option<Outcome>) {
+        let mut used = 0;
+        while used < budget {
+            let Some(Reverse((_, cx, cy))) = self.open.pop() else {
+                return (used, Some(None));
+            };
+            used += 1;
+            self.iterations += 1;
+            if self.iterations > max_iterations {
+                return (used, Some(None));
+            }
+            if (cx, cy) == self.goal {
+                return (used, Some(Some(self.path())));
+            }
+
+            let current_g = self.g_score[&(cx, cy)];
+            let directions: &[(i32, i32)] = if self.eight { &DIRS_8 } else { &DIRS_4 };
+            for &(dx, dy) in directions {
+                let next = (cx + dx, cy + dy);
+                if next.0 < 0 || next.0 >= width || next.1 < 0 || next.1 >= height { continue; }
+                // Allow moving to goal even if "impassable" (it's often the target entity)
+                if next != self.goal && !is_passable(next.0, next.1) {
+                    continue;
+                }
+                let cost = match (self.eight, dx != 0 && dy != 0) {
+                    (false, _) => 1,
+                    (true, false) => 10,
+                    (true, true) => 14,
+                };
+                let new_g = current_g + cost;
+                if new_g < self.g_score.get(&next).copied().unwrap_or(i32::MAX) {
+                    self.g_score.insert(next, new_g);
+                    self.came_from.insert(next, (cx, cy));
+                    self.open.push(Reverse((new_g + self.heuristic(next), next.0, next.1)));
+                }
+            }
+        }
+        (used, None)
+    }
+
+    fn path(&self) -> Vec<(i32, i32)> {
+        let mut path = vec![self.goal];
+        let mut pos = self.goal;
+        while pos != self.start {
+            pos = self.came_from[&pos];
+            path.push(pos);
+        }
+        path.reverse();
+        path
+    }
+}
@@ -0,0 +1,261 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::geometry::distance_manhattan;
+
+// =============================================================================
+// REUSABLE PATHFINDER CONTEXT
+// =============================================================================
+///
+/// Reusable A* search context.
+///
+/// `astar` and friends allocate `width × height` scratch arrays and a fresh
+/// heap on every call.  A `Pathfinder` owns those buffers and keeps them
+/// between queries; node arrays are generation-stamped, so starting a new
+/// search is O(1) instead of clearing them.  After the first few queries
+/// warm the buffers up, searches do not allocate at all.
+///
+/// The search modes behave exactly like their free-function counterparts
+/// (same neighbour order, costs, heuristics and iteration counting), so they
+/// return identical paths.
+///
+/// ```ignore
+/// let mut pf = Pathfinder::new(map_w, map_h);
+/// for monster in monsters {
+///     if let Some(step) = pf.next_step(monster.pos, player_pos, |x, y| !is_wall(x, y), 500) {
+///         // move monster to `step`
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pathfinder {
+    width: i32,
+    height: i32,
+    generation: u32,
+    /// Node data is valid only where `stamp[i] == generation`.
+    stamp: Vec<u32>,
+    g_score: Vec<i32>,
+    came_from: Vec<i32>,
+    open: BinaryHeap<Reverse<(i32, i32, i32)>>,
+    path: Vec<(i32, i32)>,
+}
+
+impl Pathfinder {
+    pub fn new(width: i32, height: i32) -> Self {
+        let size = (width.max(0) * height.max(0)) as usize;
+        Self {
+            width: width.max(0),
+            height: height.max(0),
+            generation: 0,
+            stamp: vec![0; size],
+            g_score: vec![0; size],
+            came_from: vec![0; size],
+            open: BinaryHeap::new(),
+            path: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> i32 { self.width }
+    pub fn height(&self) -> i32 { self.height }
+
+    /// Change the grid size.  Buffers only grow, so switching between maps of
+    /// different sizes does not reallocate once the largest has been seen.
+    pub fn resize(&mut self, width: i32, height: i32) {
+        self.width = width.max(0);
+        self.height = height.max(0);
+        let size = (self.width * self.height) as usize;
+        if size > self.stamp.len() {
+            self.stamp.resize(size, 0);
+            self.g_score.resize(size, 0);
+            self.came_from.resize(size, 0);
+        }
+        // Old node data is laid out for the previous width; invalidate it.
+        self.next_generation();
+    }
+
+    /// 4-directional A*, equivalent to `astar`.
+    ///
+    /// The returned slice borrows the pathfinder's internal buffer and is
+    /// valid until the next query.
+    pub fn astar(
+        &mut self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        is_passable: impl Fn(i32, i32) -> bool,
+        max_iterations: usize,
+    ) -> Option<&[(i32, i32)]> {
+        self.search(start, goal, &is_passable, max_iterations, false)?;
+        self.build_path(start, goal);
+        Some(&self.path)
+    }
+
+    /// 8-directional A*, equivalent to `astar_8dir`.
+    pub fn astar_8dir(
+        &mut self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        is_passable: impl Fn(i32, i32) -> bool,
+        max_iterations: usize,
+    ) -> Option<&[(i32, i32)]> {
+        self.search(start, goal, &is_passable, max_iterations, true)?;
+        self.build_path(start, goal);
+        Some(&self.path)
+    }
+
+    /// First step of the 4-directional path, equivalent to `astar_next_step`.
+    /// Returns None if already at goal or no path exists.
+    pub fn next_step(
+        &mut self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        is_passable: impl Fn(i32, i32) -> bool,
+        max_iterations: usize,
+    ) -> Option<(i32, i32)> {
+        self.search(start, goal, &is_passable, max_iterations, false)?;
+        self.first_step(start, goal)
+    }
+
+    /// First step of the 8-directional path.
+    pub fn next_step_8dir(
+        &mut self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        is_passable: impl Fn(i32, i32) -> bool,
+        max_iterations: usize,
+    ) -> Option<(i32, i32)> {
+        self.search(start, goal, &is_passable, max_iterations, true)?;
+        self.first_step(start, goal)
+    }
+
+    fn next_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            // Wrapped around: stale stamps could now look current.
+            self.stamp.fill(0);
+            self.generation = 1;
+        }
+    }
+
+    #[inline]
+    fn g(&self, idx: usize) -> i32 {
+        if self.stamp[idx] == self.generation { self.g_score[idx] } else { i32::MAX }
+    }
+
+    #[inline]
+    fn parent(&self, idx: usize) -> i32 {
+        if self.stamp[idx] == self.generation { self.came_from[idx] } else { -1 }
+    }
+
+    #[inline]
+    fn set(&mut self, idx: usize, g: i32, parent: i32) {
+        self.stamp[idx] = self.generation;
+        self.g_score[idx] = g;
+        self.came_from[idx] = parent;
+    }
+
+    /// Run the search, leaving `came_from` filled for this generation.
+    /// Returns `Some(())` when the goal was reached.
+    fn search<F: Fn(i32, i32) -> bool>(
+        &mut self,
+        start: (i32, i32),
+        goal: (i32, i32),
+        is_passable: &F,
+        max_iterations: usize,
+        eight: bool,
+    ) -> Option<()> {
+        let (width, height) = (self.width, self.height);
+        if width <= 0 || height <= 0 { return None; }
+        if start.0 < 0 || start.0 >= width || start.1 < 0 || start.1 >= height { return None; }
+        if goal.0  < 0 || goal.0  >= width || goal.1  < 0 || goal.1  >= height { return None; }
+
+        self.next_generation();
+        if start == goal {
+            return Some(());
+        }
+
+        self.open.clear();
+        self.set((start.1 * width + start.0) as usize, 0, -1);
+        let h = distance_manhattan(start.0, start.1, goal.0, goal.1);
+        self.open.push(Reverse((h, start.0, start.1)));
+
+        const DIRS_4: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+        const DIRS_8: [(i32, i32); 8] = [
+            (0, -1), (1, -1), (1, 0), (1, 1),
+            (0, 1), (-1, 1), (-1, 0), (-1, -1),
+        ];
+        let directions: &[(i32, i32)] = if eight { &DIRS_8 } else { &DIRS_4 };
+
+        let mut iterations = 0;
+
+        while let Some(Reverse((_, cx, cy))) = self.open.pop() {
+            iterations += 1;
+            if iterations > max_iterations {
+                return None;
+            }
+            if (cx, cy) == goal {
+                return Some(());
+            }
+
+            let current_idx = (cy * width + cx) as usize;
+            let current_g = self.g(current_idx);
+
+            for &(dx, dy) in directions {
+                let nx = cx + dx;
+                let ny = cy + dy;
+                if nx < 0 || nx >= width || ny < 0 || ny >= height { continue; }
+
+                // Allow moving to goal even if "impassable" (it's often the target entity)
+                if (nx, ny) != goal && !is_passable(nx, ny) {
+                    continue;
+                }
+
+                let (cost, h) = if eight {
+                    let cost = if dx != 0 && dy != 0 { 14 } else { 10 };
+                    (cost, (nx - goal.0).abs().max((ny - goal.1).abs()) * 10)
+                } else {
+                    (1, distance_manhattan(nx, ny, goal.0, goal.1))
+                };
+                let next_idx = (ny * width + nx) as usize;
+                let new_g = current_g + cost;
+
+                if new_g < self.g(next_idx) {
+                    self.set(next_idx, new_g, current_idx as i32);
+                    self.open.push(Reverse((new_g + h, nx, ny)));
+                }
+            }
+        }
+
+        None
+    }
+
+    fn build_path(&mut self, start: (i32, i32), goal: (i32, i32)) {
+        self.path.clear();
+        self.path.push(goal);
+        let start_idx = start.1 * self.width + start.0;
+        let mut idx = goal.1 * self.width + goal.0;
+        while idx != start_idx {
+            idx = self.parent(idx as usize);
+            if idx == -1 { break; }
+            self.path.push((idx % self.width, idx / self.width));
+        }
+        self.path.reverse();
+    }
+
+    fn first_step(&self, start: (i32, i32), goal: (i32, i32)) -> Option<(i32, i32)> {
+        if start == goal {
+            return None;
+        }
+        let start_idx = start.1 * self.width + start.0;
+        let mut idx = goal.1 * self.width + goal.0;
+        loop {
+            let parent = self.parent(idx as usize);
+            if parent == start_idx {
+                return Some((idx % self.width, idx / self.width));
+            }
+            if parent == -1 {
+                return None;
+            }
+            idx = parent;
+        }
+    }
+}
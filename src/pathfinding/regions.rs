@@ -0,0 +1,306 @@
+use std::collections::VecDeque;
+
+use super::weighted::{DIRS_4, DIRS_8, DiagonalRule};
+
+// =============================================================================
+// CONNECTED REGIONS
+// =============================================================================
+///
+/// Connected-region labelling of a grid.
+///
+/// Every passable tile gets the label of the region it belongs to, so
+/// "can I get there at all?" becomes an O(1) lookup.  Check it before
+/// running A*, which otherwise spends its whole iteration budget before
+/// giving up on an unreachable goal.  Map generators can use region sizes to
+/// find and fill unreachable pockets.
+///
+/// Connectivity follows the movement rule: only `DiagonalRule::Always` links
+/// diagonal neighbours.  A diagonal step that may not cut corners always has
+/// a 4-directional detour, so it never joins regions on its own.
+///
+/// Labels are stable while the map is unchanged.  `set_passable` repairs
+/// labels locally: opening a tile merges regions by relabelling the smaller
+/// ones, and closing one only floods when it might split a region.
+#[derive(Debug, Clone)]
+pub struct RegionMap {
+    width: i32,
+    height: i32,
+    diagonals: bool,
+    labels: Vec<u32>,
+    /// Tile count per label; 0 for unused labels.
+    sizes: Vec<usize>,
+    free: Vec<u32>,
+}
+
+const NONE: u32 = u32::MAX;
+
+/// The 8 tiles around a centre, in ring order.
+const RING: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+
+impl RegionMap {
+    pub fn new(
+        width: i32,
+        height: i32,
+        is_passable: impl Fn(i32, i32) -> bool,
+        diagonals: DiagonalRule,
+    ) -> Self {
+        let width = width.max(0);
+        let height = height.max(0);
+        let size = (width * height) as usize;
+        let mut map = Self {
+            width,
+            height,
+            diagonals: diagonals == DiagonalRule::Always,
+            labels: (0..size as i32)
+                .map(|i| if is_passable(i % width, i / width) { NONE - 1 } else { NONE })
+                .collect(),
+            sizes: Vec::new(),
+            free: Vec::new(),
+        };
+        // Passable tiles start out as NONE - 1, "passable but unlabelled".
+        for i in 0..size {
+            if map.labels[i] == NONE - 1 {
+                let label = map.new_label();
+                map.sizes[label as usize] = map.flood(map.pos(i), label, |l| l == NONE - 1);
+            }
+        }
+        map
+    }
+
+    pub fn width(&self) -> i32 { self.width }
+    pub fn height(&self) -> i32 { self.height }
+
+    #[inline]
+    fn in_bounds(&self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    #[inline]
+    fn idx(&self, (x, y): (i32, i32)) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    #[inline]
+    fn pos(&self, i: usize) -> (i32, i32) {
+        (i as i32 % self.width, i as i32 / self.width)
+    }
+
+    fn neighborhood(&self) -> &'static [(i32, i32)] {
+        if self.diagonals { &DIRS_8 } else { &DIRS_4 }
+    }
+
+    /// Region label of a tile, or None for walls and out-of-bounds tiles.
+    pub fn region(&self, x: i32, y: i32) -> Option<u32> {
+        if !self.in_bounds((x, y)) {
+            return None;
+        }
+        match self.labels[self.idx((x, y))] {
+            NONE => None,
+            label => Some(label),
+        }
+    }
+
+    pub fn is_passable(&self, x: i32, y: i32) -> bool {
+        self.region(x, y).is_some()
+    }
+
+    /// True if both tiles are passable and connected.
+    pub fn same_region(&self, a: (i32, i32), b: (i32, i32)) -> bool {
+        match (self.region(a.0, a.1), self.region(b.0, b.1)) {
+            (Some(ra), Some(rb)) => ra == rb,
+            _ => false,
+        }
+    }
+
+    /// Whether `astar` and friends can find a path from `start` to `goal`.
+    ///
+    /// Follows their conventions: the goal is always enterable, and an agent
+    /// standing on an impassable tile (often its own body) may step off it.
+    /// Either end may therefore be impassable as long as it touches the
+    /// other's region.
+    pub fn can_reach(&self, start: (i32, i32), goal: (i32, i32)) -> bool {
+        if start == goal {
+            return self.in_bounds(start);
+        }
+        if self.same_region(start, goal) {
+            return true;
+        }
+        let from = self.touching(start);
+        let to = self.touching(goal);
+        from.iter().any(|r| to.contains(r))
+            || self.neighborhood().iter().any(|&(dx, dy)| (start.0 + dx, start.1 + dy) == goal)
+    }
+
+    /// Regions of a tile, or of its neighbours if it is impassable.
+    fn touching(&self, (x, y): (i32, i32)) -> Vec<u32> {
+        if let Some(r) = self.region(x, y) {
+            return vec![r];
+        }
+        let mut regions: Vec<u32> = self.neighborhood().iter()
+            .filter_map(|&(dx, dy)| self.region(x + dx, y + dy))
+            .collect();
+        regions.sort_unstable();
+        regions.dedup();
+        regions
+    }
+
+    /// Number of tiles in a region; 0 for unknown labels.
+    pub fn region_size(&self, label: u32) -> usize {
+        self.sizes.get(label as usize).copied().unwrap_or(0)
+    }
+
+    /// Number of regions.
+    pub fn region_count(&self) -> usize {
+        self.sizes.iter().filter(|&&s| s > 0).count()
+    }
+
+    /// All `(label, size)` pairs, in label order.
+    pub fn regions(&self) -> impl Iterator<Item = (u32, usize)> + '_ {
+        self.sizes.iter().enumerate()
+            .filter(|&(_, &s)| s > 0)
+            .map(|(l, &s)| (l as u32, s))
+    }
+
+    /// The region with the most tiles (the lowest label on ties).
+    pub fn largest_region(&self) -> Option<u32> {
+        self.regions().max_by_key(|&(l, s)| (s, std::cmp::Reverse(l))).map(|(l, _)| l)
+    }
+
+    /// Every tile of a region, in row-major order.
+    pub fn tiles(&self, label: u32) -> Vec<(i32, i32)> {
+        (0..self.labels.len())
+            .filter(|&i| self.labels[i] == label)
+            .map(|i| self.pos(i))
+            .collect()
+    }
+
+    /// Open or close a single tile and repair the labels.
+    pub fn set_passable(&mut self, x: i32, y: i32, passable: bool) {
+        if !self.in_bounds((x, y)) || self.is_passable(x, y) == passable {
+            return;
+        }
+        if passable { self.open_tile((x, y)) } else { self.close_tile((x, y)) }
+    }
+
+    fn open_tile(&mut self, pos: (i32, i32)) {
+        let mut adjacent = self.touching(pos);
+        let i = self.idx(pos);
+        let Some(&keep) = adjacent.iter().max_by_key(|&&l| (self.sizes[l as usize], std::cmp::Reverse(l))) else {
+            let label = self.new_label();
+            self.labels[i] = label;
+            self.sizes[label as usize] = 1;
+            return;
+        };
+        // Merge everything into the biggest neighbouring region, so only the
+        // smaller ones get relabelled.
+        adjacent.retain(|&l| l != keep);
+        self.labels[i] = keep;
+        self.sizes[keep as usize] += 1;
+        for other in adjacent {
+            let start = self.neighborhood().iter()
+                .map(|&(dx, dy)| (pos.0 + dx, pos.1 + dy))
+                .find(|&(nx, ny)| self.region(nx, ny) == Some(other))
+                .unwrap();
+            let moved = self.flood(start, keep, |l| l == other);
+            self.sizes[keep as usize] += moved;
+            self.release(other);
+        }
+    }
+
+    fn close_tile(&mut self, pos: (i32, i32)) {
+        let i = self.idx(pos);
+        let old = self.labels[i];
+        self.labels[i] = NONE;
+        self.sizes[old as usize] -= 1;
+        if self.sizes[old as usize] == 0 {
+            self.release(old);
+            return;
+        }
+        if !self.might_split(pos) {
+            return;
+        }
+        // Give each piece that is still connected to a neighbour a new label.
+        let seeds: Vec<(i32, i32)> = self.neighborhood().iter()
+            .map(|&(dx, dy)| (pos.0 + dx, pos.1 + dy))
+            .filter(|&(nx, ny)| self.region(nx, ny) == Some(old))
+            .collect();
+        for seed in seeds {
+            if self.labels[self.idx(seed)] != old {
+                continue; // already reached from an earlier seed
+            }
+            let label = self.new_label();
+            let count = self.flood(seed, label, |l| l == old);
+            self.sizes[label as usize] = count;
+        }
+        self.release(old);
+    }
+
+    /// False when the passable tiles around `pos` are connected to each other
+    /// without it, so closing it cannot split its region.
+    fn might_split(&self, pos: (i32, i32)) -> bool {
+        let open: Vec<bool> = RING.iter().map(|&(dx, dy)| self.is_passable(pos.0 + dx, pos.1 + dy)).collect();
+        // Ring tiles that were linked to the centre.
+        let linked = |k: usize| open[k] && (self.diagonals || k % 2 == 1);
+        let Some(first) = (0..8).find(|&k| linked(k)) else { return false };
+        // Flood the ring from `first`.  Consecutive ring tiles are always
+        // 4-adjacent; with diagonals, edge tiles also touch the next edge tile.
+        let mut reached = [false; 8];
+        reached[first] = true;
+        let mut stack = vec![first];
+        while let Some(k) = stack.pop() {
+            let mut nexts = vec![(k + 1) % 8, (k + 7) % 8];
+            if self.diagonals && k % 2 == 1 {
+                nexts.extend([(k + 2) % 8, (k + 6) % 8]);
+            }
+            for next in nexts {
+                if open[next] && !reached[next] {
+                    reached[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        (0..8).any(|k| linked(k) && !reached[k])
+    }
+
+    fn new_label(&mut self) -> u32 {
+        match self.free.pop() {
+            Some(label) => label,
+            None => {
+                self.sizes.push(0);
+                (self.sizes.len() - 1) as u32
+            }
+        }
+    }
+
+    fn release(&mut self, label: u32) {
+        self.sizes[label as usize] = 0;
+        self.free.push(label);
+    }
+
+    /// Relabel the tiles reachable from `start` whose label matches `from` to
+    /// `label`.  Returns how many were relabelled.
+    fn flood(&mut self, start: (i32, i32), label: u32, from: impl Fn(u32) -> bool) -> usize {
+        let si = self.idx(start);
+        if !from(self.labels[si]) {
+            return 0;
+        }
+        self.labels[si] = label;
+        let mut count = 1;
+        let mut queue = VecDeque::from([start]);
+        while let Some((x, y)) = queue.pop_front() {
+            for &(dx, dy) in self.neighborhood() {
+                let n = (x + dx, y + dy);
+                if !self.in_bounds(n) {
+                    continue;
+                }
+                let ni = self.idx(n);
+                if from(self.labels[ni]) {
+                    self.labels[ni] = label;
+                    count += 1;
+                    queue.push_back(n);
+                }
+            }
+        }
+        count
+    }
+}
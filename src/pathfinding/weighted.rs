@@ -0,0 +1,202 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// =============================================================================
+// WEIGHTED A* PATHFINDING
+// =============================================================================
+
+/// Distance estimate used to guide weighted A*.
+///
+/// Values are measured in straight steps and scaled by
+/// `AStarOptions::min_cost`, so the heuristic stays admissible as long as no
+/// straight step is cheaper than `min_cost` (and, for `Octile` / `Euclidean`,
+/// no diagonal step is cheaper than `min_cost × √2`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    /// `dx + dy` — exact for 4-directional movement.
+    Manhattan,
+    /// `max(dx, dy)` — exact when diagonals cost the same as straight steps.
+    Chebyshev,
+    /// `max + (√2 − 1) × min` — exact when diagonals cost `√2` straight steps.
+    Octile,
+    /// `max + 0.4 × min` — exact when diagonals cost 1.4 straight steps, the
+    /// integer 10/14 costs of `astar_8dir`.  Use instead of `Octile` with
+    /// those costs, which it would overestimate.
+    IntegerOctile,
+    /// Straight-line distance.
+    Euclidean,
+    /// Always 0; degrades A* to Dijkstra's algorithm.
+    Zero,
+}
+
+impl Heuristic {
+    /// Estimated cost between two tiles given the cost of one straight step.
+    pub fn estimate(self, a: (i32, i32), b: (i32, i32), min_cost: u32) -> u32 {
+        let dx = (a.0 - b.0).unsigned_abs();
+        let dy = (a.1 - b.1).unsigned_abs();
+        let (lo, hi) = (dx.min(dy), dx.max(dy));
+        match self {
+            Heuristic::Manhattan => (dx + dy) * min_cost,
+            Heuristic::Chebyshev => hi * min_cost,
+            Heuristic::Octile => {
+                hi * min_cost + ((std::f64::consts::SQRT_2 - 1.0) * (lo * min_cost) as f64) as u32
+            }
+            Heuristic::IntegerOctile => hi * min_cost + lo * min_cost * 2 / 5,
+            Heuristic::Euclidean => (((dx * dx + dy * dy) as f64).sqrt() * min_cost as f64) as u32,
+            Heuristic::Zero => 0,
+        }
+    }
+}
+
+/// Whether and how diagonal steps are taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagonalRule {
+    /// Diagonals are always allowed, even squeezing between two walls.
+    Always,
+    /// Diagonals are allowed only when both orthogonally adjacent tiles can
+    /// be entered, so paths never clip a wall corner.
+    NoCornerCutting,
+    /// 4-directional movement only.
+    Never,
+}
+
+/// Configuration for `astar_weighted`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AStarOptions {
+    pub heuristic: Heuristic,
+    pub diagonals: DiagonalRule,
+    /// Cost of the cheapest straight step; scales the heuristic.
+    pub min_cost: u32,
+    /// When true, the goal can be entered even if `cost` rejects it (it is
+    /// often the target entity).  Entering it then costs `min_cost`.
+    pub goal_always_enterable: bool,
+    /// Maximum nodes to expand before giving up.
+    pub max_iterations: usize,
+}
+
+impl Default for AStarOptions {
+    /// Matches `astar`: 4-directional, Manhattan, enterable goal.
+    fn default() -> Self {
+        Self {
+            heuristic: Heuristic::Manhattan,
+            diagonals: DiagonalRule::Never,
+            min_cost: 1,
+            goal_always_enterable: true,
+            max_iterations: 10_000,
+        }
+    }
+}
+
+impl AStarOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self { self.heuristic = heuristic; self }
+    pub fn with_diagonals(mut self, diagonals: DiagonalRule) -> Self { self.diagonals = diagonals; self }
+    pub fn with_min_cost(mut self, min_cost: u32) -> Self { self.min_cost = min_cost; self }
+    pub fn with_goal_always_enterable(mut self, enterable: bool) -> Self { self.goal_always_enterable = enterable; self }
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self { self.max_iterations = max_iterations; self }
+}
+
+pub(crate) const DIRS_4: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+pub(crate) const DIRS_8: [(i32, i32); 8] = [
+    (0, -1), (1, 0), (0, 1), (-1, 0),
+    (1, -1), (1, 1), (-1, 1), (-1, -1),
+];
+
+/// A* with per-step costs.
+///
+/// `cost(from, to)` returns the cost of stepping between two adjacent tiles,
+/// or `None` if the step is impossible.  Costs may depend on direction, so
+/// roads can be cheap, fire expensive and doors costly to open.
+///
+/// Returns the path including start and goal, plus its total cost.  Returns
+/// None if no path exists or `max_iterations` is exceeded.
+pub fn astar_weighted(
+    start: (i32, i32),
+    goal: (i32, i32),
+    width: i32,
+    height: i32,
+    cost: impl Fn((i32, i32), (i32, i32)) -> Option<u32>,
+    options: &AStarOptions,
+) -> Option<(Vec<(i32, i32)>, u32)> {
+    if width <= 0 || height <= 0 { return None; }
+    if start.0 < 0 || start.0 >= width || start.1 < 0 || start.1 >= height { return None; }
+    if goal.0  < 0 || goal.0  >= width || goal.1  < 0 || goal.1  >= height { return None; }
+
+    if start == goal {
+        return Some((vec![start], 0));
+    }
+
+    let size = (width * height) as usize;
+    // Priority queue: (f_score, h_score, x, y) - h breaks ties toward the goal
+    let mut open: BinaryHeap<Reverse<(u32, u32, i32, i32)>> = BinaryHeap::new();
+    let mut came_from: Vec<i32> = vec![-1; size];
+    let mut g_score: Vec<u32> = vec![u32::MAX; size];
+
+    g_score[(start.1 * width + start.0) as usize] = 0;
+    let h = options.heuristic.estimate(start, goal, options.min_cost);
+    open.push(Reverse((h, h, start.0, start.1)));
+
+    let directions: &[(i32, i32)] = match options.diagonals {
+        DiagonalRule::Never => &DIRS_4,
+        _ => &DIRS_8,
+    };
+    let step_cost = |from: (i32, i32), to: (i32, i32)| {
+        cost(from, to).or_else(|| (options.goal_always_enterable && to == goal).then_some(options.min_cost))
+    };
+
+    let mut iterations = 0;
+
+    while let Some(Reverse((f, h, cx, cy))) = open.pop() {
+        let current_idx = (cy * width + cx) as usize;
+        let current_g = g_score[current_idx];
+        // Stale queue entry: a cheaper route to this tile was already expanded.
+        if f - h > current_g {
+            continue;
+        }
+
+        iterations += 1;
+        if iterations > options.max_iterations {
+            return None;
+        }
+
+        if (cx, cy) == goal {
+            let mut path = vec![goal];
+            let mut idx = current_idx as i32;
+            while came_from[idx as usize] != -1 {
+                idx = came_from[idx as usize];
+                path.push((idx % width, idx / width));
+            }
+            path.reverse();
+            return Some((path, current_g));
+        }
+
+        for &(dx, dy) in directions {
+            let (nx, ny) = (cx + dx, cy + dy);
+            if nx < 0 || nx >= width || ny < 0 || ny >= height { continue; }
+
+            if dx != 0 && dy != 0 && options.diagonals == DiagonalRule::NoCornerCutting {
+                let side_a = cost((cx, cy), (cx + dx, cy)).is_some();
+                let side_b = cost((cx, cy), (cx, cy + dy)).is_some();
+                if !side_a || !side_b {
+                    continue;
+                }
+            }
+
+            let Some(step) = step_cost((cx, cy), (nx, ny)) else { continue };
+            let next_idx = (ny * width + nx) as usize;
+            let new_g = current_g.saturating_add(step);
+
+            if new_g < g_score[next_idx] {
+                g_score[next_idx] = new_g;
+                came_from[next_idx] = current_idx as i32;
+                let h = options.heuristic.estimate((nx, ny), goal, options.min_cost);
+                open.push(Reverse((new_g.saturating_add(h), h, nx, ny)));
+            }
+        }
+    }
+
+    None
+}
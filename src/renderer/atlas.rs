@@ -1,5 +1,15 @@
 use wgpu::util::DeviceExt;
 
+/// Handle to a tile atlas registered with `Renderer::register_tileset`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TilesetId(pub(crate) u16);
+
+impl TilesetId {
+    /// The atlas given to `EngineBuilder::with_tileset`, registered as
+    /// `"default"`.
+    pub const DEFAULT: TilesetId = TilesetId(0);
+}
+
 pub struct Atlas {
     pub texture_view: wgpu::TextureView,
     pub sampler: wgpu::Sampler,
@@ -0,0 +1,280 @@
+// ── Screenshots and frame capture ─────────────────────────────────────────────
+//
+// Copies finished (post-processed) frames to the CPU without stalling the
+// render loop: the GPU copy is recorded into the frame's own command buffer,
+// the buffer is mapped asynchronously and collected on a later frame, and
+// PNG encoding happens on a background thread.
+//
+// Two consumers:
+// - one-off screenshots (`request_screenshot`), written as soon as the frame
+//   arrives;
+// - a rolling recording of the last N seconds (`start_recording`), kept in
+//   memory and written as a numbered PNG sequence on demand
+//   (`dump_recording`).
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use image::RgbaImage;
+
+use super::readback::FrameReadback;
+
+/// True for names `dump_recording` writes: `frame_` and at least five
+/// digits, then `.png`.
+fn is_dumped_frame(name: &str) -> bool {
+    name.strip_prefix("frame_")
+        .and_then(|rest| rest.strip_suffix(".png"))
+        .is_some_and(|digits| digits.len() >= 5 && digits.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Delete numbered frames left in `dir` by an earlier dump.
+fn remove_old_frames(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        if !is_dumped_frame(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+        if let Err(e) = std::fs::remove_file(entry.path()) {
+            eprintln!("[capture] cannot remove '{}': {e}", entry.path().display());
+        }
+    }
+}
+
+/// What a captured frame is for.
+struct Purpose {
+    /// Screenshot paths to write the frame to.
+    screenshots: Vec<PathBuf>,
+    /// Capture time, if the frame belongs in the recording.
+    recorded_at: Option<Instant>,
+}
+
+/// A frame copy waiting for its buffer to be mapped.
+struct InFlight {
+    readback: FrameReadback,
+    mapped: Receiver<Result<(), wgpu::BufferAsyncError>>,
+    purpose: Purpose,
+}
+
+/// A frame copy recorded into the current frame's encoder, not yet
+/// submitted.
+pub(crate) struct PendingCapture {
+    readback: FrameReadback,
+    purpose: Purpose,
+}
+
+/// Rolling in-memory buffer of recent frames.
+struct Recording {
+    length: Duration,
+    interval: Duration,
+    last: Option<Instant>,
+    frames: VecDeque<(Instant, Arc<RgbaImage>)>,
+}
+
+/// Screenshot and rolling frame capture for a `Renderer`.
+///
+/// Recorded frames are kept uncompressed: an 800×600 window recorded at 10 fps
+/// for 5 seconds holds about 100 MB.  Keep the rate and length modest.
+pub struct FrameCapture {
+    screenshots: Vec<PathBuf>,
+    recording: Option<Recording>,
+    in_flight: Vec<InFlight>,
+    writers: Vec<JoinHandle<()>>,
+    /// Latest recording dump writer per target directory.
+    dump_writers: HashMap<PathBuf, JoinHandle<()>>,
+    /// Set once the "unsupported" warning has been printed.
+    warned: bool,
+}
+
+impl Default for FrameCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameCapture {
+    pub fn new() -> Self {
+        Self {
+            screenshots: Vec::new(),
+            recording: None,
+            in_flight: Vec::new(),
+            writers: Vec::new(),
+            dump_writers: HashMap::new(),
+            warned: false,
+        }
+    }
+
+    /// Save the next rendered frame as a PNG at `path`.
+    pub fn request_screenshot(&mut self, path: impl Into<PathBuf>) {
+        self.screenshots.push(path.into());
+    }
+
+    /// Keep the last `seconds` of frames, sampled at up to `fps` frames per
+    /// second.  Restarting clears frames recorded so far.
+    pub fn start_recording(&mut self, seconds: f32, fps: f32) {
+        self.recording = Some(Recording {
+            length: Duration::from_secs_f32(seconds.max(0.0)),
+            interval: Duration::from_secs_f32(1.0 / fps.max(0.001)),
+            last: None,
+            frames: VecDeque::new(),
+        });
+    }
+
+    /// Stop recording and drop the recorded frames.
+    pub fn stop_recording(&mut self) {
+        self.recording = None;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Number of frames currently held by the recording.
+    pub fn recorded_frames(&self) -> usize {
+        self.recording.as_ref().map_or(0, |r| r.frames.len())
+    }
+
+    /// Write the recorded frames, oldest first, to `dir` as
+    /// `frame_00000.png`, `frame_00001.png`, ….  Files in `dir` with that
+    /// numbered form are deleted first, so the sequence never mixes two
+    /// dumps; any other file is left alone.
+    ///
+    /// Writing happens on a background thread and the recording continues.
+    /// Dumps to the same `dir` path run one after another.  Returns the
+    /// number of frames being written.
+    pub fn dump_recording(&mut self, dir: impl AsRef<Path>) -> usize {
+        let Some(recording) = &self.recording else { return 0 };
+        let frames: Vec<Arc<RgbaImage>> = recording.frames.iter().map(|(_, f)| Arc::clone(f)).collect();
+        let count = frames.len();
+        let dir = dir.as_ref().to_path_buf();
+        let previous = self.dump_writers.remove(&dir);
+        let target = dir.clone();
+        let writer = std::thread::spawn(move || {
+            if let Some(previous) = previous {
+                let _ = previous.join();
+            }
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                eprintln!("[capture] cannot create '{}': {e}", dir.display());
+                return;
+            }
+            remove_old_frames(&dir);
+            for (i, frame) in frames.iter().enumerate() {
+                let path = dir.join(format!("frame_{i:05}.png"));
+                if let Err(e) = frame.save(&path) {
+                    eprintln!("[capture] failed to write '{}': {e}", path.display());
+                }
+            }
+        });
+        self.dump_writers.insert(target, writer);
+        count
+    }
+
+    /// Record a copy of `texture` into `encoder` if a screenshot was requested
+    /// or the recording is due a frame.
+    pub(crate) fn begin_frame(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) -> Option<PendingCapture> {
+        let now = Instant::now();
+        let record = self.recording.as_ref()
+            .is_some_and(|r| r.last.is_none_or(|last| now.duration_since(last) >= r.interval));
+        if self.screenshots.is_empty() && !record {
+            return None;
+        }
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) || !FrameReadback::supports(texture.format()) {
+            if !self.warned {
+                eprintln!("[capture] frame capture is not supported for this surface ({:?}).", texture.format());
+                self.warned = true;
+            }
+            self.screenshots.clear();
+            return None;
+        }
+
+        let recorded_at = match &mut self.recording {
+            Some(recording) if record => {
+                recording.last = Some(now);
+                Some(now)
+            }
+            _ => None,
+        };
+        let purpose = Purpose { screenshots: std::mem::take(&mut self.screenshots), recorded_at };
+        Some(PendingCapture { readback: FrameReadback::copy_from(device, encoder, texture), purpose })
+    }
+
+    /// Start mapping a capture once its frame has been submitted.
+    pub(crate) fn submitted(&mut self, capture: PendingCapture) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        capture.readback.map(move |result| {
+            let _ = tx.send(result);
+        });
+        self.in_flight.push(InFlight { readback: capture.readback, mapped: rx, purpose: capture.purpose });
+    }
+
+    /// Collect captures whose buffers have been mapped.  Never blocks.
+    pub(crate) fn poll(&mut self, device: &wgpu::Device) {
+        if self.in_flight.is_empty() {
+            return;
+        }
+        let _ = device.poll(wgpu::PollType::Poll);
+        self.collect();
+    }
+
+    /// Block until every pending capture has been collected and every PNG
+    /// has been written.
+    pub fn flush(&mut self, device: &wgpu::Device) {
+        if !self.in_flight.is_empty() {
+            let _ = device.poll(wgpu::PollType::wait_indefinitely());
+            self.collect();
+        }
+        let dumps = self.dump_writers.drain().map(|(_, w)| w);
+        for writer in self.writers.drain(..).chain(dumps) {
+            let _ = writer.join();
+        }
+    }
+
+    fn collect(&mut self) {
+        let mut i = 0;
+        while i < self.in_flight.len() {
+            match self.in_flight[i].mapped.try_recv() {
+                Err(TryRecvError::Empty) => i += 1,
+                Ok(Err(_)) | Err(TryRecvError::Disconnected) => {
+                    self.in_flight.remove(i);
+                }
+                Ok(Ok(())) => {
+                    // `remove` keeps recording frames in order.
+                    let InFlight { readback, purpose, .. } = self.in_flight.remove(i);
+                    let frame = readback.finish();
+                    self.deliver(frame, purpose);
+                }
+            }
+        }
+        self.writers.retain(|w| !w.is_finished());
+        self.dump_writers.retain(|_, w| !w.is_finished());
+    }
+
+    fn deliver(&mut self, frame: RgbaImage, purpose: Purpose) {
+        let frame = Arc::new(frame);
+        if !purpose.screenshots.is_empty() {
+            let frame = Arc::clone(&frame);
+            self.writers.push(std::thread::spawn(move || {
+                for path in purpose.screenshots {
+                    match frame.save(&path) {
+                        Ok(()) => eprintln!("[capture] saved screenshot '{}'", path.display()),
+                        Err(e) => eprintln!("[capture] failed to write '{}': {e}", path.display()),
+                    }
+                }
+            }));
+        }
+        if let (Some(at), Some(recording)) = (purpose.recorded_at, self.recording.as_mut()) {
+            recording.frames.push_back((at, frame));
+            while recording.frames.front().is_some_and(|(t, _)| at.duration_since(*t) > recording.length) {
+                recording.frames.pop_front();
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 pub mod atlas;
+pub mod capture;
 pub mod particle_pipeline;
 pub mod pipeline;
 pub mod post_process;
+pub mod readback;
 pub mod sprite_atlas;
 pub mod text;
 pub mod text_pipeline;
@@ -14,10 +16,12 @@ use wgpu::util::DeviceExt;
 use winit::dpi::PhysicalSize;
 use winit::window::Window;
 
-use atlas::Atlas;
+use atlas::{Atlas, TilesetId};
+use capture::FrameCapture;
 use particle_pipeline::{ParticlePipeline, ParticleVertex, create_particle_pipeline};
-use pipeline::{TilePipeline, TileVertex, create_tile_pipeline, orthographic_projection};
+use pipeline::{TileBatch, TilePipeline, TileVertex, create_tile_pipeline, orthographic_projection};
 use post_process::PostProcessStack;
+use readback::FrameReadback;
 use sprite_atlas::SpriteAtlas;
 use text::Vertex as TextVertex;
 use text_pipeline::{TextPipeline, create_text_pipeline};
@@ -47,9 +51,18 @@ struct MtsdfParams {
 // (no gamma conversion — distance values are linear).
 static MTSDF_FONT_PNG: &[u8] = include_bytes!("../../resources/font_atlas.png");
 
+/// Where finished frames go.
+enum FrameTarget {
+    /// Presented to a window surface.
+    Surface(wgpu::Surface<'static>),
+    /// Kept in an offscreen texture for readback (`Renderer::new_headless`).
+    Offscreen(wgpu::Texture),
+}
+
 pub struct Renderer {
-    pub window: Arc<Window>,
-    surface: wgpu::Surface<'static>,
+    /// The window being drawn to; None for a headless renderer.
+    window: Option<Arc<Window>>,
+    target: FrameTarget,
     pub(crate) device: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
@@ -71,8 +84,9 @@ pub struct Renderer {
     /// Storage buffer for entity animation offsets [f32; 2], indexed by entity_id.
     entity_offsets_buffer: wgpu::Buffer,
     entity_offsets_bind_group: wgpu::BindGroup,
-    /// Bind group for the character/glyph tile atlas (always present).
-    atlas_bind_group: wgpu::BindGroup,
+    /// Registered tile atlases for the character grid, indexed by
+    /// `TilesetId`; index 0 is the builder's tileset (always present).
+    tilesets: Vec<Tileset>,
     /// Bind group for the optional sprite atlas (None until load_sprite_folder is called).
     sprite_atlas_bind_group: Option<wgpu::BindGroup>,
     /// Keeps the MTSDF font GPU texture alive (TextureView holds a ref-count
@@ -104,7 +118,6 @@ pub struct Renderer {
     text_vertex_buffer_capacity: u32,
     text_index_buffer: Option<wgpu::Buffer>,
     text_index_buffer_capacity: u32,
-    pub(crate) atlas: Atlas,
     /// Loaded sprite atlas metadata (UVs, tile spans, etc.).
     pub(crate) sprite_atlas: Option<SpriteAtlas>,
     // ── Modern UI vertex buffer ──
@@ -115,6 +128,55 @@ pub struct Renderer {
     ui_sprite_vertex_buffer_capacity: u32,
     /// Post-processing stack for "juice" effects.
     pub post_process: PostProcessStack,
+    /// Screenshots and rolling frame recording of the final frame.
+    pub capture: FrameCapture,
+}
+
+/// Geometry for one frame, passed to [`Renderer::render`].
+#[derive(Clone, Copy, Default)]
+pub struct FrameVertices<'a> {
+    /// Background fills and glyphs of the character grid.
+    pub char_verts: &'a [TileVertex],
+    /// Per-tileset ranges of `char_verts`.
+    pub char_batches: &'a [TileBatch],
+    pub sprite_verts: &'a [TileVertex],
+    pub particle_verts: &'a [ParticleVertex],
+    pub ui_verts: &'a [UIVertex],
+    /// Screen-space sprites.
+    pub ui_sprite_verts: &'a [TileVertex],
+    pub text_verts: &'a [TextVertex],
+    pub text_indices: &'a [u16],
+}
+
+/// A named tile atlas for the character grid.
+struct Tileset {
+    name: String,
+    atlas: Atlas,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Bind group for sampling an atlas texture in the tile pipeline.
+fn create_atlas_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+    label: &str,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(label),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
 }
 
 /// Load a PNG from raw bytes as an `Rgba8Unorm` texture (no gamma conversion).
@@ -150,6 +212,24 @@ fn load_rgba8_texture(
     (texture, view)
 }
 
+/// Colour texture used as the frame target of a headless renderer.
+fn create_offscreen_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("offscreen_frame"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: config.usage,
+        view_formats: &[],
+    })
+}
+
 fn create_depth_texture(
     device: &wgpu::Device,
     config: &wgpu::SurfaceConfiguration,
@@ -198,8 +278,11 @@ impl Renderer {
         let caps = surface.get_capabilities(&adapter);
         let format = caps.formats[0];
 
+        // COPY_SRC lets `capture` read frames back, where the surface allows it.
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (caps.usages & wgpu::TextureUsages::COPY_SRC);
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format,
             width: size.width.max(1),
             height: size.height.max(1),
@@ -211,7 +294,72 @@ impl Renderer {
         surface.configure(&device, &config);
 
         let atlas = Atlas::from_png(&device, &queue, png_bytes, tile_w, tile_h);
-        
+        Self::from_parts(device, queue, config, Some(window), FrameTarget::Surface(surface), atlas, use_scanlines)
+    }
+
+    /// Create a renderer without a window.  Frames are drawn into an
+    /// offscreen `width × height` texture with the same pipelines and
+    /// post-processing as a windowed renderer; read them back with
+    /// [`Renderer::read_frame`].
+    ///
+    /// Prefers wgpu's software fallback adapter so output does not depend on
+    /// the GPU, and falls back to any adapter.  Returns None if no adapter or
+    /// device is available (e.g. on a CI machine without any graphics stack).
+    pub async fn new_headless(
+        width: u32,
+        height: u32,
+        png_bytes: &[u8],
+        tile_w: u32,
+        tile_h: u32,
+        use_scanlines: bool,
+    ) -> Option<Self> {
+        let instance = wgpu::Instance::default();
+        let software = wgpu::RequestAdapterOptions {
+            force_fallback_adapter: true,
+            ..Default::default()
+        };
+        let adapter = match instance.request_adapter(&software).await {
+            Ok(adapter) => adapter,
+            Err(_) => instance.request_adapter(&wgpu::RequestAdapterOptions::default()).await.ok()?,
+        };
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor::default())
+            .await
+            .ok()?;
+
+        // Rgba8 sRGB matches what the surface formats show on screen and reads
+        // back without swizzling.
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::AutoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+        let texture = create_offscreen_texture(&device, &config);
+
+        let atlas = Atlas::from_png(&device, &queue, png_bytes, tile_w, tile_h);
+        Some(Self::from_parts(device, queue, config, None, FrameTarget::Offscreen(texture), atlas, use_scanlines))
+    }
+
+    /// Shared setup of pipelines, bind groups and buffers once the device and
+    /// frame target exist.
+    fn from_parts(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        config: wgpu::SurfaceConfiguration,
+        window: Option<Arc<Window>>,
+        target: FrameTarget,
+        atlas: Atlas,
+        use_scanlines: bool,
+    ) -> Self {
+        let format = config.format;
+        let scale_factor = window.as_ref().map_or(1.0, |w| w.scale_factor()) as f32;
+
         // ── Entity Offsets Storage Buffer ──
         // Pre-allocated for MAX_ANIMATED_ENTITIES entries (16 bytes each = [f32;4] for alignment).
         let initial_offsets = vec![[0.0f32, 0.0, 0.0, 0.0]; crate::engine::MAX_ANIMATED_ENTITIES];
@@ -291,20 +439,9 @@ impl Renderer {
         });
 
         // ── Tile atlas bind group (Nearest sampler) ───────────────────────
-        let atlas_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("atlas_bg"),
-            layout: &tile_pipeline.atlas_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&atlas.texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&atlas.sampler),
-                },
-            ],
-        });
+        let atlas_bind_group = create_atlas_bind_group(
+            &device, &tile_pipeline.atlas_bind_group_layout, &atlas.texture_view, &atlas.sampler, "atlas_bg");
+        let tilesets = vec![Tileset { name: "default".to_string(), atlas, bind_group: atlas_bind_group }];
 
         // ── MTSDF font atlas (Linear sampler, Rgba8Unorm) ─────────────────
         let (font_texture, font_view) =
@@ -358,13 +495,13 @@ impl Renderer {
             post_process.add_effect(Box::new(post_process::ScanlineEffect::new(
                 &device,
                 config.format,
-                window.scale_factor() as f32,
+                scale_factor,
             )));
         }
 
         Self {
             window,
-            surface,
+            target,
             device,
             queue,
             config,
@@ -379,7 +516,7 @@ impl Renderer {
             camera_bind_group,
             entity_offsets_buffer,
             entity_offsets_bind_group,
-            atlas_bind_group,
+            tilesets,
             sprite_atlas_bind_group: None,
             font_texture,
             font_bind_group,
@@ -397,13 +534,13 @@ impl Renderer {
             text_vertex_buffer_capacity: 0,
             text_index_buffer: None,
             text_index_buffer_capacity: 0,
-            atlas,
             sprite_atlas: None,
             ui_modern_vertex_buffer: None,
             ui_modern_vertex_buffer_capacity: 0,
             ui_sprite_vertex_buffer: None,
             ui_sprite_vertex_buffer_capacity: 0,
             post_process,
+            capture: FrameCapture::new(),
         }
     }
 
@@ -411,37 +548,89 @@ impl Renderer {
     pub fn load_sprite_folder(&mut self, path: &str, tile_w: u32, tile_h: u32) {
         let atlas = SpriteAtlas::load_folder(&self.device, &self.queue, path, tile_w, tile_h);
 
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("sprite_atlas_bg"),
-            layout: &self.tile_pipeline.atlas_bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&atlas.texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&atlas.sampler),
-                },
-            ],
-        });
+        let bind_group = create_atlas_bind_group(
+            &self.device, &self.tile_pipeline.atlas_bind_group_layout, &atlas.texture_view, &atlas.sampler, "sprite_atlas_bg");
 
         self.sprite_atlas_bind_group = Some(bind_group);
         self.sprite_atlas = Some(atlas);
     }
 
+    /// Register a tile atlas for the character grid under `name` and return
+    /// its handle for `jEngine::set_foreground_from`.  Tiles are read
+    /// row-major in `tile_w × tile_h` cells and stretched to the grid's cell
+    /// size when drawn.
+    ///
+    /// Registering an existing name replaces its atlas and keeps the handle.
+    /// The builder's tileset is registered as `"default"`.
+    pub fn register_tileset(&mut self, name: &str, png_bytes: &[u8], tile_w: u32, tile_h: u32) -> TilesetId {
+        let atlas = Atlas::from_png(&self.device, &self.queue, png_bytes, tile_w, tile_h);
+        let bind_group = create_atlas_bind_group(
+            &self.device, &self.tile_pipeline.atlas_bind_group_layout, &atlas.texture_view, &atlas.sampler, name);
+        let tileset = Tileset { name: name.to_string(), atlas, bind_group };
+
+        if let Some(id) = self.tileset_id(name) {
+            self.tilesets[id.0 as usize] = tileset;
+            return id;
+        }
+        let id = TilesetId(u16::try_from(self.tilesets.len()).expect("too many tilesets"));
+        self.tilesets.push(tileset);
+        id
+    }
+
+    /// Handle of the tileset registered as `name`.
+    pub fn tileset_id(&self, name: &str) -> Option<TilesetId> {
+        self.tilesets.iter().position(|t| t.name == name).map(|i| TilesetId(i as u16))
+    }
+
+    /// The atlas behind a tileset handle.
+    pub fn tileset_atlas(&self, id: TilesetId) -> Option<&Atlas> {
+        self.tilesets.get(id.0 as usize).map(|t| &t.atlas)
+    }
+
+    /// Number of registered tilesets, including the default one.
+    pub fn tileset_count(&self) -> usize {
+        self.tilesets.len()
+    }
+
     /// Returns the metadata (UVs, spans) for a named sprite if it exists.
     pub fn get_sprite_data(&self, name: &str) -> Option<crate::renderer::sprite_atlas::SpriteData> {
         self.sprite_atlas.as_ref()?.get_data(name).cloned()
     }
 
+    /// The window being drawn to, or None for a headless renderer.
+    pub fn window(&self) -> Option<&Arc<Window>> {
+        self.window.as_ref()
+    }
+
+    /// Current frame size in physical pixels: the window's inner size, or
+    /// the offscreen texture size when headless.
+    pub fn size(&self) -> PhysicalSize<u32> {
+        match &self.window {
+            Some(window) => window.inner_size(),
+            None => PhysicalSize::new(self.config.width, self.config.height),
+        }
+    }
+
+    /// The window's DPI scale factor; 1.0 when headless.
+    pub fn scale_factor(&self) -> f64 {
+        self.window.as_ref().map_or(1.0, |w| w.scale_factor())
+    }
+
+    /// True for a renderer created with [`Renderer::new_headless`].
+    pub fn is_headless(&self) -> bool {
+        matches!(self.target, FrameTarget::Offscreen(_))
+    }
+
     pub fn resize(&mut self, new_size: PhysicalSize<u32>) {
         if new_size.width == 0 || new_size.height == 0 {
             return;
         }
         self.config.width = new_size.width;
         self.config.height = new_size.height;
-        self.surface.configure(&self.device, &self.config);
+        match &mut self.target {
+            FrameTarget::Surface(surface) => surface.configure(&self.device, &self.config),
+            FrameTarget::Offscreen(texture) => *texture = create_offscreen_texture(&self.device, &self.config),
+        }
 
         let (depth_texture, depth_view) = create_depth_texture(&self.device, &self.config, "depth_texture");
         self.depth_texture = depth_texture;
@@ -451,7 +640,7 @@ impl Renderer {
         self.queue
             .write_buffer(&self.projection_buffer, 0, bytemuck::cast_slice(&proj));
 
-        self.post_process.resize(&self.device, &self.queue, &self.config, self.window.scale_factor() as f32);
+        self.post_process.resize(&self.device, &self.queue, &self.config, self.scale_factor() as f32);
     }
 
     /// Upload a new camera view-projection matrix to the GPU.
@@ -497,7 +686,8 @@ impl Renderer {
     /// Render one frame.
     ///
     /// Draw order within the single render pass:
-    /// 1. `char_verts`     — character tile atlas (bg fills + char glyphs)   [camera]
+    /// 1. `char_verts`     — character tile atlases (bg fills + char glyphs) [camera],
+    ///    drawn as `char_batches`, one draw per tileset
     /// 2. `sprite_verts`   — sprite atlas (static and animated sprites)       [camera]
     /// 3. `particle_verts` — particle pipeline                                [camera]
     /// 4. `ui_verts`       — UI solid fills (TileVertex, Layer 2)             [screen]
@@ -505,20 +695,27 @@ impl Renderer {
     ///
     /// Passes 1–3 use the camera bind group (scroll/zoom).
     /// Passes 4–5 use the plain projection bind group (screen-fixed).
-    pub fn render(
-        &mut self,
-        char_verts: &[TileVertex],
-        sprite_verts: &[TileVertex],
-        particle_verts: &[ParticleVertex],
-        ui_verts: &[UIVertex],
-        ui_sprite_verts: &[TileVertex],
-        text_verts: &[TextVertex],
-        text_indices: &[u16],
-    ) -> Result<(), wgpu::SurfaceError> {
-        let frame = self.surface.get_current_texture()?;
-        let view = frame
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+    pub fn render(&mut self, vertices: &FrameVertices) -> Result<(), wgpu::SurfaceError> {
+        let FrameVertices {
+            char_verts,
+            char_batches,
+            sprite_verts,
+            particle_verts,
+            ui_verts,
+            ui_sprite_verts,
+            text_verts,
+            text_indices,
+        } = *vertices;
+        let (frame, view) = match &self.target {
+            FrameTarget::Surface(surface) => {
+                let frame = surface.get_current_texture()?;
+                let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+                (Some(frame), view)
+            }
+            FrameTarget::Offscreen(texture) => {
+                (None, texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            }
+        };
 
         // ── Persistent buffer helpers ─────────────────────────────────────
         //
@@ -625,16 +822,19 @@ impl Renderer {
                 multiview_mask: None,
             });
 
-            // ── Pass 1: character tile atlas [camera] ─────────────────────
+            // ── Pass 1: character tile atlases [camera] ───────────────────
             if !char_verts.is_empty() {
                 if let Some(vbuf) = &self.char_vertex_buffer {
                     let byte_len = char_verts.len() as u64 * std::mem::size_of::<TileVertex>() as u64;
                     pass.set_pipeline(&self.tile_pipeline.render_pipeline);
                     pass.set_bind_group(0, &self.camera_bind_group, &[]);
-                    pass.set_bind_group(1, &self.atlas_bind_group, &[]);
                     pass.set_bind_group(2, &self.entity_offsets_bind_group, &[]);
                     pass.set_vertex_buffer(0, vbuf.slice(..byte_len));
-                    pass.draw(0..char_verts.len() as u32, 0..1);
+                    for batch in char_batches {
+                        let Some(tileset) = self.tilesets.get(batch.tileset.0 as usize) else { continue };
+                        pass.set_bind_group(1, &tileset.bind_group, &[]);
+                        pass.draw(batch.vertices.clone(), 0..1);
+                    }
                 }
             }
 
@@ -717,8 +917,40 @@ impl Renderer {
             self.post_process.run(&self.device, &self.queue, &mut encoder, &view);
         }
 
+        // ── Frame capture (screenshots / recording) ───────────────────────
+        let final_texture = match (&frame, &self.target) {
+            (Some(frame), _) => &frame.texture,
+            (None, FrameTarget::Offscreen(texture)) => texture,
+            (None, FrameTarget::Surface(_)) => unreachable!("surface frames always exist"),
+        };
+        let capture = self.capture.begin_frame(&self.device, &mut encoder, final_texture);
+
         self.queue.submit(std::iter::once(encoder.finish()));
-        frame.present();
+        if let Some(capture) = capture {
+            self.capture.submitted(capture);
+        }
+        self.capture.poll(&self.device);
+        if let Some(frame) = frame {
+            frame.present();
+        }
         Ok(())
     }
+
+    /// Wait for pending screenshots and recording dumps to be written.
+    pub fn flush_captures(&mut self) {
+        self.capture.flush(&self.device);
+    }
+
+    /// Read the last rendered frame back from a headless renderer, after
+    /// post-processing.  Blocks until the GPU is done.  Returns None for a
+    /// windowed renderer.
+    pub fn read_frame(&self) -> Option<image::RgbaImage> {
+        let FrameTarget::Offscreen(texture) = &self.target else { return None };
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("read_frame") });
+        let readback = FrameReadback::copy_from(&self.device, &mut encoder, texture);
+        self.queue.submit(std::iter::once(encoder.finish()));
+        readback.read(&self.device)
+    }
 }
@@ -1,3 +1,7 @@
+use std::ops::Range;
+
+use super::atlas::TilesetId;
+
 #[repr(C)]
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct TileVertex {
@@ -31,6 +35,14 @@ impl TileVertex {
     }
 }
 
+/// A run of vertices in a tile vertex buffer that all sample one tileset.
+/// Vertices are grouped by tileset so each atlas costs one draw call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileBatch {
+    pub tileset: TilesetId,
+    pub vertices: Range<u32>,
+}
+
 pub struct TilePipeline {
     pub render_pipeline: wgpu::RenderPipeline,
     pub projection_bind_group_layout: wgpu::BindGroupLayout,
@@ -0,0 +1,120 @@
+// ── Frame readback ────────────────────────────────────────────────────────────
+//
+// Copies a rendered colour texture into a mappable buffer and converts the
+// mapped bytes into an `image::RgbaImage`.  Rows in the buffer are padded to
+// `COPY_BYTES_PER_ROW_ALIGNMENT` (256 bytes) as wgpu requires; the padding is
+// stripped during conversion.
+
+use image::RgbaImage;
+
+/// A pending copy of a frame into CPU-visible memory.
+///
+/// Create it with [`FrameReadback::copy_from`] before submitting the encoder,
+/// then call [`FrameReadback::read`] after the submission.
+pub struct FrameReadback {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+    format: wgpu::TextureFormat,
+}
+
+impl FrameReadback {
+    /// True for the 8-bit RGBA/BGRA formats the readback can convert.
+    pub fn supports(format: wgpu::TextureFormat) -> bool {
+        matches!(
+            format,
+            wgpu::TextureFormat::Rgba8Unorm
+                | wgpu::TextureFormat::Rgba8UnormSrgb
+                | wgpu::TextureFormat::Bgra8Unorm
+                | wgpu::TextureFormat::Bgra8UnormSrgb
+        )
+    }
+
+    /// Record a copy of the whole of `texture` into a new readback buffer.
+    ///
+    /// The texture needs `COPY_SRC` usage and a format accepted by
+    /// [`FrameReadback::supports`].
+    pub fn copy_from(device: &wgpu::Device, encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) -> Self {
+        let (width, height) = (texture.width(), texture.height());
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = (width * 4).div_ceil(align) * align;
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame_readback_buffer"),
+            size: padded_bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+
+        Self { buffer, width, height, padded_bytes_per_row, format: texture.format() }
+    }
+
+    pub fn width(&self) -> u32 { self.width }
+    pub fn height(&self) -> u32 { self.height }
+
+    /// Start mapping the buffer; `callback` runs once the copy has finished
+    /// and the buffer is readable (during a later `Device::poll`).  Call
+    /// only after the encoder holding the copy has been submitted.
+    pub fn map(&self, callback: impl FnOnce(Result<(), wgpu::BufferAsyncError>) + Send + 'static) {
+        self.buffer.map_async(wgpu::MapMode::Read, .., callback);
+    }
+
+    /// Convert a mapped buffer into an image and release it.
+    pub fn finish(self) -> RgbaImage {
+        let image = self.to_image(&self.buffer.slice(..).get_mapped_range());
+        self.buffer.unmap();
+        image
+    }
+
+    /// Block until the GPU has finished the copy and return the frame.
+    ///
+    /// Returns None if mapping fails.
+    pub fn read(self, device: &wgpu::Device) -> Option<RgbaImage> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.map(move |result| {
+            let _ = tx.send(result);
+        });
+        device.poll(wgpu::PollType::wait_indefinitely()).ok()?;
+        rx.recv().ok()?.ok()?;
+        Some(self.finish())
+    }
+
+    /// Convert the mapped buffer contents into an RGBA image, removing row
+    /// padding and swapping channels for BGRA formats.
+    pub fn to_image(&self, data: &[u8]) -> RgbaImage {
+        let row_bytes = (self.width * 4) as usize;
+        let bgra = matches!(
+            self.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+        let mut pixels = Vec::with_capacity(row_bytes * self.height as usize);
+        for row in data.chunks(self.padded_bytes_per_row as usize).take(self.height as usize) {
+            pixels.extend_from_slice(&row[..row_bytes]);
+        }
+        if bgra {
+            for px in pixels.chunks_exact_mut(4) {
+                px.swap(0, 2);
+            }
+        }
+        RgbaImage::from_raw(self.width, self.height, pixels).expect("readback buffer size mismatch")
+    }
+}
@@ -0,0 +1,279 @@
+// =============================================================================
+// TARGETING.RS — Tile targeting mode for ranged attacks and spells
+//
+// A reusable controller that lets the player pick a tile with the keyboard or
+// the mouse, previews the projectile path (`geometry::line`) and the area of
+// effect, and reports the chosen tile or a cancel.
+//
+// Typical use inside a scene:
+//
+//   match self.targeting.update(engine) {
+//       TargetResult::Pending      => {}
+//       TargetResult::Selected(t)  => { /* fire at t */ }
+//       TargetResult::Cancelled    => { /* leave targeting mode */ }
+//   }
+//   // ...and at the end of `draw`:
+//   self.targeting.draw(engine, |x, y| self.is_wall(x, y));
+// =============================================================================
+
+use crate::engine::{Color, jEngine};
+use crate::geometry::{circle_outline, distance_chebyshev, line};
+use crate::input::{ActionMap, InputSource, InputState, KeyCode, MouseButton};
+
+/// Logical inputs understood by the targeting controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    /// Snap the cursor back onto the origin.
+    Recenter,
+    Confirm,
+    Cancel,
+}
+
+/// Outcome of a targeting update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetResult {
+    /// Still choosing.
+    Pending,
+    /// The player confirmed this tile.
+    Selected((i32, i32)),
+    /// The player backed out.
+    Cancelled,
+}
+
+/// The previewed projectile path, split at the first blocking tile.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TargetPath {
+    /// Tiles the projectile travels through, origin excluded.  The last entry
+    /// is the impact tile (a blocking tile, the range limit, or the cursor).
+    pub reached: Vec<(i32, i32)>,
+    /// Tiles past the impact point that the projectile cannot reach.
+    pub blocked: Vec<(i32, i32)>,
+}
+
+impl TargetPath {
+    /// Tile where the projectile stops, if it leaves the origin at all.
+    pub fn impact(&self) -> Option<(i32, i32)> {
+        self.reached.last().copied()
+    }
+}
+
+/// Colors used by `Targeting::draw`.
+#[derive(Debug, Clone, Copy)]
+pub struct TargetingColors {
+    pub path: Color,
+    pub blocked: Color,
+    pub area: Color,
+    pub cursor: Color,
+}
+
+impl Default for TargetingColors {
+    fn default() -> Self {
+        Self {
+            path:    Color([0.20, 0.55, 0.35, 1.0]),
+            blocked: Color([0.55, 0.10, 0.10, 1.0]),
+            area:    Color([0.70, 0.55, 0.10, 1.0]),
+            cursor:  Color([0.95, 0.85, 0.30, 1.0]),
+        }
+    }
+}
+
+/// Tile-picking controller with line and area-of-effect previews.
+pub struct Targeting {
+    /// Tile the projectile is fired from.
+    pub origin: (i32, i32),
+    /// Currently highlighted tile.
+    pub cursor: (i32, i32),
+    /// Maximum Chebyshev range; `None` means unlimited.
+    pub range: Option<i32>,
+    /// Area-of-effect radius around the impact tile; `0` targets a single tile.
+    pub radius: i32,
+    /// Input bindings.  Rebind freely; defaults are set up by `new`.
+    pub actions: ActionMap<TargetAction>,
+    pub colors: TargetingColors,
+    width: i32,
+    height: i32,
+    last_mouse_pos: Option<[f32; 2]>,
+}
+
+impl Targeting {
+    /// Start targeting from `origin` on a `width × height` map.
+    pub fn new(origin: (i32, i32), width: i32, height: i32) -> Self {
+        let mut actions = ActionMap::new();
+        actions.bind(TargetAction::Up,        InputSource::Key(KeyCode::ArrowUp));
+        actions.bind(TargetAction::Up,        InputSource::Key(KeyCode::Numpad8));
+        actions.bind(TargetAction::Down,      InputSource::Key(KeyCode::ArrowDown));
+        actions.bind(TargetAction::Down,      InputSource::Key(KeyCode::Numpad2));
+        actions.bind(TargetAction::Left,      InputSource::Key(KeyCode::ArrowLeft));
+        actions.bind(TargetAction::Left,      InputSource::Key(KeyCode::Numpad4));
+        actions.bind(TargetAction::Right,     InputSource::Key(KeyCode::ArrowRight));
+        actions.bind(TargetAction::Right,     InputSource::Key(KeyCode::Numpad6));
+        actions.bind(TargetAction::UpLeft,    InputSource::Key(KeyCode::Numpad7));
+        actions.bind(TargetAction::UpRight,   InputSource::Key(KeyCode::Numpad9));
+        actions.bind(TargetAction::DownLeft,  InputSource::Key(KeyCode::Numpad1));
+        actions.bind(TargetAction::DownRight, InputSource::Key(KeyCode::Numpad3));
+        actions.bind(TargetAction::Recenter,  InputSource::Key(KeyCode::Numpad5));
+        actions.bind(TargetAction::Confirm,   InputSource::Key(KeyCode::Enter));
+        actions.bind(TargetAction::Confirm,   InputSource::Key(KeyCode::KeyF));
+        actions.bind(TargetAction::Confirm,   InputSource::Mouse(MouseButton::Left));
+        actions.bind(TargetAction::Cancel,    InputSource::Key(KeyCode::Escape));
+        actions.bind(TargetAction::Cancel,    InputSource::Mouse(MouseButton::Right));
+
+        Self {
+            origin,
+            cursor: origin,
+            range: None,
+            radius: 0,
+            actions,
+            colors: TargetingColors::default(),
+            width,
+            height,
+            last_mouse_pos: None,
+        }
+    }
+
+    pub fn with_range(mut self, range: i32) -> Self { self.range = Some(range); self }
+    pub fn with_radius(mut self, radius: i32) -> Self { self.radius = radius.max(0); self }
+    pub fn with_cursor(mut self, cursor: (i32, i32)) -> Self { self.set_cursor(cursor); self }
+
+    /// Move the cursor, clamped to the map bounds.
+    pub fn set_cursor(&mut self, (x, y): (i32, i32)) {
+        self.cursor = (
+            x.clamp(0, (self.width - 1).max(0)),
+            y.clamp(0, (self.height - 1).max(0)),
+        );
+    }
+
+    /// Returns true if the cursor is within range of the origin.
+    pub fn in_range(&self) -> bool {
+        self.range.is_none_or(|r| {
+            distance_chebyshev(self.origin.0, self.origin.1, self.cursor.0, self.cursor.1) <= r
+        })
+    }
+
+    /// Process one tick of input using the engine's input state and camera.
+    ///
+    /// The mouse only moves the cursor when it has actually moved, so keyboard
+    /// targeting is not overridden by a stationary pointer.
+    pub fn update(&mut self, engine: &jEngine) -> TargetResult {
+        let [mx, my] = engine.input.mouse_pos;
+        let [wx, wy] = engine.screen_to_world(mx, my);
+        let tile = (
+            (wx / engine.tile_width() as f32).floor() as i32,
+            (wy / engine.tile_height() as f32).floor() as i32,
+        );
+        self.handle_input(&engine.input, Some(tile))
+    }
+
+    /// Process one tick of input.  `mouse_tile` is the grid tile under the
+    /// pointer (see `update` for the camera-aware conversion).
+    pub fn handle_input(&mut self, input: &InputState, mouse_tile: Option<(i32, i32)>) -> TargetResult {
+        if self.actions.is_pressed(TargetAction::Cancel, input) {
+            return TargetResult::Cancelled;
+        }
+
+        let mouse_moved = self.last_mouse_pos.is_some_and(|p| p != input.mouse_pos);
+        self.last_mouse_pos = Some(input.mouse_pos);
+        if let Some((tx, ty)) = mouse_tile
+            && mouse_moved
+            && tx >= 0 && ty >= 0 && tx < self.width && ty < self.height
+        {
+            self.cursor = (tx, ty);
+        }
+
+        let moves = [
+            (TargetAction::Up, (0, -1)),
+            (TargetAction::Down, (0, 1)),
+            (TargetAction::Left, (-1, 0)),
+            (TargetAction::Right, (1, 0)),
+            (TargetAction::UpLeft, (-1, -1)),
+            (TargetAction::UpRight, (1, -1)),
+            (TargetAction::DownLeft, (-1, 1)),
+            (TargetAction::DownRight, (1, 1)),
+        ];
+        for (action, (dx, dy)) in moves {
+            if self.actions.is_pressed(action, input) {
+                self.set_cursor((self.cursor.0 + dx, self.cursor.1 + dy));
+            }
+        }
+        if self.actions.is_pressed(TargetAction::Recenter, input) {
+            self.cursor = self.origin;
+        }
+
+        if self.actions.is_pressed(TargetAction::Confirm, input) && self.in_range() && self.cursor != self.origin {
+            return TargetResult::Selected(self.cursor);
+        }
+        TargetResult::Pending
+    }
+
+    /// Compute the projectile path from the origin to the cursor.
+    ///
+    /// The path stops at the first tile where `is_blocking` returns true (that
+    /// tile is the impact point) or where it leaves the allowed range.
+    pub fn path(&self, is_blocking: impl Fn(i32, i32) -> bool) -> TargetPath {
+        let mut result = TargetPath::default();
+        let mut stopped = false;
+        for (x, y) in line(self.origin.0, self.origin.1, self.cursor.0, self.cursor.1).into_iter().skip(1) {
+            if stopped {
+                result.blocked.push((x, y));
+                continue;
+            }
+            let out_of_range = self.range.is_some_and(|r| {
+                distance_chebyshev(self.origin.0, self.origin.1, x, y) > r
+            });
+            if out_of_range {
+                stopped = true;
+                result.blocked.push((x, y));
+                continue;
+            }
+            result.reached.push((x, y));
+            if is_blocking(x, y) {
+                stopped = true;
+            }
+        }
+        result
+    }
+
+    /// Outline of the area of effect around the impact tile (empty when
+    /// `radius` is 0 or the path never leaves the origin).
+    pub fn area_outline(&self, is_blocking: impl Fn(i32, i32) -> bool) -> Vec<(i32, i32)> {
+        if self.radius == 0 {
+            return Vec::new();
+        }
+        let Some((cx, cy)) = self.path(is_blocking).impact() else { return Vec::new() };
+        circle_outline(cx, cy, self.radius)
+            .into_iter()
+            .filter(|&(x, y)| x >= 0 && y >= 0 && x < self.width && y < self.height)
+            .collect()
+    }
+
+    /// Paint the path, blocked segment, area outline and cursor onto the
+    /// background layer.  Call at the end of `draw`, after the map.
+    pub fn draw(&self, engine: &mut jEngine, is_blocking: impl Fn(i32, i32) -> bool) {
+        let path = self.path(&is_blocking);
+        for &(x, y) in &path.reached {
+            set_bg(engine, x, y, self.colors.path);
+        }
+        for &(x, y) in &path.blocked {
+            set_bg(engine, x, y, self.colors.blocked);
+        }
+        for (x, y) in self.area_outline(&is_blocking) {
+            set_bg(engine, x, y, self.colors.area);
+        }
+        let cursor = if self.in_range() { self.colors.cursor } else { self.colors.blocked };
+        set_bg(engine, self.cursor.0, self.cursor.1, cursor);
+    }
+}
+
+fn set_bg(engine: &mut jEngine, x: i32, y: i32, color: Color) {
+    if x >= 0 && y >= 0 {
+        engine.set_background(x as u32, y as u32, color);
+    }
+}
@@ -0,0 +1,128 @@
+use jengine::ai::{BbValue, Blackboard, BehaviorLibrary, Brain, Node, Status, run_brains};
+use jengine::ecs::{Entity, World};
+use std::rc::Rc;
+
+#[derive(Debug, PartialEq)]
+struct Hp(i32);
+
+#[derive(Debug, PartialEq, Default)]
+struct Moves(u32);
+
+fn agent(world: &mut World) -> Entity {
+    let e = world.spawn();
+    world.insert(e, Moves::default());
+    world.insert(e, Blackboard::new());
+    e
+}
+
+fn step() -> Node {
+    Node::action(|w, e| {
+        w.get_mut::<Moves>(e).unwrap().0 += 1;
+        Status::Success
+    })
+}
+
+#[test]
+fn sequence_stops_at_first_failure() {
+    let mut world = World::new();
+    let e = agent(&mut world);
+    let tree = Node::sequence(vec![step(), Node::condition(|_, _| false), step()]);
+    assert_eq!(tree.tick(&mut world, e), Status::Failure);
+    assert_eq!(world.get::<Moves>(e), Some(&Moves(1)));
+}
+
+#[test]
+fn selector_falls_through_to_first_success() {
+    let mut world = World::new();
+    let e = agent(&mut world);
+    let tree = Node::selector(vec![
+        Node::condition(|_, _| false),
+        Node::action(|_, _| Status::Running),
+        step(),
+    ]);
+    assert_eq!(tree.tick(&mut world, e), Status::Running);
+    assert_eq!(world.get::<Moves>(e), Some(&Moves(0)));
+}
+
+#[test]
+fn invert_and_succeed_decorators() {
+    let mut world = World::new();
+    let e = agent(&mut world);
+    assert_eq!(Node::invert(Node::condition(|_, _| true)).tick(&mut world, e), Status::Failure);
+    assert_eq!(Node::succeed(Node::condition(|_, _| false)).tick(&mut world, e), Status::Success);
+    assert_eq!(Node::invert(Node::action(|_, _| Status::Running)).tick(&mut world, e), Status::Running);
+}
+
+#[test]
+fn utility_picks_highest_score_and_fails_when_all_zero() {
+    let mut world = World::new();
+    let e = agent(&mut world);
+    world.insert(e, Hp(2));
+
+    let flee = Node::action(|w, e| {
+        w.get_mut::<Blackboard>(e).unwrap().set("choice", BbValue::Int(1));
+        Status::Success
+    });
+    let fight = Node::action(|w, e| {
+        w.get_mut::<Blackboard>(e).unwrap().set("choice", BbValue::Int(2));
+        Status::Success
+    });
+    let tree = Node::utility(vec![
+        (Rc::new(|w: &World, e| if w.get::<Hp>(e).unwrap().0 < 5 { 0.9 } else { 0.0 }), flee),
+        (Rc::new(|_: &World, _| 0.5), fight),
+    ]);
+
+    assert_eq!(tree.tick(&mut world, e), Status::Success);
+    assert_eq!(world.get::<Blackboard>(e).unwrap().get_int("choice"), Some(1));
+
+    world.insert(e, Hp(10));
+    tree.tick(&mut world, e);
+    assert_eq!(world.get::<Blackboard>(e).unwrap().get_int("choice"), Some(2));
+
+    let none = Node::utility(vec![(Rc::new(|_: &World, _| 0.0), step())]);
+    assert_eq!(none.tick(&mut world, e), Status::Failure);
+}
+
+#[test]
+fn run_brains_inserts_blackboard_and_respects_cooldown() {
+    let mut world = World::new();
+    let e = world.spawn();
+    world.insert(e, Moves::default());
+    world.insert(e, Brain::new(Node::cooldown("step", 3, step())));
+
+    for _ in 0..7 {
+        run_brains(&mut world);
+    }
+    // Fires on ticks 1, 4 and 7.
+    assert_eq!(world.get::<Moves>(e), Some(&Moves(3)));
+    assert_eq!(world.get::<Blackboard>(e).unwrap().ticks, 7);
+}
+
+#[test]
+fn archetypes_load_from_json() {
+    let mut lib = BehaviorLibrary::new();
+    lib.register_condition("hurt", |w, e| w.get::<Hp>(e).is_some_and(|h| h.0 < 5));
+    lib.register_action("step", |w, e| {
+        w.get_mut::<Moves>(e).unwrap().0 += 1;
+        Status::Success
+    });
+    lib.load_archetypes_json(
+        r#"{ "coward": { "sequence": [ { "condition": "hurt" }, { "action": "step" } ] } }"#,
+    )
+    .unwrap();
+
+    let mut world = World::new();
+    let e = agent(&mut world);
+    world.insert(e, Hp(3));
+    world.insert(e, lib.brain("coward").unwrap());
+    run_brains(&mut world);
+    assert_eq!(world.get::<Moves>(e), Some(&Moves(1)));
+    assert!(lib.brain("hero").is_none());
+}
+
+#[test]
+fn unknown_leaf_names_are_reported() {
+    let mut lib = BehaviorLibrary::new();
+    let err = lib.load_archetypes_json(r#"{ "orc": { "action": "smash" } }"#).unwrap_err();
+    assert!(err.contains("smash"), "{err}");
+}
@@ -0,0 +1,99 @@
+use jengine::dice::{Advantage, Dice, DiceGroup, Expr, Keep};
+use jengine::rng::Rng;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+#[test]
+fn parses_basic_notation() {
+    let d = Dice::parse("2d6+3").unwrap();
+    assert_eq!(
+        d.expr(),
+        &Expr::Add(
+            Box::new(Expr::Dice(DiceGroup { count: 2, sides: 6, keep: Keep::All, advantage: Advantage::None })),
+            Box::new(Expr::Num(3)),
+        )
+    );
+    assert_eq!((d.min(), d.max()), (5, 15));
+    assert!(close(d.average(), 10.0));
+    assert_eq!(d.to_string(), "2d6+3");
+}
+
+#[test]
+fn keep_drop_and_percentile() {
+    let d = Dice::parse("3d8kh2").unwrap();
+    assert_eq!((d.min(), d.max()), (2, 16));
+    assert_eq!(Dice::parse("4d6dl1").unwrap().expr(), Dice::parse("4d6kh3").unwrap().expr());
+    assert_eq!(Dice::parse("d%").unwrap().max(), 100);
+    // Highest of 2d6 has mean 161/36; lowest has 91/36.
+    assert!(close(Dice::parse("2d6kh1").unwrap().average(), 161.0 / 36.0));
+    assert!(close(Dice::parse("2d6kl1").unwrap().average(), 91.0 / 36.0));
+}
+
+#[test]
+fn advantage_matches_keep_highest() {
+    let adv = Dice::parse("1d20 adv").unwrap();
+    let dis = Dice::parse("1d20 DIS").unwrap();
+    assert!(close(adv.average(), Dice::parse("2d20kh1").unwrap().average()));
+    assert!(close(dis.average(), Dice::parse("2d20kl1").unwrap().average()));
+    assert!(close(adv.average(), 13.825));
+    assert_eq!((adv.min(), adv.max()), (1, 20));
+}
+
+#[test]
+fn arithmetic_precedence_and_bounds() {
+    let d = Dice::parse(" (1d4 + 1) * 2 - -1 ").unwrap();
+    assert_eq!((d.min(), d.max()), (5, 11));
+    assert!(close(d.average(), 8.0));
+    let neg = Dice::parse("1d6 - 1d6").unwrap();
+    assert_eq!((neg.min(), neg.max()), (-5, 5));
+    assert!(close(neg.average(), 0.0));
+}
+
+#[test]
+fn rolls_stay_in_bounds_and_are_deterministic() {
+    let d = Dice::parse("4d6dl1+2").unwrap();
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    for _ in 0..500 {
+        let r = d.roll(&mut a);
+        assert!((d.min()..=d.max()).contains(&r));
+        assert_eq!(r, d.roll(&mut b));
+    }
+}
+
+#[test]
+fn huge_expressions_saturate_instead_of_overflowing() {
+    let mut rng = Rng::new(1);
+    let d = Dice::parse("4000000000*4000000000*4000000000").unwrap();
+    assert_eq!(d.roll(&mut rng), i64::MAX);
+    assert_eq!((d.min(), d.max()), (i64::MAX, i64::MAX));
+
+    let d = Dice::parse("-(4000000000*4000000000*4000000000) - 1d6").unwrap();
+    assert_eq!(d.roll(&mut rng), i64::MIN);
+    assert_eq!((d.min(), d.max()), (i64::MIN, i64::MIN));
+}
+
+#[test]
+fn parse_errors_report_positions() {
+    let err = Dice::parse("2d6 + ").unwrap_err();
+    assert_eq!(err.pos, 6);
+    let err = Dice::parse("2d0").unwrap_err();
+    assert_eq!(err.pos, 0);
+    let err = Dice::parse("3d8kh4").unwrap_err();
+    assert_eq!(err.pos, 3);
+    let err = Dice::parse("2d6 x").unwrap_err();
+    assert_eq!(err.pos, 4);
+    assert!(err.to_string().contains("position 4"), "{err}");
+    assert!(Dice::parse("(1d6").is_err());
+    assert!(Dice::parse("2d6kh1 adv").is_err());
+}
+
+#[test]
+fn serde_uses_notation_strings() {
+    let d: Dice = serde_json::from_str("\"1d8+2\"").unwrap();
+    assert_eq!(d.max(), 10);
+    assert_eq!(serde_json::to_string(&d).unwrap(), "\"1d8+2\"");
+    assert!(serde_json::from_str::<Dice>("\"1d\"").is_err());
+}
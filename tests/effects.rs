@@ -0,0 +1,105 @@
+use jengine::ecs::World;
+use jengine::effects::*;
+
+#[derive(Debug, PartialEq)]
+struct Hp(i32);
+
+#[test]
+fn stats_combine_additive_then_multiplicative_modifiers() {
+    let mut stats = Stats::new().with("speed", 10.0);
+    assert_eq!(stats.value("speed"), 10.0);
+    stats.add_modifier("boots", Modifier::add("speed", 2.0));
+    stats.add_modifier("haste", Modifier::mul("speed", 1.5));
+    assert_eq!(stats.value("speed"), 18.0);
+    stats.remove_source("haste");
+    assert_eq!(stats.value("speed"), 12.0);
+    stats.set_base("speed", 20.0);
+    assert_eq!(stats.value("speed"), 22.0);
+    assert_eq!(stats.value("unknown"), 0.0);
+}
+
+#[test]
+fn turn_effect_ticks_and_expires() {
+    let mut world = World::new();
+    let e = world.spawn();
+    world.insert(e, Hp(10));
+    let poison = StatusEffect::new("poison", Duration::Turns(3))
+        .on_tick(|w, e, stacks| w.get_mut::<Hp>(e).unwrap().0 -= stacks as i32)
+        .on_expire(|w, e, _| w.get_mut::<Hp>(e).unwrap().0 += 100);
+    apply_effect(&mut world, e, poison);
+    assert!(has_effect(&world, e, "poison"));
+
+    end_turn(&mut world);
+    end_turn(&mut world);
+    assert_eq!(world.get::<Hp>(e), Some(&Hp(8)));
+    end_turn(&mut world);
+    assert!(!has_effect(&world, e, "poison"));
+    assert_eq!(world.get::<Hp>(e), Some(&Hp(107)));
+
+    // Timed effects ignore turns.
+    apply_effect(&mut world, e, StatusEffect::new("haste", Duration::Seconds(1.0)));
+    end_turn(&mut world);
+    assert!(has_effect(&world, e, "haste"));
+}
+
+#[test]
+fn timed_effect_ticks_at_interval_and_removes_modifiers_on_expiry() {
+    let mut world = World::new();
+    let e = world.spawn();
+    world.insert(e, Hp(0));
+    world.insert(e, Stats::new().with("speed", 10.0));
+    let haste = StatusEffect::new("haste", Duration::Seconds(1.0))
+        .with_modifier(Modifier::mul("speed", 2.0))
+        .with_tick_interval(0.25)
+        .on_tick(|w, e, _| w.get_mut::<Hp>(e).unwrap().0 += 1);
+    apply_effect(&mut world, e, haste);
+    assert_eq!(world.get::<Stats>(e).unwrap().value("speed"), 20.0);
+
+    for _ in 0..5 {
+        update_effects(&mut world, 0.125);
+    }
+    assert_eq!(world.get::<Hp>(e), Some(&Hp(2)));
+    assert!(has_effect(&world, e, "haste"));
+
+    for _ in 0..3 {
+        update_effects(&mut world, 0.125);
+    }
+    assert!(!has_effect(&world, e, "haste"));
+    assert_eq!(world.get::<Hp>(e), Some(&Hp(4)));
+    assert_eq!(world.get::<Stats>(e).unwrap().value("speed"), 10.0);
+}
+
+#[test]
+fn stacking_rules() {
+    let mut world = World::new();
+    let e = world.spawn();
+    world.insert(e, Stats::new().with("armor", 5.0));
+
+    let sunder = || {
+        StatusEffect::new("sunder", Duration::Turns(2))
+            .with_stacking(Stacking::Stack, 3)
+            .with_modifier(Modifier::add("armor", -1.0))
+    };
+    for _ in 0..5 {
+        apply_effect(&mut world, e, sunder());
+    }
+    let fx = world.get::<StatusEffects>(e).unwrap();
+    assert_eq!(fx.stacks("sunder"), 3);
+    assert_eq!(world.get::<Stats>(e).unwrap().value("armor"), 2.0);
+
+    let shield = || StatusEffect::new("shield", Duration::Turns(2)).with_stacking(Stacking::Extend, 1);
+    apply_effect(&mut world, e, shield());
+    apply_effect(&mut world, e, shield());
+    let fx = world.get::<StatusEffects>(e).unwrap();
+    assert_eq!(fx.get("shield").unwrap().duration, Duration::Turns(4));
+
+    let stun = |t| StatusEffect::new("stun", Duration::Turns(t)).with_stacking(Stacking::Ignore, 1);
+    apply_effect(&mut world, e, stun(1));
+    apply_effect(&mut world, e, stun(9));
+    let fx = world.get::<StatusEffects>(e).unwrap();
+    assert_eq!(fx.get("stun").unwrap().duration, Duration::Turns(1));
+
+    assert!(remove_effect(&mut world, e, "sunder"));
+    assert!(!remove_effect(&mut world, e, "sunder"));
+    assert_eq!(world.get::<Stats>(e).unwrap().value("armor"), 5.0);
+}
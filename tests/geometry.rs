@@ -115,3 +115,242 @@ fn test_direction_toward_diagonal() {
 fn test_direction_toward_same_point() {
     assert_eq!(direction_toward(2, 2, 2, 2), (0, 0));
 }
+
+// ── Area-of-effect shapes ────────────────────────────────────────────────────
+
+#[test]
+fn test_rect_filled_and_hollow() {
+    let filled: Vec<_> = rect_filled(1, 1, 3, 2).collect();
+    assert_eq!(filled, vec![(1, 1), (2, 1), (3, 1), (1, 2), (2, 2), (3, 2)]);
+
+    let hollow: Vec<_> = rect_hollow(0, 0, 3, 3).collect();
+    assert_eq!(hollow.len(), 8);
+    assert!(!hollow.contains(&(1, 1)));
+}
+
+#[test]
+fn test_diamond() {
+    let pts: Vec<_> = diamond(0, 0, 2).collect();
+    assert_eq!(pts.len(), 13);
+    assert!(pts.contains(&(2, 0)));
+    assert!(pts.contains(&(1, 1)));
+    assert!(!pts.contains(&(2, 1)));
+}
+
+#[test]
+fn test_ring_matches_circle_difference() {
+    let full: Vec<_> = ring(0, 0, 0, 3).collect();
+    let mut circle = points_in_radius(0, 0, 3);
+    let mut sorted = full.clone();
+    sorted.sort();
+    circle.sort();
+    assert_eq!(sorted, circle);
+
+    let inner: Vec<_> = ring(0, 0, 0, 1).collect();
+    let outer: Vec<_> = ring(0, 0, 2, 3).collect();
+    assert!(inner.iter().all(|p| !outer.contains(p)));
+    assert_eq!(inner.len() + outer.len(), full.len());
+}
+
+#[test]
+fn test_cone_points_toward_target() {
+    let pts: Vec<_> = cone(0, 0, 5, 0, 90.0, 4).collect();
+    assert!(pts.contains(&(4, 0)));
+    assert!(pts.contains(&(2, 2)));   // exactly on the 45° edge
+    assert!(!pts.contains(&(1, 2)));  // outside the cone
+    assert!(!pts.contains(&(0, 0)));  // origin excluded
+    assert!(pts.iter().all(|&(x, _)| x > 0));
+    assert_eq!(cone(3, 3, 3, 3, 90.0, 4).count(), 0);
+}
+
+#[test]
+fn test_beam_width() {
+    let thin: Vec<_> = beam(0, 0, 4, 0, 1).collect();
+    assert_eq!(thin, vec![(1, 0), (2, 0), (3, 0), (4, 0)]);
+
+    let wide: Vec<_> = beam(0, 0, 4, 0, 3).collect();
+    assert!(wide.contains(&(2, 1)));
+    assert!(wide.contains(&(2, -1)));
+    assert!(!wide.contains(&(2, 2)));
+}
+
+#[test]
+fn test_blast_clipped_by_walls() {
+    // Wall column at x = 2; tiles behind it are shielded.
+    let pts: Vec<_> = blast(0, 0, 4, |x, _| x == 2).collect();
+    assert!(pts.contains(&(0, 0)));
+    assert!(pts.contains(&(2, 0)));   // the wall itself is hit
+    assert!(!pts.contains(&(3, 0)));
+    assert!(pts.contains(&(-3, 0)));
+}
+
+// ── Alternative line algorithms ───────────────────────────────────────────────
+
+#[test]
+fn test_line_symmetric_matches_in_both_directions() {
+    for x in -4..=4 {
+        for y in -4..=4 {
+            let mut back = line_symmetric(x, y, 0, 0);
+            back.reverse();
+            assert_eq!(line_symmetric(0, 0, x, y), back);
+            let blocked = |bx: i32, by: i32| (bx, by) == (1, 1) || (bx, by) == (-2, 1);
+            assert_eq!(
+                line_of_sight_with(0, 0, x, y, LineAlgorithm::Symmetric, blocked),
+                line_of_sight_with(x, y, 0, 0, LineAlgorithm::Symmetric, blocked),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_permissive_lines_are_valid_and_symmetric() {
+    let lines = permissive_lines(0, 0, 4, 2);
+    assert!(lines.len() > 1);
+    for points in &lines {
+        assert_eq!(points.len(), 5);
+        assert_eq!(points.first(), Some(&(0, 0)));
+        assert_eq!(points.last(), Some(&(4, 2)));
+        assert!(points.windows(2).all(|w| distance_chebyshev(w[0].0, w[0].1, w[1].0, w[1].1) == 1));
+    }
+    let mut back: Vec<Vec<(i32, i32)>> = permissive_lines(4, 2, 0, 0)
+        .into_iter()
+        .map(|mut p| { p.reverse(); p })
+        .collect();
+    let mut forward = lines.clone();
+    back.sort();
+    forward.sort();
+    assert_eq!(forward, back);
+    assert_eq!(permissive_lines(3, 3, 3, 3), vec![vec![(3, 3)]]);
+}
+
+#[test]
+fn test_permissive_sees_past_a_wall_bresenham_does_not() {
+    // Bresenham from (0,0) to (4,1) goes through (2,0) or (2,1); block the one it uses.
+    let used = line(0, 0, 4, 1)[2];
+    let blocked = |x: i32, y: i32| (x, y) == used;
+    assert!(!line_of_sight(0, 0, 4, 1, blocked));
+    assert!(!line_of_sight_with(0, 0, 4, 1, LineAlgorithm::Bresenham, blocked));
+    assert!(line_of_sight_with(0, 0, 4, 1, LineAlgorithm::Permissive, blocked));
+    assert!(line_of_sight_with(4, 1, 0, 0, LineAlgorithm::Permissive, blocked));
+    // A full wall still blocks every line.
+    assert!(!line_of_sight_with(0, 0, 4, 1, LineAlgorithm::Permissive, |x, _| x == 2));
+}
+
+#[test]
+fn test_line_dda_supercover() {
+    assert_eq!(line_dda(0, 0, 3, 0), vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+    // Exact corners are crossed diagonally.
+    assert_eq!(line_dda(0, 0, 2, 2), vec![(0, 0), (1, 1), (2, 2)]);
+    assert_eq!(line_dda(0, 0, 3, 1), vec![(0, 0), (1, 0), (2, 1), (3, 1)]);
+    // Otherwise every touched tile is included.
+    assert_eq!(line_dda(0, 0, 2, 1), vec![(0, 0), (1, 0), (1, 1), (2, 1)]);
+    let mut back = line_dda(2, 1, 0, 0);
+    back.reverse();
+    assert_eq!(back, line_dda(0, 0, 2, 1));
+}
+
+#[test]
+fn test_ray_cells_and_walk_line_with() {
+    assert_eq!(ray_cells(0.2, 0.2, 2.7, 0.9), vec![(0, 0), (1, 0), (2, 0)]);
+    assert_eq!(ray_cells(0.5, 0.5, 0.9, 0.1), vec![(0, 0)]);
+    assert_eq!(ray_cells(1.5, 0.5, -0.5, 0.5), vec![(1, 0), (0, 0), (-1, 0)]);
+
+    for algorithm in [LineAlgorithm::Bresenham, LineAlgorithm::Symmetric, LineAlgorithm::Permissive, LineAlgorithm::Dda] {
+        let mut visited = Vec::new();
+        assert!(walk_line_with(0, 0, 3, 0, algorithm, |x, y| { visited.push((x, y)); true }));
+        assert_eq!(visited, vec![(0, 0), (1, 0), (2, 0), (3, 0)], "{algorithm:?}");
+        let mut count = 0;
+        assert!(!walk_line_with(0, 0, 5, 0, algorithm, |_, _| { count += 1; count < 3 }));
+    }
+}
+
+// ── Grid rectangles ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_grid_rect_basics() {
+    let room = GridRect::from_corners(6, 4, 2, 1);
+    assert_eq!(room, GridRect::new(2, 1, 5, 4));
+    assert_eq!((room.right(), room.bottom()), (7, 5));
+    assert_eq!(room.area(), 20);
+    assert_eq!(room.center(), (4, 3));
+    assert!(room.contains(2, 1) && room.contains(6, 4));
+    assert!(!room.contains(7, 4) && !room.contains(2, 5));
+    assert!(room.contains_rect(&GridRect::new(3, 2, 2, 2)));
+    assert!(!room.contains_rect(&GridRect::new(3, 2, 5, 2)));
+
+    let other = GridRect::new(5, 3, 4, 4);
+    assert_eq!(room.intersection(&other), Some(GridRect::new(5, 3, 2, 2)));
+    assert!(room.intersects(&other));
+    assert_eq!(room.union(&other), GridRect::new(2, 1, 7, 6));
+    // Touching edges don't overlap.
+    assert!(!room.intersects(&GridRect::new(7, 1, 3, 3)));
+    assert_eq!(room.union(&GridRect::default()), room);
+
+    assert_eq!(room.inflate(1, 2), GridRect::new(1, -1, 7, 8));
+    assert!(room.inflate(-3, 0).is_empty());
+    assert_eq!(room.translate(-2, 1), GridRect::new(0, 2, 5, 4));
+}
+
+#[test]
+fn test_grid_rect_iterators() {
+    let room = GridRect::new(0, 0, 4, 3);
+    assert_eq!(room.points().count(), 12);
+    assert_eq!(room.edge().count(), 10);
+    assert_eq!(room.interior().collect::<Vec<_>>(), vec![(1, 1), (2, 1)]);
+    assert_eq!(GridRect::new(0, 0, 2, 2).interior().count(), 0);
+}
+
+#[test]
+fn test_grid_rect_split() {
+    let rect = GridRect::new(0, 0, 10, 4);
+    assert_eq!(rect.split_x(3), Some((GridRect::new(0, 0, 3, 4), GridRect::new(3, 0, 7, 4))));
+    assert_eq!(rect.split_y(1), Some((GridRect::new(0, 0, 10, 1), GridRect::new(0, 1, 10, 3))));
+    assert_eq!(rect.split_x(0), None);
+    assert_eq!(rect.split_y(4), None);
+
+    let mut rng = jengine::rng::Rng::new(7);
+    let mut leaves = vec![GridRect::new(0, 0, 60, 40)];
+    while let Some(i) = leaves.iter().position(|r| r.area() > 150) {
+        let Some((a, b)) = leaves[i].split_random(&mut rng, 5) else { break };
+        leaves.splice(i..=i, [a, b]);
+    }
+    assert!(leaves.len() > 8);
+    assert_eq!(leaves.iter().map(|r| r.area()).sum::<i32>(), 2400);
+    for (i, a) in leaves.iter().enumerate() {
+        assert!(a.w >= 5 && a.h >= 5, "{a:?}");
+        assert!(leaves[i + 1..].iter().all(|b| !a.intersects(b)));
+    }
+    assert_eq!(GridRect::new(0, 0, 9, 9).split_random(&mut rng, 5), None);
+}
+
+// ── Grid transforms ───────────────────────────────────────────────────────────
+
+#[test]
+fn test_grid_transform_coordinates() {
+    // 3×2 grid; top-left corner under each transform.
+    let corner: Vec<(i32, i32)> = GridTransform::ALL.iter().map(|t| t.apply(0, 0, 3, 2)).collect();
+    assert_eq!(corner, vec![(0, 0), (1, 0), (2, 1), (0, 2), (2, 0), (0, 1), (0, 0), (1, 2)]);
+    assert_eq!(GridTransform::Rotate90.transformed_size(3, 2), (2, 3));
+    assert_eq!(GridTransform::Rotate90.apply_offset(1, 0), (0, 1), "east turns south");
+
+    for t in GridTransform::ALL {
+        let (w, h) = t.transformed_size(3, 2);
+        for (x, y) in rect_filled(0, 0, 3, 2) {
+            let (tx, ty) = t.apply(x, y, 3, 2);
+            assert!(tx >= 0 && ty >= 0 && tx < w && ty < h);
+            assert_eq!(t.inverse().apply(tx, ty, w, h), (x, y), "{t:?}");
+        }
+    }
+}
+
+#[test]
+fn test_grid_transform_whole_grid() {
+    let grid: Vec<char> = "ab\ncd\nef".lines().flat_map(|l| l.chars()).collect();
+    let (rotated, w, h) = GridTransform::Rotate90.apply_grid(&grid, 2, 3);
+    assert_eq!((w, h), (3, 2));
+    assert_eq!(rotated.iter().collect::<String>(), "ecafdb");
+    let (flipped, _, _) = GridTransform::FlipX.apply_grid(&grid, 2, 3);
+    assert_eq!(flipped.iter().collect::<String>(), "badcfe");
+    let (back, w, h) = GridTransform::Rotate270.apply_grid(&rotated, 3, 2);
+    assert_eq!((back, w, h), (grid, 2, 3));
+}
@@ -0,0 +1,89 @@
+use jengine::hex::*;
+
+#[test]
+fn test_cube_round_trip() {
+    let h = Hex::new(2, -5);
+    let c = h.to_cube();
+    assert_eq!(c.q + c.r + c.s, 0);
+    assert_eq!(Hex::from_cube(c), h);
+}
+
+#[test]
+fn test_neighbors_are_distance_one() {
+    let center = Hex::new(3, -1);
+    for n in center.neighbors() {
+        assert_eq!(center.distance(n), 1);
+    }
+    assert_eq!(center.neighbor(6), center.neighbor(0));
+}
+
+#[test]
+fn test_distance() {
+    assert_eq!(Hex::new(0, 0).distance(Hex::new(3, -3)), 3);
+    assert_eq!(Hex::new(0, 0).distance(Hex::new(2, 1)), 3);
+    assert_eq!(Hex::new(-1, 2).distance(Hex::new(-1, 2)), 0);
+}
+
+#[test]
+fn test_line_is_contiguous() {
+    let a = Hex::new(0, 0);
+    let b = Hex::new(4, -1);
+    let line = hex_line(a, b);
+    assert_eq!(line.len(), a.distance(b) as usize + 1);
+    assert_eq!(line.first(), Some(&a));
+    assert_eq!(line.last(), Some(&b));
+    for w in line.windows(2) {
+        assert_eq!(w[0].distance(w[1]), 1);
+    }
+}
+
+#[test]
+fn test_ring_and_spiral_sizes() {
+    let c = Hex::new(1, 1);
+    assert_eq!(hex_ring(c, 0), vec![c]);
+    let ring = hex_ring(c, 2);
+    assert_eq!(ring.len(), 12);
+    assert!(ring.iter().all(|h| h.distance(c) == 2));
+
+    let spiral = hex_spiral(c, 2);
+    assert_eq!(spiral.len(), 1 + 6 + 12);
+    assert_eq!(spiral[0], c);
+}
+
+#[test]
+fn test_offset_round_trip() {
+    for layout in [OffsetLayout::OddR, OffsetLayout::EvenR, OffsetLayout::OddQ, OffsetLayout::EvenQ] {
+        for row in -3..4 {
+            for col in -3..4 {
+                let h = offset_to_hex(col, row, layout);
+                assert_eq!(hex_to_offset(h, layout), (col, row), "{layout:?} ({col}, {row})");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_offset_odd_r_known_values() {
+    // Row 1 is shoved right in odd-r, so (0, 1) is axial (0, 1) shifted left.
+    assert_eq!(offset_to_hex(0, 1, OffsetLayout::OddR), Hex::new(0, 1));
+    assert_eq!(offset_to_hex(1, 2, OffsetLayout::OddR), Hex::new(0, 2));
+}
+
+#[test]
+fn test_pixel_round_trip() {
+    for orientation in [HexOrientation::PointyTop, HexOrientation::FlatTop] {
+        let layout = HexLayout::new(orientation, [10.0, 10.0], [100.0, 50.0]);
+        for h in hex_spiral(Hex::new(0, 0), 3) {
+            let [x, y] = layout.hex_to_pixel(h);
+            assert_eq!(layout.pixel_to_hex(x, y), h);
+            // A point slightly off-centre still lands in the same hex.
+            assert_eq!(layout.pixel_to_hex(x + 2.0, y - 2.0), h);
+        }
+    }
+}
+
+#[test]
+fn test_origin_hex_at_layout_origin() {
+    let layout = HexLayout::new(HexOrientation::PointyTop, [8.0, 8.0], [16.0, 24.0]);
+    assert_eq!(layout.hex_to_pixel(Hex::new(0, 0)), [16.0, 24.0]);
+}
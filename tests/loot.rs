@@ -0,0 +1,84 @@
+use jengine::dice::Dice;
+use jengine::loot::{LootDrop, LootEntry, LootTable, TableSet, pick_weighted};
+use jengine::rng::Rng;
+
+const TABLES: &str = r#"{
+    "monsters": {
+        "rolls": "3",
+        "unique": true,
+        "guaranteed": [ { "prefab": "rat" } ],
+        "entries": [
+            { "prefab": "goblin", "weight": 10, "max_depth": 5 },
+            { "prefab": "orc", "curve": [[1, 0], [3, 5], [10, 20]] },
+            { "table": "rare", "weight": 1, "min_depth": 4 }
+        ]
+    },
+    "rare": { "entries": [ { "prefab": "troll" } ] }
+}"#;
+
+#[test]
+fn depth_range_and_curve_weights() {
+    let e = LootEntry::prefab("orc", 1.0).with_curve(vec![(1, 0.0), (3, 5.0), (10, 20.0)]);
+    assert_eq!(e.weight_at(0), 0.0);
+    assert_eq!(e.weight_at(2), 2.5);
+    assert_eq!(e.weight_at(3), 5.0);
+    assert_eq!(e.weight_at(50), 20.0);
+
+    let g = LootEntry::prefab("goblin", 4.0).with_depth(Some(2), Some(5));
+    assert_eq!(g.weight_at(1), 0.0);
+    assert_eq!(g.weight_at(2), 4.0);
+    assert_eq!(g.weight_at(6), 0.0);
+}
+
+#[test]
+fn json_tables_roll_unique_picks_and_nested_tables() {
+    let set = TableSet::from_json(TABLES).unwrap();
+    let mut rng = Rng::new(3);
+
+    // Depth 1: only the goblin is eligible, so unique picks stop after one.
+    assert_eq!(set.roll("monsters", 1, &mut rng), vec!["rat", "goblin"]);
+
+    // Depth 8: goblin is out of range; orc and the nested table remain.
+    let mut got = set.roll("monsters", 8, &mut rng);
+    got.sort();
+    assert_eq!(got, vec!["orc", "rat", "troll"]);
+}
+
+#[test]
+fn rolls_are_deterministic_for_a_seed() {
+    let set = TableSet::from_json(TABLES).unwrap();
+    let a: Vec<_> = (0..20).map(|d| set.roll("monsters", d, &mut Rng::new(d as u64))).collect();
+    let b: Vec<_> = (0..20).map(|d| set.roll("monsters", d, &mut Rng::new(d as u64))).collect();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn weighted_pick_follows_weights() {
+    let mut rng = Rng::new(42);
+    let mut counts = [0; 3];
+    for _ in 0..10_000 {
+        counts[pick_weighted(&[1.0, 0.0, 3.0], &mut rng).unwrap()] += 1;
+    }
+    assert_eq!(counts[1], 0);
+    assert!((2300..2700).contains(&counts[0]), "{counts:?}");
+    assert_eq!(pick_weighted(&[0.0, 0.0], &mut rng), None);
+}
+
+#[test]
+fn builder_tables_and_validation() {
+    let mut set = TableSet::new();
+    set.insert(
+        "chest",
+        LootTable::new()
+            .with_rolls(Dice::parse("2").unwrap())
+            .with_guaranteed(LootDrop::Prefab("gold".into()))
+            .with_entry(LootEntry::prefab("potion", 1.0)),
+    );
+    assert_eq!(set.roll("chest", 1, &mut Rng::new(0)), vec!["gold", "potion", "potion"]);
+    assert!(set.roll("missing", 1, &mut Rng::new(0)).is_empty());
+
+    set.insert("a", LootTable::new().with_entry(LootEntry::table("b", 1.0)));
+    assert!(set.validate().unwrap_err().contains("unknown loot table 'b'"));
+    set.insert("b", LootTable::new().with_guaranteed(LootDrop::Table("a".into())));
+    assert!(set.validate().unwrap_err().contains("cycle"));
+}
@@ -1,3 +1,4 @@
+use jengine::hex::Hex;
 use jengine::pathfinding::prelude::*;
 
 // ── A* ────────────────────────────────────────────────────────────────────────
@@ -147,3 +148,1053 @@ fn dijkstra_multiple_goals() {
     // Cell (5,0) is 5 from left, 4 from right.
     assert_eq!(map.get(5, 0), 4.0);
 }
+
+// ── Hex A* ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn astar_hex_open_grid_is_shortest() {
+    let start = Hex::new(0, 0);
+    let goal = Hex::new(4, -2);
+    let path = astar_hex(start, goal, |h| h.distance(start) <= 10, 1000).unwrap();
+    assert_eq!(path.first(), Some(&start));
+    assert_eq!(path.last(), Some(&goal));
+    assert_eq!(path.len(), start.distance(goal) as usize + 1);
+    for w in path.windows(2) {
+        assert_eq!(w[0].distance(w[1]), 1);
+    }
+}
+
+#[test]
+fn astar_hex_routes_around_wall() {
+    let start = Hex::new(0, 0);
+    let goal = Hex::new(3, 0);
+    // Wall of hexes with q == 1, except far away.
+    let path = astar_hex(start, goal, |h| h.q != 1 || h.r < -3, 5000).unwrap();
+    assert!(path.iter().all(|h| h.q != 1 || h.r < -3));
+    assert!(path.len() > 4);
+}
+
+#[test]
+fn astar_hex_enclosed_returns_none() {
+    let start = Hex::new(0, 0);
+    let result = astar_hex(start, Hex::new(5, 0), |h| h.distance(start) < 2, 1000);
+    assert!(result.is_none());
+}
+
+// ── Weighted A* ───────────────────────────────────────────────────────────────
+
+#[test]
+fn heuristics_estimate_expected_distances() {
+    assert_eq!(Heuristic::Manhattan.estimate((0, 0), (3, 4), 1), 7);
+    assert_eq!(Heuristic::Chebyshev.estimate((0, 0), (3, 4), 1), 4);
+    assert_eq!(Heuristic::Octile.estimate((0, 0), (3, 4), 10), 52);
+    assert_eq!(Heuristic::IntegerOctile.estimate((0, 0), (3, 4), 10), 52);
+    assert_eq!(Heuristic::IntegerOctile.estimate((0, 0), (10, 10), 10), 140);
+    assert_eq!(Heuristic::Euclidean.estimate((0, 0), (3, 4), 1), 5);
+    assert_eq!(Heuristic::Zero.estimate((0, 0), (3, 4), 1), 0);
+}
+
+#[test]
+fn astar_weighted_prefers_cheap_road() {
+    // Row 0 is a road (cost 1); everything else is mud (cost 5).
+    let cost = |_: (i32, i32), to: (i32, i32)| Some(if to.1 == 0 { 1 } else { 5 });
+    let (path, total) = astar_weighted((0, 1), (6, 1), 7, 3, cost, &AStarOptions::default()).unwrap();
+    assert!(path.iter().any(|&(_, y)| y == 0));
+    assert_eq!(total, 1 + 6 + 5);
+}
+
+#[test]
+fn astar_weighted_diagonal_rules() {
+    // Walls at (1,0) and (0,1): the only way from (0,0) to (1,1) is the corner.
+    let cost = |_: (i32, i32), to: (i32, i32)| (to != (1, 0) && to != (0, 1)).then_some(1);
+    let opts = AStarOptions::new().with_heuristic(Heuristic::Chebyshev);
+    assert!(astar_weighted((0, 0), (1, 1), 3, 3, cost, &opts.with_diagonals(DiagonalRule::Always)).is_some());
+    assert!(astar_weighted((0, 0), (1, 1), 3, 3, cost, &opts.with_diagonals(DiagonalRule::NoCornerCutting)).is_none());
+    assert!(astar_weighted((0, 0), (1, 1), 3, 3, cost, &opts.with_diagonals(DiagonalRule::Never)).is_none());
+
+    let (path, total) = astar_weighted(
+        (0, 0), (4, 4), 5, 5,
+        |f: (i32, i32), t: (i32, i32)| Some(if f.0 != t.0 && f.1 != t.1 { 14 } else { 10 }),
+        &AStarOptions::new().with_heuristic(Heuristic::Octile).with_min_cost(10).with_diagonals(DiagonalRule::Always),
+    ).unwrap();
+    assert_eq!(path.len(), 5);
+    assert_eq!(total, 56);
+}
+
+#[test]
+fn astar_weighted_goal_enterable_is_optional() {
+    let cost = |_: (i32, i32), to: (i32, i32)| (to != (3, 0)).then_some(1);
+    let opts = AStarOptions::default();
+    assert!(astar_weighted((0, 0), (3, 0), 5, 5, cost, &opts).is_some());
+    assert!(astar_weighted((0, 0), (3, 0), 5, 5, cost, &opts.with_goal_always_enterable(false)).is_none());
+}
+
+// ── Jump Point Search ─────────────────────────────────────────────────────────
+
+fn path_cost(path: &[(i32, i32)]) -> i32 {
+    path.windows(2)
+        .map(|w| if w[0].0 != w[1].0 && w[0].1 != w[1].1 { 14 } else { 10 })
+        .sum()
+}
+
+#[test]
+fn jps_open_grid_is_straight() {
+    let path = jps((0, 0), (9, 9), 10, 10, |_, _| true, 1000).unwrap();
+    assert_eq!(path.len(), 10);
+    assert_eq!(path_cost(&path), 126);
+    assert_eq!(jps((3, 3), (3, 3), 10, 10, |_, _| true, 10), Some(vec![(3, 3)]));
+}
+
+#[test]
+fn jps_enterable_goal_and_enclosed() {
+    // Goal is "impassable" (an enemy) but still enterable, like astar_8dir.
+    assert!(jps((0, 0), (5, 0), 10, 10, |x, y| (x, y) != (5, 0), 1000).is_some());
+    // Start enclosed by a ring of walls.
+    let walls = |x: i32, y: i32| (x - 5).abs().max((y - 5).abs()) != 2;
+    assert!(jps((5, 5), (0, 0), 10, 10, walls, 10_000).is_none());
+}
+
+#[test]
+fn jps_matches_astar_8dir_on_random_maps() {
+    use jengine::rng::Rng;
+    let (w, h) = (40, 30);
+    let mut rng = Rng::new(1234);
+    for _ in 0..200 {
+        let walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.3)).collect();
+        let passable = |x: i32, y: i32| !walls[(y * w + x) as usize];
+        let start = (rng.range(0, w), rng.range(0, h));
+        let goal = (rng.range(0, w), rng.range(0, h));
+        if !passable(start.0, start.1) {
+            continue;
+        }
+
+        let a = astar_8dir(start, goal, w, h, passable, 100_000);
+        let j = jps(start, goal, w, h, passable, 100_000);
+        assert_eq!(a.is_some(), j.is_some(), "{start:?} -> {goal:?}");
+        let (Some(a), Some(j)) = (a, j) else { continue };
+
+        assert_eq!(path_cost(&a), path_cost(&j), "{start:?} -> {goal:?}");
+        assert_eq!((j[0], *j.last().unwrap()), (start, goal));
+        for step in j.windows(2) {
+            assert!((step[0].0 - step[1].0).abs() <= 1 && (step[0].1 - step[1].1).abs() <= 1);
+            assert!(step[1] == goal || passable(step[1].0, step[1].1));
+        }
+    }
+}
+
+#[test]
+fn jps_expands_fewer_nodes_than_astar() {
+    let (_, a) = astar_8dir_with_stats((0, 0), (99, 60), 100, 100, |_, _| true, 1_000_000);
+    let (_, j) = jps_with_stats((0, 0), (99, 60), 100, 100, |_, _| true, 1_000_000);
+    assert!(j.expanded * 10 < a.expanded, "jps {} vs a* {}", j.expanded, a.expanded);
+}
+
+// ── Pathfinder context ────────────────────────────────────────────────────────
+
+#[test]
+fn pathfinder_matches_free_functions_across_queries() {
+    use jengine::rng::Rng;
+    let (w, h) = (30, 20);
+    let mut rng = Rng::new(99);
+    let mut pf = Pathfinder::new(w, h);
+    for _ in 0..100 {
+        let walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.25)).collect();
+        let passable = |x: i32, y: i32| !walls[(y * w + x) as usize];
+        let start = (rng.range(0, w), rng.range(0, h));
+        let goal = (rng.range(0, w), rng.range(0, h));
+
+        assert_eq!(pf.astar(start, goal, passable, 2000).map(<[_]>::to_vec), astar(start, goal, w, h, passable, 2000));
+        assert_eq!(pf.astar_8dir(start, goal, passable, 2000).map(<[_]>::to_vec), astar_8dir(start, goal, w, h, passable, 2000));
+        assert_eq!(pf.next_step(start, goal, passable, 2000), astar_next_step(start, goal, w, h, passable, 2000));
+    }
+}
+
+#[test]
+fn pathfinder_resize_and_bounds() {
+    let mut pf = Pathfinder::new(5, 5);
+    assert!(pf.astar((0, 0), (9, 9), |_, _| true, 100).is_none());
+    pf.resize(10, 10);
+    assert_eq!(pf.astar((0, 0), (9, 9), |_, _| true, 1000).unwrap().len(), 19);
+    assert_eq!(pf.next_step_8dir((0, 0), (9, 9), |_, _| true, 1000), Some((1, 1)));
+    assert_eq!(pf.next_step((4, 4), (4, 4), |_, _| true, 10), None);
+    pf.resize(3, 3);
+    assert_eq!(pf.astar((0, 0), (2, 2), |_, _| true, 100).unwrap().len(), 5);
+}
+
+// ── Weighted Dijkstra maps ────────────────────────────────────────────────────
+
+#[test]
+fn weighted_dijkstra_uses_tile_costs_and_diagonals() {
+    // Column x == 2 is swamp (cost 5).
+    let cost = |x: i32, _: i32| Some(if x == 2 { 5.0 } else { 1.0 });
+    let four = DijkstraMap::weighted(5, 5, &[((0, 0), 0.0)], cost, None);
+    assert_eq!(four.get(1, 0), 1.0);
+    assert_eq!(four.get(2, 0), 6.0);
+    assert_eq!(four.get(4, 4), 12.0);
+
+    let eight = DijkstraMap::weighted(5, 5, &[((0, 0), 0.0)], |_, _| Some(1.0), Some(1.0));
+    assert_eq!(eight.get(4, 4), 4.0);
+    assert_eq!(eight.direction_to_goal(4, 4), (-1, -1));
+
+    let octile = DijkstraMap::weighted(5, 5, &[((0, 0), 0.0)], |_, _| Some(1.0), Some(1.5));
+    assert_eq!(octile.get(2, 1), 2.5);
+}
+
+#[test]
+fn weighted_dijkstra_goal_values_and_rescan() {
+    // Two goals; the right one starts at -3 so it dominates most of the row.
+    let mut map = DijkstraMap::weighted(10, 1, &[((0, 0), 0.0), ((9, 0), -3.0)], |_, _| Some(1.0), None);
+    assert_eq!(map.get(3, 0), 3.0);
+    assert_eq!(map.get(4, 0), 2.0);
+
+    // Wall off tile 5 and rebuild the left half from the left goal only.
+    map.set_cost(5, 0, None);
+    for x in 1..10 {
+        map.set(x, 0, f32::MAX);
+    }
+    map.set(9, 0, -3.0);
+    map.rescan();
+    assert_eq!(map.get(4, 0), 4.0);
+    assert_eq!(map.get(5, 0), f32::MAX);
+    assert_eq!(map.get(6, 0), 0.0);
+}
+
+#[test]
+fn flee_map_escapes_past_threat_instead_of_hugging_corner() {
+    // A 3-high corridor with the threat (impassable) near the left dead end.
+    // An entity between the wall and the threat should slip past it towards
+    // the open far end rather than cower in the corner.
+    let (w, h, threat_pos) = (40, 3, (3, 1));
+    let cost = |x: i32, y: i32| ((x, y) != threat_pos).then_some(1.0);
+    let threat = DijkstraMap::weighted(w, h, &[(threat_pos, 0.0)], cost, Some(1.0));
+
+    let walk = |step: &dyn Fn((i32, i32)) -> (i32, i32)| {
+        let mut pos = (1, 1);
+        for _ in 0..100 {
+            let (dx, dy) = step(pos);
+            if (dx, dy) == (0, 0) { break; }
+            pos = (pos.0 + dx, pos.1 + dy);
+        }
+        pos
+    };
+
+    let naive = walk(&|(x, y)| threat.direction_away(x, y));
+    assert_eq!(naive.0, 0);
+
+    let flee = threat.flee_map(1.2);
+    let escaped = walk(&|(x, y)| flee.direction_to_goal(x, y));
+    assert_eq!(escaped.0, w - 1);
+}
+
+// ── HPA* ──────────────────────────────────────────────────────────────────────
+
+fn assert_valid_path(path: &[(i32, i32)], start: (i32, i32), goal: (i32, i32), passable: impl Fn(i32, i32) -> bool) {
+    assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+    for w in path.windows(2) {
+        let (dx, dy) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+        assert!(dx.abs() <= 1 && dy.abs() <= 1 && (dx, dy) != (0, 0), "{w:?}");
+        assert!(passable(w[1].0, w[1].1));
+        if dx != 0 && dy != 0 {
+            assert!(passable(w[0].0 + dx, w[0].1) && passable(w[0].0, w[0].1 + dy), "corner cut {w:?}");
+        }
+    }
+}
+
+#[test]
+fn hpa_matches_flat_search_reachability_and_is_near_optimal() {
+    use jengine::rng::Rng;
+    let (w, h) = (48, 40);
+    let mut rng = Rng::new(77);
+    let opts = AStarOptions::new()
+        .with_heuristic(Heuristic::Octile)
+        .with_diagonals(DiagonalRule::NoCornerCutting)
+        .with_min_cost(10)
+        .with_goal_always_enterable(false)
+        .with_max_iterations(1_000_000);
+    let (mut hpa_total, mut best_total) = (0, 0);
+    for _ in 0..20 {
+        let walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.3)).collect();
+        let passable = |x: i32, y: i32| !walls[(y * w + x) as usize];
+        let cost = |f: (i32, i32), t: (i32, i32)| {
+            passable(t.0, t.1).then_some(if f.0 != t.0 && f.1 != t.1 { 14 } else { 10 })
+        };
+        let mut hpa = HpaMap::new(w, h, 8, passable);
+        for _ in 0..20 {
+            let start = (rng.range(0, w), rng.range(0, h));
+            let goal = (rng.range(0, w), rng.range(0, h));
+            if !passable(start.0, start.1) || !passable(goal.0, goal.1) {
+                continue;
+            }
+            let flat = astar_weighted(start, goal, w, h, cost, &opts);
+            let hier = hpa.find_path(start, goal);
+            assert_eq!(flat.is_some(), hier.is_some(), "{start:?} -> {goal:?}");
+            let (Some((_, best)), Some(path)) = (flat, hier) else { continue };
+            assert_valid_path(&path, start, goal, passable);
+            let total: u32 = path.windows(2).map(|s| if s[0].0 != s[1].0 && s[0].1 != s[1].1 { 14 } else { 10 }).sum();
+            assert!(total >= best);
+            hpa_total += total;
+            best_total += best;
+        }
+    }
+    assert!(hpa_total * 100 <= best_total * 115, "hpa {hpa_total} vs optimal {best_total}");
+}
+
+#[test]
+fn hpa_incremental_update_matches_rebuild() {
+    let (w, h) = (40, 40);
+    let mut walls = vec![false; (w * h) as usize];
+    let mut hpa = HpaMap::new(w, h, 10, |_, _| true);
+    assert_eq!(hpa.cluster_count(), 16);
+
+    // Wall across the middle with a single gap at x = 35.
+    for x in 0..w {
+        if x != 35 {
+            walls[(20 * w + x) as usize] = true;
+            hpa.set_passable(x, 20, false);
+        }
+    }
+    let passable = |x: i32, y: i32| !walls[(y * w + x) as usize];
+    let path = hpa.find_path((2, 2), (2, 38)).unwrap();
+    assert_valid_path(&path, (2, 2), (2, 38), passable);
+    assert!(path.contains(&(35, 20)));
+
+    let mut fresh = HpaMap::new(w, h, 10, passable);
+    assert_eq!(fresh.node_count(), hpa.node_count());
+    assert_eq!(fresh.find_path((2, 2), (2, 38)).unwrap().len(), path.len());
+
+    // Seal the gap: no route remains.
+    hpa.set_passable(35, 20, false);
+    assert!(hpa.find_path((2, 2), (2, 38)).is_none());
+}
+
+#[test]
+fn hpa_lazy_refinement_yields_chunks() {
+    let mut hpa = HpaMap::new(64, 64, 16, |_, _| true);
+    let mut abs = hpa.find_abstract_path((0, 0), (63, 63)).unwrap();
+    assert!(abs.waypoints().len() > 2);
+    let mut tiles = vec![(0, 0)];
+    let mut chunks = 0;
+    while !abs.is_done() {
+        tiles.extend(abs.refine_next(&hpa).unwrap());
+        chunks += 1;
+    }
+    assert!(chunks > 1);
+    assert_eq!(abs.refine_next(&hpa), None);
+    assert_valid_path(&tiles, (0, 0), (63, 63), |_, _| true);
+    assert_eq!(tiles, hpa.find_path((0, 0), (63, 63)).unwrap());
+
+    // Same-cluster queries are answered directly.
+    assert_eq!(hpa.find_abstract_path((1, 1), (5, 9)).unwrap().waypoints(), &[(1, 1), (5, 9)]);
+    assert_eq!(hpa.find_path((3, 3), (3, 3)), Some(vec![(3, 3)]));
+}
+
+#[test]
+fn hpa_single_row_or_column_of_clusters() {
+    // One cluster per row: stacked clusters have consecutive indices.
+    let mut narrow = HpaMap::new(8, 40, 8, |_, _| true);
+    assert!(narrow.node_count() > 0);
+    let path = narrow.find_path((1, 1), (6, 38)).unwrap();
+    assert_valid_path(&path, (1, 1), (6, 38), |_, _| true);
+
+    let mut short = HpaMap::new(40, 8, 8, |_, _| true);
+    assert!(short.node_count() > 0);
+    let path = short.find_path((1, 1), (38, 6)).unwrap();
+    assert_valid_path(&path, (1, 1), (38, 6), |_, _| true);
+}
+
+// ── Flow fields ───────────────────────────────────────────────────────────────
+
+#[test]
+fn flow_field_distances_match_weighted_astar() {
+    use jengine::rng::Rng;
+    let (w, h) = (24, 18);
+    let mut rng = Rng::new(9);
+    let tiles: Vec<Option<u32>> = (0..w * h)
+        .map(|_| if rng.chance(0.2) { None } else { Some(rng.range(1, 4) as u32) })
+        .collect();
+    let tile = |x: i32, y: i32| tiles[(y * w + x) as usize];
+    let goal = (12, 9);
+    let mut field = FlowField::new(w, h, tile, DiagonalRule::NoCornerCutting);
+    field.build(&[goal]);
+
+    let cost = |f: (i32, i32), t: (i32, i32)| {
+        tile(t.0, t.1).map(|c| c * if f.0 != t.0 && f.1 != t.1 { 14 } else { 10 })
+    };
+    let opts = AStarOptions::new()
+        .with_heuristic(Heuristic::Zero)
+        .with_diagonals(DiagonalRule::NoCornerCutting)
+        .with_max_iterations(1_000_000);
+    for y in 0..h {
+        for x in 0..w {
+            if tile(x, y).is_none() {
+                continue;
+            }
+            let expected = astar_weighted((x, y), goal, w, h, cost, &opts).map(|(_, c)| c);
+            assert_eq!(field.distance(x, y), expected, "({x}, {y})");
+        }
+    }
+}
+
+#[test]
+fn flow_field_agents_follow_directions_to_nearest_goal() {
+    let walls = [(5, 1), (5, 2), (5, 3), (5, 4), (5, 5)];
+    let passable = |x: i32, y: i32| !walls.contains(&(x, y));
+    let mut field = FlowField::new(12, 8, |x, y| passable(x, y).then_some(1), DiagonalRule::Always);
+    field.build(&[(0, 0), (11, 7)]);
+
+    assert_eq!(field.goals(), &[(0, 0), (11, 7)]);
+    assert_eq!(field.direction(0, 0), (0, 0));
+    assert_eq!(field.next_step(1, 1), Some((0, 0)));
+    assert_eq!(field.direction(10, 6), (1, 1));
+
+    for start in [(4, 3), (6, 3), (3, 7), (11, 0)] {
+        let mut pos = start;
+        let mut steps = 0;
+        while let Some(next) = field.next_step(pos.0, pos.1) {
+            assert!(passable(next.0, next.1));
+            assert!(field.distance(next.0, next.1) < field.distance(pos.0, pos.1));
+            pos = next;
+            steps += 1;
+        }
+        assert!(pos == (0, 0) || pos == (11, 7), "{start:?} stopped at {pos:?}");
+        assert!(steps > 0);
+    }
+}
+
+#[test]
+fn flow_field_retarget_matches_full_build() {
+    use jengine::rng::Rng;
+    let (w, h) = (30, 30);
+    let mut rng = Rng::new(4);
+    let tiles: Vec<Option<u32>> = (0..w * h)
+        .map(|_| if rng.chance(0.25) { None } else { Some(rng.range(1, 3) as u32) })
+        .collect();
+    let tile = |x: i32, y: i32| tiles[(y * w + x) as usize];
+
+    for diagonals in [DiagonalRule::Never, DiagonalRule::NoCornerCutting, DiagonalRule::Always] {
+        let mut field = FlowField::new(w, h, tile, diagonals);
+        let mut goal = (15, 15);
+        field.build(&[goal]);
+        for _ in 0..40 {
+            goal = ((goal.0 + rng.range(-2, 3)).clamp(0, w - 1), (goal.1 + rng.range(-2, 3)).clamp(0, h - 1));
+            field.retarget(&[goal]);
+            let mut fresh = FlowField::new(w, h, tile, diagonals);
+            fresh.build(&[goal]);
+            for y in 0..h {
+                for x in 0..w {
+                    assert_eq!(field.distance(x, y), fresh.distance(x, y), "{diagonals:?} ({x}, {y})");
+                    assert_eq!(field.direction(x, y), fresh.direction(x, y), "{diagonals:?} ({x}, {y})");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn flow_field_cost_changes_take_effect_on_rebuild() {
+    let mut field = FlowField::new(6, 3, |_, _| Some(1), DiagonalRule::Never);
+    field.build(&[(5, 1)]);
+    assert_eq!(field.distance(0, 1), Some(50));
+    for y in 0..3 {
+        field.set_cost(3, y, None);
+    }
+    field.retarget(&[(5, 1)]);
+    assert_eq!(field.distance(0, 1), None);
+    assert_eq!(field.next_step(0, 1), None);
+    field.set_cost(3, 1, Some(5));
+    field.retarget(&[(5, 1)]);
+    assert_eq!(field.distance(0, 1), Some(90));
+}
+
+// ── Cooperative A* ────────────────────────────────────────────────────────────
+
+fn assert_no_conflicts(plans: &[Option<Vec<(i32, i32)>>]) {
+    let plans: Vec<&Vec<(i32, i32)>> = plans.iter().flatten().collect();
+    for (i, a) in plans.iter().enumerate() {
+        for w in a.windows(2) {
+            let (dx, dy) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+            assert!(dx.abs() <= 1 && dy.abs() <= 1, "agent {i} jumped {w:?}");
+        }
+        for b in &plans[i + 1..] {
+            for t in 0..a.len() {
+                assert_ne!(a[t], b[t], "vertex conflict at t={t}");
+                if t > 0 {
+                    assert!(!(a[t] == b[t - 1] && a[t - 1] == b[t]), "swap conflict at t={t}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cooperative_agents_pass_in_corridor_using_pocket() {
+    // A 1-wide corridor along y = 1 with a single side pocket at (1, 0).  The
+    // priority agent walks straight through; the other ducks into the pocket.
+    let passable = |x: i32, y: i32| y == 1 || (x, y) == (1, 0);
+    let agents = [((7, 1), (0, 1)), ((0, 1), (7, 1))];
+    let plans = CooperativePlanner::new(8, 3).with_window(20).plan(&agents, passable);
+    assert_no_conflicts(&plans);
+    for (plan, &(_, goal)) in plans.iter().zip(&agents) {
+        let plan = plan.as_ref().unwrap();
+        assert_eq!(plan.len(), 21);
+        assert_eq!(*plan.last().unwrap(), goal);
+        assert!(plan.iter().all(|&(x, y)| passable(x, y)));
+    }
+    assert_eq!(plans[0].as_ref().unwrap()[7], (0, 1));
+    assert!(plans[1].as_ref().unwrap().contains(&(1, 0)));
+}
+
+#[test]
+fn cooperative_agent_waits_for_higher_priority_agent() {
+    // Two corridors crossing at (3, 3); both agents reach it at t = 3.
+    let passable = |x: i32, y: i32| x == 3 || y == 3;
+    let agents = [((0, 3), (6, 3)), ((3, 0), (3, 6))];
+    let plans = CooperativePlanner::new(7, 7).with_window(10).plan(&agents, passable);
+    assert_no_conflicts(&plans);
+    let first = plans[0].as_ref().unwrap();
+    let second = plans[1].as_ref().unwrap();
+    assert_eq!(first[6], (6, 3), "priority agent goes straight through");
+    assert!(second.windows(2).any(|w| w[0] == w[1] && w[0] != (3, 6)), "second agent waits");
+    assert_eq!(*second.last().unwrap(), (3, 6));
+}
+
+#[test]
+fn cooperative_follower_moves_into_vacated_start() {
+    // Leader directly ahead of the follower in a 1-wide corridor.
+    let passable = |_: i32, y: i32| y == 0;
+    let agents = [((1, 0), (6, 0)), ((0, 0), (5, 0))];
+    let plans = CooperativePlanner::new(8, 1).with_window(8).plan(&agents, passable);
+    assert_no_conflicts(&plans);
+    let leader = plans[0].as_ref().unwrap();
+    let follower = plans[1].as_ref().unwrap();
+    assert_eq!(leader[1], (2, 0));
+    assert_eq!(follower[1], (1, 0), "follower steps into the leader's start on t = 1");
+    assert_eq!(follower[5], (5, 0));
+}
+
+#[test]
+fn cooperative_zero_window_plans_one_turn() {
+    let passable = |_: i32, y: i32| y == 0;
+    let agents = [((1, 0), (6, 0)), ((0, 0), (5, 0))];
+    let planner = CooperativePlanner { window: 0, ..CooperativePlanner::new(8, 1) };
+    let plans = planner.plan(&agents, passable);
+    assert_no_conflicts(&plans);
+    assert_eq!(plans[0], Some(vec![(1, 0), (2, 0)]));
+    assert_eq!(plans[1], Some(vec![(0, 0), (1, 0)]));
+}
+
+#[test]
+fn cooperative_planning_is_deterministic_and_collision_free() {
+    use jengine::rng::Rng;
+    let (w, h) = (16, 16);
+    let mut rng = Rng::new(12);
+    let walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.15)).collect();
+    let passable = |x: i32, y: i32| !walls[(y * w + x) as usize];
+    let mut free: Vec<(i32, i32)> = (0..h).flat_map(|y| (0..w).map(move |x| (x, y))).filter(|&(x, y)| passable(x, y)).collect();
+    for i in (1..free.len()).rev() {
+        free.swap(i, rng.range(0, i as i32 + 1) as usize);
+    }
+    let agents: Vec<_> = (0..12).map(|i| (free[i], free[i + 12])).collect();
+
+    for diagonals in [DiagonalRule::Never, DiagonalRule::NoCornerCutting] {
+        let planner = CooperativePlanner::new(w, h).with_window(24).with_diagonals(diagonals);
+        let plans = planner.plan(&agents, passable);
+        assert_no_conflicts(&plans);
+        assert_eq!(plans, planner.plan(&agents, passable));
+        assert!(plans.iter().filter(|p| p.is_some()).count() >= 10);
+    }
+}
+
+#[test]
+fn cooperative_respects_external_reservations_and_unreachable_goals() {
+    let passable = |x: i32, _: i32| x != 4;
+    let planner = CooperativePlanner::new(8, 3).with_window(8);
+    let mut table = ReservationTable::new();
+    // The player (id 99) stands on (2, 1) for the whole window.
+    for t in 0..=8 {
+        table.reserve((2, 1), t, 99);
+    }
+    let agents = [((0, 1), (3, 1)), ((0, 0), (6, 0))];
+    let plans = planner.plan_with(&agents, passable, &mut table);
+    let plan = plans[0].as_ref().unwrap();
+    assert!(!plan.contains(&(2, 1)));
+    assert_eq!(*plan.last().unwrap(), (3, 1));
+    assert_eq!(plans[1], None);
+    assert_eq!(table.owner((0, 0), 5), Some(1), "unplanned agent holds its tile");
+}
+
+// ── D* Lite ───────────────────────────────────────────────────────────────────
+
+fn fresh_cost(
+    start: (i32, i32),
+    goal: (i32, i32),
+    (w, h): (i32, i32),
+    walls: &[bool],
+    diagonals: DiagonalRule,
+) -> Option<u32> {
+    let cost = |f: (i32, i32), t: (i32, i32)| {
+        (!walls[(t.1 * w + t.0) as usize]).then_some(if f.0 != t.0 && f.1 != t.1 { 14 } else { 10 })
+    };
+    let opts = AStarOptions::new()
+        .with_heuristic(Heuristic::Octile)
+        .with_min_cost(10)
+        .with_diagonals(diagonals)
+        .with_max_iterations(1_000_000);
+    astar_weighted(start, goal, w, h, cost, &opts).map(|(_, c)| c)
+}
+
+#[test]
+fn dstar_lite_matches_fresh_astar_after_random_edits() {
+    use jengine::rng::Rng;
+    let (w, h) = (32, 24);
+    let mut rng = Rng::new(41);
+    for diagonals in [DiagonalRule::Never, DiagonalRule::NoCornerCutting, DiagonalRule::Always] {
+        let mut walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.25)).collect();
+        let (mut start, goal) = ((0, 0), (w - 1, h - 1));
+        walls[0] = false;
+        let mut planner = DStarLite::new(w, h, start, goal, |x, y| !walls[(y * w + x) as usize], diagonals);
+        for _ in 0..60 {
+            let changes: Vec<((i32, i32), bool)> = (0..rng.range(1, 8))
+                .map(|_| {
+                    let pos = (rng.range(0, w), rng.range(0, h));
+                    (pos, pos == start || rng.chance(0.6))
+                })
+                .collect();
+            for &((x, y), passable) in &changes {
+                walls[(y * w + x) as usize] = !passable;
+            }
+            planner.update_cells(&changes);
+
+            let expected = fresh_cost(start, goal, (w, h), &walls, diagonals);
+            let found = planner.find_path();
+            assert_eq!(found.as_ref().map(|&(_, c)| c), expected, "{diagonals:?} from {start:?}");
+            if let Some((path, _)) = found {
+                let passable = |x: i32, y: i32| !walls[(y * w + x) as usize] || (x, y) == goal;
+                assert_eq!((path[0], *path.last().unwrap()), (start, goal));
+                assert!(path[1..].iter().all(|&(x, y)| passable(x, y)));
+                // Walk a step now and then so the start moves too.
+                if path.len() > 1 && rng.chance(0.5) {
+                    start = path[1];
+                    planner.set_start(start);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn dstar_lite_replans_with_less_work_than_fresh_search() {
+    use jengine::rng::Rng;
+    let (w, h) = (60, 60);
+    let mut rng = Rng::new(3);
+    let mut walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.2)).collect();
+    let (start, goal) = ((0, 0), (59, 59));
+    walls[0] = false;
+    walls[(59 * w + 59) as usize] = false;
+    let mut planner = DStarLite::new(w, h, start, goal, |x, y| !walls[(y * w + x) as usize], DiagonalRule::NoCornerCutting);
+    let (path, _) = planner.find_path().unwrap();
+
+    // The agent takes two steps, then finds the tile ahead walled off.
+    planner.set_start(path[2]);
+    let blocked = path[4];
+    walls[(blocked.1 * w + blocked.0) as usize] = true;
+    planner.update_cells(&[(blocked, false)]);
+    let (repaired, cost) = planner.find_path().unwrap();
+    assert!(!repaired.contains(&blocked));
+    assert_eq!(Some(cost), fresh_cost(path[2], goal, (w, h), &walls, DiagonalRule::NoCornerCutting));
+
+    let mut fresh = DStarLite::new(w, h, path[2], goal, |x, y| !walls[(y * w + x) as usize], DiagonalRule::NoCornerCutting);
+    assert_eq!(fresh.find_path().unwrap().1, cost);
+    let (incremental, full) = (planner.last_stats().expanded, fresh.last_stats().expanded);
+    assert!(incremental * 2 < full, "{incremental} vs {full}");
+
+    // Unchanged cells are ignored and cost no work.
+    planner.update_cells(&[(blocked, false)]);
+    assert_eq!(planner.find_path().unwrap().1, cost);
+    assert_eq!(planner.last_stats().expanded, 0);
+}
+
+#[test]
+fn dstar_lite_handles_doors_and_goal_changes() {
+    // A wall down x = 4 with a door at (4, 2).
+    let mut planner = DStarLite::new(9, 5, (0, 2), (8, 2), |x, y| x != 4 || y == 2, DiagonalRule::Never);
+    assert_eq!(planner.next_step(), Some((1, 2)));
+    planner.update_cells(&[((4, 2), false)]);
+    assert_eq!(planner.find_path(), None);
+    assert_eq!(planner.next_step(), None);
+    planner.update_cells(&[((4, 0), true)]);
+    let (path, cost) = planner.find_path().unwrap();
+    assert!(path.contains(&(4, 0)));
+    assert_eq!(cost, 120);
+
+    planner.set_goal((2, 2));
+    assert_eq!(planner.find_path().unwrap().1, 20);
+    planner.set_start((2, 2));
+    assert_eq!(planner.find_path(), Some((vec![(2, 2)], 0)));
+    assert_eq!(planner.next_step(), None);
+}
+
+// ── Multi-tile agents ─────────────────────────────────────────────────────────
+
+#[test]
+fn clearance_map_annotates_squares_and_rectangles() {
+    // 6×4 room with a wall tile at (3, 1).
+    let map = ClearanceMap::new(6, 4, |x, y| (x, y) != (3, 1));
+    assert_eq!(map.clearance(0, 0), 3);
+    assert_eq!(map.clearance(2, 0), 1);
+    assert_eq!(map.clearance(4, 0), 2);
+    assert_eq!(map.clearance(3, 1), 0);
+    assert_eq!(map.clearance(5, 3), 1);
+    assert_eq!(map.clearance(-1, 0), 0);
+
+    assert!(map.fits(0, 0, 3, 3));
+    assert!(!map.fits(1, 0, 3, 3));
+    assert!(map.fits(0, 2, 6, 2), "wide body below the wall");
+    assert!(!map.fits(0, 1, 6, 2));
+    assert!(map.fits(4, 0, 2, 4), "tall body right of the wall");
+    assert!(!map.fits(4, 1, 2, 4), "would stick out of the map");
+    assert!(!map.fits(0, 0, 0, 1));
+}
+
+#[test]
+fn clearance_set_passable_matches_rebuild() {
+    use jengine::rng::Rng;
+    let (w, h) = (20, 16);
+    let mut rng = Rng::new(5);
+    let mut walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.2)).collect();
+    let mut map = ClearanceMap::new(w, h, |x, y| !walls[(y * w + x) as usize]);
+    for _ in 0..200 {
+        let (x, y) = (rng.range(0, w), rng.range(0, h));
+        let wall = rng.chance(0.4);
+        walls[(y * w + x) as usize] = wall;
+        map.set_passable(x, y, !wall);
+    }
+    let fresh = ClearanceMap::new(w, h, |x, y| !walls[(y * w + x) as usize]);
+    for y in 0..h {
+        for x in 0..w {
+            assert_eq!(map.clearance(x, y), fresh.clearance(x, y), "({x}, {y})");
+            assert_eq!(map.fits(x, y, 3, 1), fresh.fits(x, y, 3, 1));
+            assert_eq!(map.fits(x, y, 1, 2), fresh.fits(x, y, 1, 2));
+        }
+    }
+}
+
+#[test]
+fn sized_astar_keeps_big_bodies_out_of_narrow_gaps() {
+    // Wall down x = 8 with a 2-tile gap at y = 2..4 and a 3-tile gap at y = 10..13.
+    let passable = |x: i32, y: i32| x != 8 || (2..4).contains(&y) || (10..13).contains(&y);
+    let map = ClearanceMap::new(16, 16, passable);
+    let (start, goal) = ((2, 2), (12, 2));
+
+    let small = astar_sized(start, goal, &map, (1, 1), 10_000).unwrap();
+    assert_eq!(small, astar(start, goal, 16, 16, passable, 10_000).unwrap());
+    let two = astar_sized(start, goal, &map, (2, 2), 10_000).unwrap();
+    assert_eq!(two.len(), 11, "2×2 fits straight through the small gap");
+
+    for search in [astar_sized, astar_sized_8dir] {
+        let big = search(start, goal, &map, (3, 3), 10_000).unwrap();
+        assert_eq!((big[0], *big.last().unwrap()), (start, goal));
+        assert!(big.iter().all(|&(x, y)| map.fits(x, y, 3, 3)));
+        assert!(big.iter().any(|&(x, _)| x == 8), "crossed the wall");
+        assert!(big.iter().filter(|&&(x, _)| (6..=8).contains(&x)).all(|&(_, y)| y >= 10));
+        assert_eq!(search(start, goal, &map, (4, 4), 10_000), None);
+        assert!(search(start, goal, &map, (4, 3), 10_000).is_some(), "4 wide, 3 tall still fits");
+    }
+    assert_eq!(astar_sized((7, 0), goal, &map, (3, 3), 10_000), None, "start must fit");
+}
+
+#[test]
+fn sized_astar_8dir_costs_match_astar_8dir() {
+    let cost = |path: &[(i32, i32)]| -> u32 {
+        path.windows(2).map(|w| if w[0].0 != w[1].0 && w[0].1 != w[1].1 { 14 } else { 10 }).sum()
+    };
+    // Open map with long diagonals.
+    let open = |_: i32, _: i32| true;
+    let map = ClearanceMap::new(40, 40, open);
+    let pairs = [((0, 0), (39, 39)), ((2, 37), (37, 5)), ((0, 20), (39, 30))];
+    for (start, goal) in pairs {
+        let sized = astar_sized_8dir(start, goal, &map, (1, 1), 100_000).unwrap();
+        let flat = astar_8dir(start, goal, 40, 40, open, 100_000).unwrap();
+        assert_eq!(cost(&sized), cost(&flat), "{start:?} -> {goal:?}");
+    }
+
+    // Scattered walls, against Dijkstra under the same corner rule.  The
+    // first pair came out 6 too expensive with `Heuristic::Octile`.
+    use jengine::rng::Rng;
+    let mut rng = Rng::new(265);
+    let walls: Vec<bool> = (0..80 * 80).map(|_| rng.chance(0.05)).collect();
+    let passable = |x: i32, y: i32| !walls[(y * 80 + x) as usize];
+    let map = ClearanceMap::new(80, 80, passable);
+    let options = AStarOptions::new()
+        .with_heuristic(Heuristic::Zero)
+        .with_diagonals(DiagonalRule::NoCornerCutting)
+        .with_goal_always_enterable(false)
+        .with_max_iterations(100_000);
+    let step = |from: (i32, i32), to: (i32, i32)| {
+        passable(to.0, to.1).then_some(if from.0 != to.0 && from.1 != to.1 { 14 } else { 10 })
+    };
+    for (start, goal) in [((77, 16), (1, 72)), ((0, 0), (79, 79)), ((70, 3), (4, 60))] {
+        if !passable(start.0, start.1) || !passable(goal.0, goal.1) { continue; }
+        let sized = astar_sized_8dir(start, goal, &map, (1, 1), 100_000).unwrap();
+        let (_, best) = astar_weighted(start, goal, 80, 80, step, &options).unwrap();
+        assert_eq!(cost(&sized), best, "{start:?} -> {goal:?}");
+    }
+}
+
+#[test]
+fn sized_astar_8dir_does_not_clip_corners() {
+    // A 2×2 body moving diagonally past the corner of a wall block.
+    let passable = |x: i32, y: i32| !(x >= 3 && y <= 2);
+    let map = ClearanceMap::new(8, 8, passable);
+    let path = astar_sized_8dir((0, 0), (4, 5), &map, (2, 2), 10_000).unwrap();
+    for w in path.windows(2) {
+        let (dx, dy) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+        assert!(map.fits(w[1].0, w[1].1, 2, 2));
+        if dx != 0 && dy != 0 {
+            assert!(map.fits(w[0].0 + dx, w[0].1, 2, 2) && map.fits(w[0].0, w[0].1 + dy, 2, 2), "{w:?}");
+        }
+    }
+    // Cutting the corner from (1, 2) to (2, 3) would save a step.
+    assert_eq!(path.len(), 7);
+}
+
+// ── Connected regions ─────────────────────────────────────────────────────────
+
+#[test]
+fn regions_label_rooms_and_answer_reachability() {
+    // Two rooms split by a wall at x = 4; a single pillar at (1, 1).
+    let passable = |x: i32, y: i32| x != 4 && (x, y) != (1, 1);
+    let map = RegionMap::new(8, 3, passable, DiagonalRule::Never);
+    assert_eq!(map.region_count(), 2);
+    assert!(map.same_region((0, 0), (3, 2)));
+    assert!(!map.same_region((0, 0), (5, 0)));
+    assert!(!map.same_region((0, 0), (1, 1)), "walls belong to no region");
+    let left = map.region(0, 0).unwrap();
+    assert_eq!(map.region_size(left), 11);
+    assert_eq!(map.largest_region(), Some(left));
+    assert_eq!(map.region_size(map.region(5, 0).unwrap()), 9);
+    assert_eq!(map.tiles(left).len(), 11);
+
+    // Same conventions as astar: the goal and start may be impassable.
+    assert!(map.can_reach((0, 0), (1, 1)));
+    assert!(map.can_reach((1, 1), (3, 0)));
+    assert!(map.can_reach((3, 1), (4, 1)), "wall tile next to the left room");
+    assert!(!map.can_reach((0, 0), (6, 1)));
+    assert_eq!(map.can_reach((0, 0), (6, 1)), astar((0, 0), (6, 1), 8, 3, passable, 1000).is_some());
+}
+
+#[test]
+fn regions_connectivity_follows_diagonal_rule() {
+    // Two tiles touching only at a corner.
+    let passable = |x: i32, y: i32| (x, y) == (0, 0) || (x, y) == (1, 1);
+    assert!(!RegionMap::new(2, 2, passable, DiagonalRule::Never).same_region((0, 0), (1, 1)));
+    assert!(!RegionMap::new(2, 2, passable, DiagonalRule::NoCornerCutting).same_region((0, 0), (1, 1)));
+    assert!(RegionMap::new(2, 2, passable, DiagonalRule::Always).same_region((0, 0), (1, 1)));
+}
+
+#[test]
+fn regions_incremental_updates_match_rebuild() {
+    use jengine::rng::Rng;
+    let (w, h) = (24, 18);
+    let mut rng = Rng::new(43);
+    for diagonals in [DiagonalRule::Never, DiagonalRule::Always] {
+        let mut walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.4)).collect();
+        let mut map = RegionMap::new(w, h, |x, y| !walls[(y * w + x) as usize], diagonals);
+        for step in 0..400 {
+            let (x, y) = (rng.range(0, w), rng.range(0, h));
+            let wall = rng.chance(0.45);
+            walls[(y * w + x) as usize] = wall;
+            map.set_passable(x, y, !wall);
+            if step % 20 != 0 {
+                continue;
+            }
+            let fresh = RegionMap::new(w, h, |x, y| !walls[(y * w + x) as usize], diagonals);
+            assert_eq!(map.region_count(), fresh.region_count(), "{diagonals:?} step {step}");
+            for i in 0..w * h {
+                let a = (i % w, i / w);
+                // Labels may differ, but the partition must be the same.
+                let b = (rng.range(0, w), rng.range(0, h));
+                assert_eq!(map.same_region(a, b), fresh.same_region(a, b), "{diagonals:?} {a:?} {b:?}");
+                if let Some(r) = map.region(a.0, a.1) {
+                    assert_eq!(map.region_size(r), fresh.region_size(fresh.region(a.0, a.1).unwrap()));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn regions_find_unreachable_pockets() {
+    // A ring wall encloses a 2×2 pocket in the middle of an open 8×8 map.
+    let ring = |x: i32, y: i32| (2..=5).contains(&x) && (2..=5).contains(&y) && !((3..=4).contains(&x) && (3..=4).contains(&y));
+    let mut map = RegionMap::new(8, 8, |x, y| !ring(x, y), DiagonalRule::NoCornerCutting);
+    let main = map.largest_region().unwrap();
+    let pockets: Vec<(u32, usize)> = map.regions().filter(|&(l, _)| l != main).collect();
+    assert_eq!(pockets.len(), 1);
+    assert_eq!(pockets[0].1, 4);
+    for (x, y) in map.tiles(pockets[0].0) {
+        map.set_passable(x, y, false);
+    }
+    assert_eq!(map.region_count(), 1);
+    assert_eq!(map.region_size(main), 64 - 16);
+}
+
+// ── Time-sliced path requests ─────────────────────────────────────────────────
+
+#[test]
+fn path_queue_matches_astar_across_budgets() {
+    use jengine::rng::Rng;
+    let (w, h) = (40, 30);
+    let mut rng = Rng::new(44);
+    let walls: Vec<bool> = (0..w * h).map(|_| rng.chance(0.25)).collect();
+    let passable = |x: i32, y: i32| !walls[(y * w + x) as usize];
+    let pairs: Vec<((i32, i32), (i32, i32))> = (0..12)
+        .map(|_| ((rng.range(0, w), rng.range(0, h)), (rng.range(0, w), rng.range(0, h))))
+        .collect();
+
+    for (eight, budget) in [(false, 7), (false, 500), (true, 1), (true, 64)] {
+        let mut queue = PathQueue::new(w, h).with_diagonals(eight).with_budget(budget).with_max_iterations(2000);
+        let handles: Vec<PathHandle> = pairs.iter().map(|&(s, g)| queue.submit(s, g)).collect();
+        let mut ticks = 0;
+        while queue.pending_count() > 0 {
+            assert!(queue.update(passable) <= budget);
+            ticks += 1;
+        }
+        assert!(ticks > 1);
+        for (&(start, goal), &handle) in pairs.iter().zip(&handles) {
+            let expected = if eight {
+                astar_8dir(start, goal, w, h, passable, 2000)
+            } else {
+                astar(start, goal, w, h, passable, 2000)
+            };
+            let status = match expected {
+                Some(path) => PathStatus::Found(path),
+                None => PathStatus::Failed,
+            };
+            assert_eq!(queue.poll(handle), status, "{start:?} -> {goal:?}");
+        }
+    }
+}
+
+#[test]
+fn path_queue_breaks_ties_like_astar_on_open_maps() {
+    // Open maps have many equal-cost paths; the chosen one must not depend
+    // on neighbour order.
+    let (w, h) = (12, 12);
+    let open = |_: i32, _: i32| true;
+    let pairs = [((0, 0), (11, 11)), ((11, 0), (0, 11)), ((5, 6), (0, 0)), ((2, 9), (10, 3))];
+    for eight in [false, true] {
+        let mut queue = PathQueue::new(w, h).with_diagonals(eight).with_budget(1000);
+        let handles: Vec<PathHandle> = pairs.iter().map(|&(s, g)| queue.submit(s, g)).collect();
+        while queue.pending_count() > 0 {
+            queue.update(open);
+        }
+        for (&(start, goal), &handle) in pairs.iter().zip(&handles) {
+            let expected = if eight {
+                astar_8dir(start, goal, w, h, open, 1000)
+            } else {
+                astar(start, goal, w, h, open, 1000)
+            };
+            assert_eq!(queue.poll(handle), PathStatus::Found(expected.unwrap()), "{start:?} -> {goal:?}");
+        }
+    }
+}
+
+#[test]
+fn path_queue_spreads_long_search_over_ticks() {
+    let mut queue = PathQueue::new(100, 1).with_budget(10);
+    let handle = queue.submit((0, 0), (99, 0));
+    for _ in 0..9 {
+        assert_eq!(queue.update(|_, _| true), 10);
+        assert_eq!(queue.poll(handle), PathStatus::Pending);
+        assert!(queue.is_pending(handle));
+    }
+    // The 100th expansion pops the goal.
+    assert_eq!(queue.update(|_, _| true), 10);
+    let PathStatus::Found(path) = queue.poll(handle) else { panic!("expected a path") };
+    assert_eq!(path.len(), 100);
+    assert_eq!(queue.poll(handle), PathStatus::Failed, "results are reported once");
+    assert_eq!(queue.update(|_, _| true), 0);
+}
+
+#[test]
+fn path_queue_cancel_and_trivial_requests() {
+    let mut queue = PathQueue::new(50, 50).with_budget(5);
+    let first = queue.submit((0, 0), (49, 49));
+    let second = queue.submit((0, 0), (3, 0));
+    let here = queue.submit((2, 2), (2, 2));
+    let outside = queue.submit((0, 0), (50, 0));
+    assert_eq!(queue.pending_count(), 2);
+    assert_eq!(queue.poll(here), PathStatus::Found(vec![(2, 2)]));
+    assert_eq!(queue.poll(outside), PathStatus::Failed);
+
+    // Cancelling the long search hands the budget to the next one.
+    queue.update(|_, _| true);
+    assert!(queue.cancel(first));
+    assert!(!queue.cancel(first));
+    assert_eq!(queue.poll(first), PathStatus::Failed);
+    queue.update(|_, _| true);
+    assert_eq!(queue.poll(second), PathStatus::Found(vec![(0, 0), (1, 0), (2, 0), (3, 0)]));
+
+    // Walled-in goal: the search exhausts the open list and fails.
+    let blocked = queue.submit((0, 0), (10, 10));
+    while queue.is_pending(blocked) {
+        queue.update(|x, y| x < 5 && y < 5);
+    }
+    assert_eq!(queue.poll(blocked), PathStatus::Failed);
+}
+
+// ── Any-angle paths ───────────────────────────────────────────────────────────
+
+#[test]
+fn theta_star_open_grid_is_one_straight_segment() {
+    let path = theta_star((0, 0), (9, 4), 10, 10, |_, _| true, 1000).unwrap();
+    assert_eq!(path, vec![(0, 0), (9, 4)]);
+}
+
+#[test]
+fn theta_star_bends_around_a_wall() {
+    // Vertical wall at x = 5 with a gap at y = 9.
+    let passable = |x: i32, y: i32| x != 5 || y == 9;
+    let path = theta_star((0, 0), (9, 0), 10, 10, passable, 5000).unwrap();
+    assert_eq!(path.first(), Some(&(0, 0)));
+    assert_eq!(path.last(), Some(&(9, 0)));
+    assert!(path.len() < 6, "waypoints only at turns: {path:?}");
+    for pair in path.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert!(jengine::geometry::line_of_sight(a.0, a.1, b.0, b.1, |x, y| !passable(x, y)));
+    }
+    assert_eq!(theta_star((0, 0), (9, 0), 10, 10, |x, _| x != 5, 5000), None);
+}
+
+#[test]
+fn smooth_path_removes_staircase() {
+    let path = astar_8dir((0, 0), (6, 3), 10, 10, |_, _| true, 1000).unwrap();
+    assert_eq!(smooth_path(&path, |_, _| true), vec![(0, 0), (6, 3)]);
+
+    // An L-shaped corridor keeps its corner.
+    let passable = |x: i32, y: i32| y == 0 || x == 4;
+    let path = astar((0, 0), (4, 4), 5, 5, passable, 1000).unwrap();
+    assert_eq!(smooth_path(&path, passable), vec![(0, 0), (4, 0), (4, 4)]);
+    assert!(smooth_path(&[], passable).is_empty());
+}
+
+#[test]
+fn waypoint_follower_keeps_constant_speed_around_corners() {
+    let points = waypoints_to_world(&[(0, 0), (2, 0), (2, 1)], 16.0, 16.0);
+    assert_eq!(points[0], glam::Vec2::new(8.0, 8.0));
+    let mut follower = WaypointFollower::new(points);
+    assert_eq!(follower.remaining_distance(), 48.0);
+    assert_eq!(follower.direction(), glam::Vec2::X);
+
+    // 40 units: 32 to the corner, then 8 down.
+    assert_eq!(follower.update(20.0, 2.0), glam::Vec2::new(40.0, 16.0));
+    assert_eq!(follower.direction(), glam::Vec2::Y);
+    assert_eq!(follower.remaining_distance(), 8.0);
+    assert!(!follower.is_done());
+
+    assert_eq!(follower.advance(100.0), glam::Vec2::new(40.0, 24.0));
+    assert!(follower.is_done());
+    assert_eq!(follower.next_waypoint(), None);
+    assert_eq!(follower.remaining_distance(), 0.0);
+}
@@ -159,3 +159,193 @@ fn zero_physical_size_gives_zero_sized_viewport() {
     assert_eq!(v.width,  0.0);
     assert_eq!(v.height, 0.0);
 }
+
+// ── Headless Rendering Tests ──────────────────────────────────────────────
+
+use jengine::engine::{Color, Game, jEngine};
+
+/// Colored background tiles plus one screen-space UI rect.
+struct TestCard;
+
+impl Game for TestCard {
+    fn update(&mut self, _engine: &mut jEngine) {}
+    fn render(&mut self, engine: &mut jEngine) {
+        engine.clear();
+        engine.set_background(0, 0, Color::RED);
+        engine.set_background(1, 0, Color::GREEN);
+        engine.set_background(0, 1, Color::BLUE);
+        engine.ui.ui_rect(32.0, 32.0, 16.0, 16.0, Color::WHITE);
+    }
+}
+
+/// Headless engine, or None (test skipped) on machines without any adapter.
+fn headless_engine(width: u32, height: u32) -> Option<jEngine> {
+    let engine = jEngine::builder()
+        .with_size(width, height)
+        .with_tileset(jengine::DEFAULT_TILESET, 16, 16)
+        .build_headless();
+    if engine.is_none() {
+        eprintln!("no graphics adapter available; skipping headless test");
+    }
+    engine
+}
+
+/// Compare against `tests/golden/<name>.png`, allowing a small per-channel
+/// difference for rasteriser rounding.  Run with `JENGINE_BLESS=1` to
+/// (re)write the golden image.
+fn assert_matches_golden(name: &str, frame: &image::RgbaImage) {
+    let path = format!("{}/tests/golden/{name}.png", env!("CARGO_MANIFEST_DIR"));
+    if std::env::var_os("JENGINE_BLESS").is_some() {
+        std::fs::create_dir_all(std::path::Path::new(&path).parent().unwrap()).unwrap();
+        frame.save(&path).unwrap();
+        return;
+    }
+    let golden = image::open(&path)
+        .unwrap_or_else(|e| panic!("missing golden image {path} ({e}); run with JENGINE_BLESS=1"))
+        .to_rgba8();
+    assert_eq!(golden.dimensions(), frame.dimensions(), "{name}: size differs from golden");
+    let mismatched = golden.pixels().zip(frame.pixels())
+        .filter(|(a, b)| a.0.iter().zip(b.0.iter()).any(|(&x, &y)| x.abs_diff(y) > 2))
+        .count();
+    assert_eq!(mismatched, 0, "{name}: {mismatched} pixels differ from golden");
+}
+
+#[test]
+fn headless_frame_reads_back_tile_and_ui_colors() {
+    let Some(mut engine) = headless_engine(64, 48) else { return };
+    assert!(engine.renderer.is_headless());
+    assert!(engine.renderer.window().is_none());
+    assert_eq!((engine.grid_width(), engine.grid_height()), (4, 3));
+
+    let frame = engine.render_offscreen(&mut TestCard).expect("frame");
+    assert_eq!(frame.dimensions(), (64, 48));
+    assert_eq!(frame.get_pixel(8, 8).0, [255, 0, 0, 255]);
+    assert_eq!(frame.get_pixel(24, 8).0, [0, 255, 0, 255]);
+    assert_eq!(frame.get_pixel(8, 24).0, [0, 0, 255, 255]);
+    assert_eq!(frame.get_pixel(40, 40).0, [255, 255, 255, 255]);
+    assert_eq!(frame.get_pixel(60, 8).0, [0, 0, 0, 255]);
+}
+
+#[test]
+fn headless_frames_match_golden_image() {
+    let Some(mut engine) = headless_engine(64, 48) else { return };
+    let first = engine.render_offscreen(&mut TestCard).expect("frame");
+    let second = engine.render_offscreen(&mut TestCard).expect("frame");
+    assert_eq!(first, second, "rendering is deterministic");
+    assert_matches_golden("headless_test_card", &first);
+}
+
+#[test]
+fn headless_screenshot_matches_rendered_frame() {
+    let Some(mut engine) = headless_engine(64, 48) else { return };
+    let dir = std::env::temp_dir().join(format!("jengine_capture_{}", std::process::id()));
+    let path = dir.join("shot.png");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    engine.capture_screenshot(&path);
+    let frame = engine.render_offscreen(&mut TestCard).expect("frame");
+    engine.flush_captures();
+    let saved = image::open(&path).expect("screenshot written").to_rgba8();
+    assert_eq!(saved, frame);
+
+    // Only the requested frame is captured.
+    std::fs::remove_file(&path).unwrap();
+    engine.render_offscreen(&mut TestCard);
+    engine.flush_captures();
+    assert!(!path.exists());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn headless_frame_recording_dumps_numbered_pngs() {
+    let Some(mut engine) = headless_engine(64, 48) else { return };
+    let dir = std::env::temp_dir().join(format!("jengine_recording_{}", std::process::id()));
+
+    assert_eq!(engine.dump_frame_recording(&dir), 0, "nothing recorded yet");
+    // Leftovers from an earlier, longer dump are removed; other files stay,
+    // even ones that merely look similar.
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("frame_00007.png"), b"stale").unwrap();
+    std::fs::write(dir.join("notes.txt"), b"keep").unwrap();
+    std::fs::write(dir.join("frame_title.png"), b"keep").unwrap();
+    std::fs::write(dir.join("frame_7.png"), b"keep").unwrap();
+    // A high rate records every frame.
+    engine.start_frame_recording(10.0, 10_000.0);
+    for _ in 0..3 {
+        engine.render_offscreen(&mut TestCard);
+    }
+    engine.flush_captures();
+    assert_eq!(engine.renderer.capture.recorded_frames(), 3);
+
+    // Back-to-back dumps to one directory run in order instead of
+    // deleting each other's frames.
+    assert_eq!(engine.dump_frame_recording(&dir), 3);
+    assert_eq!(engine.dump_frame_recording(&dir), 3);
+    engine.flush_captures();
+    for i in 0..3 {
+        let frame = image::open(dir.join(format!("frame_{i:05}.png"))).expect("frame written").to_rgba8();
+        assert_eq!(frame.get_pixel(8, 8).0, [255, 0, 0, 255]);
+    }
+    assert!(!dir.join("frame_00003.png").exists());
+    assert!(!dir.join("frame_00007.png").exists());
+    for kept in ["notes.txt", "frame_title.png", "frame_7.png"] {
+        assert!(dir.join(kept).exists(), "{kept} was deleted");
+    }
+
+    engine.stop_frame_recording();
+    assert_eq!(engine.renderer.capture.recorded_frames(), 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// PNG of two 8×8 tiles side by side: 0 = solid blue, 1 = solid green.
+fn two_tile_png() -> Vec<u8> {
+    let img = image::RgbaImage::from_fn(16, 8, |x, _| {
+        if x < 8 { image::Rgba([0, 0, 255, 255]) } else { image::Rgba([0, 255, 0, 255]) }
+    });
+    let mut bytes = Vec::new();
+    img.write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png).unwrap();
+    bytes
+}
+
+/// Tiles from a second tileset mixed with default-tileset glyphs.
+struct MixedTilesets(jengine::renderer::atlas::TilesetId);
+
+impl Game for MixedTilesets {
+    fn update(&mut self, _engine: &mut jEngine) {}
+    fn render(&mut self, engine: &mut jEngine) {
+        engine.clear();
+        engine.set_foreground(3, 2, '@', Color::WHITE);
+        engine.set_foreground_from(0, 0, self.0, 1, Color::WHITE);
+        engine.set_foreground_from(1, 0, self.0, 0, Color::WHITE);
+        engine.set_foreground(2, 2, 'A', Color::WHITE);
+    }
+}
+
+#[test]
+fn headless_foreground_cells_select_their_tileset() {
+    let Some(mut engine) = headless_engine(64, 48) else { return };
+    use jengine::renderer::atlas::TilesetId;
+    assert_eq!(engine.tileset("default"), Some(TilesetId::DEFAULT));
+    assert_eq!(engine.tileset("terrain"), None);
+
+    let png = two_tile_png();
+    let terrain = engine.register_tileset("terrain", &png, 8, 8);
+    assert_ne!(terrain, TilesetId::DEFAULT);
+    assert_eq!(engine.tileset("terrain"), Some(terrain));
+    assert_eq!(engine.register_tileset("terrain", &png, 8, 8), terrain, "re-registering keeps the handle");
+    assert_eq!(engine.renderer.tileset_count(), 2);
+
+    let frame = engine.render_offscreen(&mut MixedTilesets(terrain)).expect("frame");
+    // Each 8×8 tile is stretched over a 16×16 cell.
+    assert_eq!(frame.get_pixel(8, 8).0, [0, 255, 0, 255]);
+    assert_eq!(frame.get_pixel(24, 8).0, [0, 0, 255, 255]);
+    assert_eq!(frame.get_pixel(40, 8).0, [0, 0, 0, 255]);
+
+    // Swapping the atlas behind a name redraws cells that use it.
+    let swapped = image::RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255]));
+    let mut png = Vec::new();
+    swapped.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png).unwrap();
+    engine.register_tileset("terrain", &png, 8, 8);
+    let frame = engine.render_offscreen(&mut MixedTilesets(terrain)).expect("frame");
+    assert_eq!(frame.get_pixel(24, 8).0, [255, 0, 0, 255]);
+}
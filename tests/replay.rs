@@ -0,0 +1,152 @@
+use jengine::input::replay::{InputFrame, Replay, ReplayPlayer, ReplayRecorder};
+use jengine::input::{InputState, KeyCode, MouseButton};
+use jengine::rng::Rng;
+
+// ── Rng ───────────────────────────────────────────────────────────────────────
+
+#[test]
+fn rng_same_seed_same_sequence() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    for _ in 0..100 {
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+}
+
+#[test]
+fn rng_reseed_restarts_sequence() {
+    let mut rng = Rng::new(7);
+    let first: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+    rng.reseed(7);
+    let again: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+    assert_eq!(first, again);
+    assert_eq!(rng.seed(), 7);
+}
+
+#[test]
+fn rng_range_stays_in_bounds() {
+    let mut rng = Rng::new(1);
+    for _ in 0..1000 {
+        let v = rng.range(-3, 4);
+        assert!((-3..4).contains(&v));
+        let w = rng.range_inclusive(1, 6);
+        assert!((1..=6).contains(&w));
+        let f = rng.next_f32();
+        assert!((0.0..1.0).contains(&f));
+    }
+    assert_eq!(rng.range(5, 5), 5);
+}
+
+// ── Recording / playback ─────────────────────────────────────────────────────
+
+/// Simulate the live event path for a handful of ticks and return the
+/// `InputState` snapshots each tick observed.
+fn simulate(recorder: &mut ReplayRecorder) -> Vec<InputState> {
+    let mut input = InputState::new();
+    let mut seen = Vec::new();
+
+    // Tick 0: nothing.
+    recorder.record(&input);
+    seen.push(clone_state(&input));
+
+    // Tick 1: press W, move the mouse, type a char.
+    input.keys_held.insert(KeyCode::KeyW);
+    input.keys_pressed.insert(KeyCode::KeyW);
+    input.mouse_pos = [10.0, 20.0];
+    input.chars_typed.push('w');
+    recorder.record(&input);
+    seen.push(clone_state(&input));
+    input.clear_frame_state();
+
+    // Tick 2: W still held, left click.
+    input.mouse_held.insert(MouseButton::Left);
+    input.mouse_pressed.insert(MouseButton::Left);
+    input.mouse_wheel = 1.0;
+    recorder.record(&input);
+    seen.push(clone_state(&input));
+    input.clear_frame_state();
+
+    // Tick 3: release everything.
+    input.keys_held.remove(&KeyCode::KeyW);
+    input.keys_released.insert(KeyCode::KeyW);
+    input.mouse_held.remove(&MouseButton::Left);
+    input.mouse_released.insert(MouseButton::Left);
+    recorder.record(&input);
+    seen.push(clone_state(&input));
+
+    seen
+}
+
+fn clone_state(s: &InputState) -> InputState {
+    InputState {
+        keys_held: s.keys_held.clone(),
+        keys_pressed: s.keys_pressed.clone(),
+        keys_released: s.keys_released.clone(),
+        mouse_pos: s.mouse_pos,
+        mouse_wheel: s.mouse_wheel,
+        mouse_held: s.mouse_held.clone(),
+        mouse_pressed: s.mouse_pressed.clone(),
+        mouse_released: s.mouse_released.clone(),
+        chars_typed: s.chars_typed.clone(),
+        key_consumed: false,
+        mouse_consumed: false,
+    }
+}
+
+fn assert_same(a: &InputState, b: &InputState) {
+    assert_eq!(a.keys_held, b.keys_held);
+    assert_eq!(a.keys_pressed, b.keys_pressed);
+    assert_eq!(a.keys_released, b.keys_released);
+    assert_eq!(a.mouse_held, b.mouse_held);
+    assert_eq!(a.mouse_pressed, b.mouse_pressed);
+    assert_eq!(a.mouse_released, b.mouse_released);
+    assert_eq!(a.mouse_pos, b.mouse_pos);
+    assert_eq!(a.mouse_wheel, b.mouse_wheel);
+    assert_eq!(a.chars_typed, b.chars_typed);
+}
+
+#[test]
+fn recorder_stores_one_frame_per_tick() {
+    let mut rec = ReplayRecorder::new(99, 60);
+    simulate(&mut rec);
+    assert_eq!(rec.len(), 4);
+    let replay = rec.finish();
+    assert_eq!(replay.seed, 99);
+    assert_eq!(replay.ups, 60);
+    assert_eq!(replay.frames[0], InputFrame::default());
+    assert_eq!(replay.frames[1].keys_down, vec![KeyCode::KeyW]);
+    // W is still held on tick 2 — no delta.
+    assert!(replay.frames[2].keys_down.is_empty());
+    assert_eq!(replay.frames[3].keys_up, vec![KeyCode::KeyW]);
+}
+
+#[test]
+fn playback_reproduces_recorded_states() {
+    let mut rec = ReplayRecorder::new(1, 60);
+    let seen = simulate(&mut rec);
+    let mut player = ReplayPlayer::new(rec.finish());
+
+    let mut input = InputState::new();
+    for expected in &seen {
+        assert!(player.apply_next(&mut input));
+        assert_same(&input, expected);
+        input.clear_frame_state();
+    }
+    assert!(player.is_finished());
+    assert!(!player.apply_next(&mut input));
+}
+
+#[test]
+fn replay_json_round_trip() {
+    let mut rec = ReplayRecorder::new(1234, 30);
+    simulate(&mut rec);
+    let replay = rec.finish();
+    let json = replay.to_json().unwrap();
+    let back = Replay::from_json(&json).unwrap();
+    assert_eq!(replay, back);
+}
+
+#[test]
+fn replay_invalid_json_is_error() {
+    assert!(Replay::from_json("{ not json").is_err());
+}
@@ -0,0 +1,104 @@
+use jengine::input::{InputState, KeyCode, MouseButton};
+use jengine::targeting::{TargetResult, Targeting};
+
+fn press(key: KeyCode) -> InputState {
+    let mut input = InputState::new();
+    input.keys_held.insert(key);
+    input.keys_pressed.insert(key);
+    input
+}
+
+#[test]
+fn cursor_starts_on_origin_and_moves_with_keys() {
+    let mut t = Targeting::new((5, 5), 20, 20);
+    assert_eq!(t.cursor, (5, 5));
+    assert_eq!(t.handle_input(&press(KeyCode::ArrowRight), None), TargetResult::Pending);
+    assert_eq!(t.handle_input(&press(KeyCode::Numpad3), None), TargetResult::Pending);
+    assert_eq!(t.cursor, (7, 6));
+}
+
+#[test]
+fn cursor_is_clamped_to_map() {
+    let mut t = Targeting::new((0, 0), 10, 10);
+    t.handle_input(&press(KeyCode::ArrowLeft), None);
+    t.handle_input(&press(KeyCode::ArrowUp), None);
+    assert_eq!(t.cursor, (0, 0));
+}
+
+#[test]
+fn confirm_returns_selected_tile() {
+    let mut t = Targeting::new((2, 2), 10, 10).with_cursor((6, 2));
+    assert_eq!(t.handle_input(&press(KeyCode::Enter), None), TargetResult::Selected((6, 2)));
+}
+
+#[test]
+fn confirm_on_origin_or_out_of_range_is_ignored() {
+    let mut t = Targeting::new((2, 2), 10, 10).with_range(3);
+    assert_eq!(t.handle_input(&press(KeyCode::Enter), None), TargetResult::Pending);
+    t.set_cursor((8, 2));
+    assert!(!t.in_range());
+    assert_eq!(t.handle_input(&press(KeyCode::Enter), None), TargetResult::Pending);
+}
+
+#[test]
+fn cancel_returns_cancelled() {
+    let mut t = Targeting::new((2, 2), 10, 10);
+    assert_eq!(t.handle_input(&press(KeyCode::Escape), None), TargetResult::Cancelled);
+}
+
+#[test]
+fn mouse_moves_cursor_only_when_pointer_moves() {
+    let mut t = Targeting::new((2, 2), 10, 10);
+    let mut input = InputState::new();
+    input.mouse_pos = [10.0, 10.0];
+    t.handle_input(&input, Some((4, 4)));
+    // First sample only establishes the pointer position.
+    assert_eq!(t.cursor, (2, 2));
+
+    input.mouse_pos = [50.0, 50.0];
+    t.handle_input(&input, Some((7, 3)));
+    assert_eq!(t.cursor, (7, 3));
+
+    // Keyboard movement is not overridden by a stationary pointer.
+    input.keys_pressed.insert(KeyCode::ArrowDown);
+    t.handle_input(&input, Some((7, 3)));
+    assert_eq!(t.cursor, (7, 4));
+}
+
+#[test]
+fn mouse_click_confirms() {
+    let mut t = Targeting::new((2, 2), 10, 10).with_cursor((5, 5));
+    let mut input = InputState::new();
+    input.mouse_pressed.insert(MouseButton::Left);
+    assert_eq!(t.handle_input(&input, None), TargetResult::Selected((5, 5)));
+}
+
+#[test]
+fn path_splits_at_first_blocking_tile() {
+    let t = Targeting::new((0, 0), 10, 10).with_cursor((6, 0));
+    let path = t.path(|x, _| x == 3);
+    assert_eq!(path.reached, vec![(1, 0), (2, 0), (3, 0)]);
+    assert_eq!(path.blocked, vec![(4, 0), (5, 0), (6, 0)]);
+    assert_eq!(path.impact(), Some((3, 0)));
+}
+
+#[test]
+fn path_stops_at_range_limit() {
+    let t = Targeting::new((0, 0), 10, 10).with_range(2).with_cursor((4, 0));
+    let path = t.path(|_, _| false);
+    assert_eq!(path.reached, vec![(1, 0), (2, 0)]);
+    assert_eq!(path.blocked, vec![(3, 0), (4, 0)]);
+}
+
+#[test]
+fn area_outline_is_centered_on_impact() {
+    let t = Targeting::new((0, 5), 20, 20).with_radius(2).with_cursor((10, 5));
+    let outline = t.area_outline(|x, _| x == 6);
+    assert!(outline.contains(&(8, 5)));
+    assert!(outline.contains(&(4, 5)));
+    assert!(outline.contains(&(6, 3)));
+    assert!(!outline.contains(&(6, 5)));
+
+    let single = Targeting::new((0, 0), 10, 10).with_cursor((3, 3));
+    assert!(single.area_outline(|_, _| false).is_empty());
+}